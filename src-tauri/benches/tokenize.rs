@@ -0,0 +1,118 @@
+// Time to tokenize a multi-megabyte file with the lexers, run with
+//
+//   cargo bench --bench tokenize
+//
+// Sources of this repository are repeated until the file is FILE_SIZE bytes.
+// The color lookup of the Theme is compared with the one tokenizers did before it,
+// cloning the settings' languages for every token and searching them.
+
+use std::time::{Duration, Instant};
+
+use app_lib::commands::cmd_utils::tokenize_lines;
+use app_lib::settings::{LanguageSyntaxHighlight, Settings};
+use app_lib::syntax_highlight::theme::Theme;
+use app_lib::syntax_highlight::{Token, TokenType, create_token};
+
+const FILE_SIZE: usize = 4 << 20;
+const RUNS: u32 = 5;
+// the old lookup takes seconds for a whole file, it is timed on the first tokens only
+const OLD_LOOKUP_TOKENS: usize = 20_000;
+
+fn repeat_lines(source: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut size = 0;
+
+    while size < FILE_SIZE {
+        for line in source.lines() {
+            size += line.len() + 1;
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+// color of a token the way create_token looked it up before the Theme
+fn old_token_color(settings_config: &Settings, language: &str, token_type: TokenType) -> String {
+    let languages: Vec<LanguageSyntaxHighlight> = settings_config.syntax_highlight.languages.clone();
+    let language_syntax = languages.iter()
+        .find(|lang| lang.language == language)
+        .or_else(|| languages.iter().find(|lang| lang.language == "python"))
+        .unwrap();
+
+    let color = match token_type {
+        TokenType::Keyword => &language_syntax.keyword_color,
+        TokenType::Identifier => &language_syntax.identifier_color,
+        TokenType::Number => &language_syntax.number_color,
+        TokenType::Operator => &language_syntax.operator_color,
+        TokenType::Punctuation => &language_syntax.punctuation_color,
+        TokenType::StringLiteral => &language_syntax.string_literal_color,
+        TokenType::Whitespace => &language_syntax.whitespace_color,
+        TokenType::Comment => &language_syntax.comment_color,
+        TokenType::ClassName => &language_syntax.class_name_color,
+        TokenType::FunctionName => &language_syntax.function_name_color,
+        TokenType::Preprocessor => &language_syntax.preprocessor_color,
+        TokenType::Lifetime => &language_syntax.lifetime_color,
+        TokenType::Builtin => &language_syntax.builtin_color,
+        _ => &language_syntax.unknown_color,
+    };
+    color.to_string()
+}
+
+// nanoseconds per token to look up the colors of `tokens` with the Theme and the old way
+fn compare_lookups(settings_config: &Settings, theme: &Theme, language: &str, tokens: &[&Token]) -> (f64, f64) {
+    let per_token = |elapsed: Duration| elapsed.as_nanos() as f64 / tokens.len() as f64;
+
+    let start = Instant::now();
+    let theme_tokens: Vec<Token> = tokens.iter()
+        .map(|token| create_token(theme, language, token.token_type, &token.value))
+        .collect();
+    let theme_lookup = per_token(start.elapsed());
+
+    let start = Instant::now();
+    let old_colors: Vec<(String, String)> = tokens.iter()
+        .map(|token| (token.value.clone(), old_token_color(settings_config, language, token.token_type)))
+        .collect();
+    let old_lookup = per_token(start.elapsed());
+
+    assert_eq!(theme_tokens.len(), old_colors.len());
+    (theme_lookup, old_lookup)
+}
+
+fn main() {
+    let settings_config = Settings::init_default_values();
+
+    let start = Instant::now();
+    let theme = Theme::new(&settings_config);
+    println!("theme: built in {:?}", start.elapsed());
+
+    let files = [
+        ("rust", include_str!("../src/commands/cmd_utils.rs")),
+        ("javascript", include_str!("../../vite.config.js")),
+        ("typescript", include_str!("../../src/helpers/editor/helper.ts")),
+    ];
+    for (language, source) in files {
+        let lines = repeat_lines(source);
+        let mut fastest = Duration::MAX;
+
+        let mut line_contents = Vec::new();
+
+        for _ in 0..RUNS {
+            let start = Instant::now();
+            line_contents = tokenize_lines(language, &theme, &lines);
+            fastest = fastest.min(start.elapsed());
+            assert_eq!(line_contents.len(), lines.len());
+        }
+        let megabytes_per_second = FILE_SIZE as f64 / (1 << 20) as f64 / fastest.as_secs_f64();
+        println!("{language}: {} lines in {:?}, {megabytes_per_second:.1} MB/s", lines.len(), fastest);
+
+        let tokens: Vec<&Token> = line_contents.iter()
+            .flat_map(|line_content| &line_content.tokens)
+            .take(OLD_LOOKUP_TOKENS)
+            .collect();
+        let (theme_lookup, old_lookup) = compare_lookups(&settings_config, &theme, language, &tokens);
+        println!(
+            "{language}: color lookup {theme_lookup:.0} ns/token, {old_lookup:.0} ns/token with the settings cloned per token, {:.0}x faster",
+            old_lookup / theme_lookup
+        );
+    }
+}
@@ -1,29 +1,98 @@
 use std::sync::Arc;
-use std::path::Path;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use tauri::{AppHandle, Emitter};
 use rayon::prelude::*;
 
-use crate::syntax_highlight::{Token, TokenType};
+use crate::syntax_highlight::{Token, TokenType, LineContent, LineState};
 use crate::syntax_highlight::languages::*;
 use crate::{AppStateFileContentCaches};
 use crate::settings::Settings;
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::sub_tokens::split_sub_tokens;
+
+// number of lines each rayon task tokenizes in one go
+const TOKENIZE_CHUNK_SIZE: usize = 256;
 
 pub fn tokenize(
     language: &str, 
-    settings_config: &Settings, 
-    content: &str
-) -> Vec<Token> {
-
-    // use python by default
-    let tokens = match language {
-        "rust" => tokenize_rust_code(settings_config, content),
-        "javascript" => tokenize_javascript_code(settings_config, content),
-        "typescript" => tokenize_typescript_code(settings_config, content),
-        _ => tokenize_python_code(settings_config, content),
-    };
-    tokens
+    theme: &Theme, 
+    content: &str, 
+    state: &LineState
+) -> LineContent {
+
+    // unknown languages fall back to plain text
+    tokenize_with(get_language(language).as_ref(), theme, content, state)
+}
+
+fn tokenize_with(
+    language: &dyn Language, 
+    theme: &Theme, 
+    content: &str, 
+    state: &LineState
+) -> LineContent {
+    let (tokens, end_state) = language.tokenize_line(theme, content, state);
+    let tokens = split_sub_tokens(theme, language.name(), tokens, state);
+    LineContent { tokens, state: end_state }
+}
+
+fn tokenize_chunk(
+    language: &dyn Language, 
+    theme: &Theme, 
+    lines: &[String], 
+    start_state: &LineState
+) -> Vec<LineContent> {
+    let mut state = start_state.clone();
+
+    lines.iter()
+        .map(|content| {
+            let line_content = tokenize_with(language, theme, content, &state);
+            state = line_content.state.clone();
+            line_content
+        })
+        .collect()
+}
+
+// Tokenize all lines of a file.
+// Chunks are tokenized in parallel assuming each one starts in the normal state,
+// then the chunk boundaries are walked in order and lines are re-tokenized with the
+// real incoming state until it matches the state the parallel pass started from.
+pub fn tokenize_lines(
+    language: &str, 
+    theme: &Theme, 
+    lines: &[String]
+) -> Vec<LineContent> {
+    let language = get_language(language);
+    let language = language.as_ref();
+
+    let mut chunks: Vec<Vec<LineContent>> = lines.par_chunks(TOKENIZE_CHUNK_SIZE)
+        .map(|chunk| tokenize_chunk(language, theme, chunk, &LineState::Normal))
+        .collect();
+
+    let mut state = LineState::Normal;
+
+    for (chunk, chunk_lines) in chunks.iter_mut().zip(lines.chunks(TOKENIZE_CHUNK_SIZE)) {
+        let mut assumed_state = LineState::Normal;
+
+        for (line_content, content) in chunk.iter_mut().zip(chunk_lines) {
+            if state == assumed_state {
+                break; // re-synchronized, the rest of the chunk is already correct
+            }
+            assumed_state = line_content.state.clone();
+            *line_content = tokenize_with(language, theme, content, &state);
+            state = line_content.state.clone();
+        }
+        if let Some(last_line) = chunk.last() {
+            state = last_line.state.clone();
+        }
+    }
+    chunks.into_iter().flatten().collect()
+}
+
+// `<div>` in source code has to show up as text, not become markup
+pub fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
 }
 
 pub fn line_processing(tokens: &Vec<Token>) -> String {
@@ -33,8 +102,14 @@ pub fn line_processing(tokens: &Vec<Token>) -> String {
     let process_result: String = tokens
         .par_iter()
         .map(|token| {
-            let processed_value = token.value.replace(" ", "&nbsp;").replace("\t", "&nbsp;&nbsp;&nbsp;&nbsp;");
-            format!("<span style=\"color: {}\">{}</span>", token.color, processed_value)
+            let processed_value = escape_html(&token.value).replace(" ", "&nbsp;").replace("\t", "&nbsp;&nbsp;&nbsp;&nbsp;");
+            // colors and styles are validated when settings and themes load, escaped
+            // anyway so one that slips through can't end the attribute
+            let color = escape_html(&token.color);
+            match token.style {
+                Some(ref style) => format!("<span style=\"color: {}; {}\">{}</span>", color, escape_html(style), processed_value),
+                None => format!("<span style=\"color: {}\">{}</span>", color, processed_value),
+            }
         })
         .collect();
 
@@ -46,6 +121,7 @@ pub fn insert_file_content_into_cache(
     line_contents_string: &Vec<String>,
     line_contents_dom: &Vec<String>,
     language: &String,
+    line_contents: Vec<LineContent>,
     file_path: &str, 
 ) {
     file_content_caches_state.insert(
@@ -53,7 +129,8 @@ pub fn insert_file_content_into_cache(
         (
             Arc::new(line_contents_string.clone()),
             Arc::new(line_contents_dom.clone()), 
-            Arc::new(language.clone())
+            Arc::new(language.clone()),
+            Arc::new(line_contents)
         )
     );
 }
@@ -63,7 +140,7 @@ pub fn read_file_content_from_cache(
     file_path: &str
 ) -> Option<(Arc<Vec<String>>, Arc<Vec<String>>, Arc<String>)> {
 
-    if let Some((line_contents, content_dom, language)) = file_content_caches_state.get(file_path) {
+    if let Some((line_contents, content_dom, language, _)) = file_content_caches_state.get(file_path) {
         return Some((
             Arc::clone(line_contents),
             Arc::clone(content_dom),
@@ -73,6 +150,55 @@ pub fn read_file_content_from_cache(
     None
 }
 
+// same normalization read_file_content and save_file apply to the file content
+pub fn process_line_content(content: &str) -> String {
+    content.replace("\u{a0}", " ").replace("\t", "    ")
+}
+
+// Replace lines `start_line..end_line` with `new_lines` and re-tokenize them,
+// plus every following line whose incoming lexer state changed because of the edit.
+// Returns the number of lines from start_line that got re-tokenized.
+pub fn retokenize_lines(
+    language: &str, 
+    theme: &Theme, 
+    line_contents_string: &mut Vec<String>, 
+    line_contents: &mut Vec<LineContent>, 
+    start_line: usize, 
+    end_line: usize, 
+    new_lines: Vec<String>
+) -> usize {
+    let language = get_language(language);
+    let language = language.as_ref();
+
+    let mut state = match start_line {
+        0 => LineState::Normal,
+        _ => line_contents[start_line - 1].state.clone(),
+    };
+    // state the first line after the edited range started with before the edit
+    let mut old_state = match end_line {
+        0 => LineState::Normal,
+        _ => line_contents[end_line - 1].state.clone(),
+    };
+
+    let mut new_line_contents: Vec<LineContent> = Vec::with_capacity(new_lines.len());
+    for content in &new_lines {
+        let line_content = tokenize_with(language, theme, content, &state);
+        state = line_content.state.clone();
+        new_line_contents.push(line_content);
+    }
+    let mut line_index = start_line + new_lines.len();
+    line_contents.splice(start_line..end_line, new_line_contents);
+    line_contents_string.splice(start_line..end_line, new_lines);
+
+    while line_index < line_contents.len() && state != old_state {
+        old_state = line_contents[line_index].state.clone();
+        line_contents[line_index] = tokenize_with(language, theme, &line_contents_string[line_index], &state);
+        state = line_contents[line_index].state.clone();
+        line_index += 1;
+    }
+    line_index - start_line
+}
+
 pub fn detect_bg_image_path_change(
     app: &AppHandle, 
     old_settings: &Settings, 
@@ -112,19 +238,19 @@ pub fn detect_bg_type_change(
     }
 }
 
-pub fn detect_language(file_path: &str) -> String {
-    let path = Path::new(file_path);
-    let language = path.extension().and_then(|ext| ext.to_str())
-        .map(|ext| match ext {
-            "js" => "javascript",
-            "rs" => "rust",
-            "ts" => "typescript",
-            _ => "python",
-        })
-        .unwrap_or("python")
-        .to_string();
-
-    language
+// `lines` are the file's content, its first and last lines may name the language.
+// The user's file associations win over everything else.
+pub fn detect_language(
+    file_path: &str, 
+    lines: &[String], 
+    file_associations: &HashMap<String, String>
+) -> String {
+    let registry = language_registry().read().unwrap();
+
+    registry.associated(file_path, file_associations)
+        .or_else(|| registry.detect(file_path, lines))
+        .map(|language| language.name().to_string())
+        .unwrap_or(DEFAULT_LANGUAGE.to_string())
 }
 
 pub fn get_lines_from_file(file_path: &str) -> Vec<String> {
@@ -141,19 +267,61 @@ mod tests {
     use std::collections::HashMap;
     use std::sync::Arc;
 
-    use crate::settings::Settings;
-    use crate::syntax_highlight::{Token, TokenType};
-
-    type AppStateFileContentCaches = HashMap<String, (Arc<Vec<String>>, Arc<Vec<String>>, Arc<String>)>;
+    use crate::settings::{Settings, TokenStyle};
+    use crate::syntax_highlight::{Token, TokenType, LineState};
+    use crate::syntax_highlight::theme::Theme;
 
     #[test]
     fn test_tokenize() {
         let language = "javascript";
-        let settings_config = Settings::init_default_values();
+        let theme = Theme::new(&Settings::init_default_values());
         let content = "Hello world";
 
-        let tokens = tokenize(language, &settings_config, content);
-        assert_eq!(tokens.len(), 3);
+        let line_content = tokenize(language, &theme, content, &LineState::Normal);
+        assert_eq!(line_content.tokens.len(), 3);
+        assert_eq!(line_content.state, LineState::Normal);
+    }
+
+    #[test]
+    fn test_tokenize_carries_block_comment_state() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let first = tokenize("javascript", &theme, "let a = 1; /* start", &LineState::Normal);
+        assert_eq!(first.state, LineState::InBlockComment { depth: 1 });
+
+        let second = tokenize("javascript", &theme, "still comment */ let b", &first.state);
+        assert_eq!(second.tokens[0].token_type, TokenType::Comment);
+        assert_eq!(second.tokens[0].value, "still comment */");
+        assert_eq!(second.state, LineState::Normal);
+    }
+
+    #[test]
+    fn test_tokenize_lines_resyncs_across_chunks() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        // the template literal opens right before a chunk boundary and closes after it
+        let mut lines: Vec<String> = vec!["let x = 1;".to_string(); TOKENIZE_CHUNK_SIZE - 1];
+        lines.push("let s = `first".to_string());
+        lines.push("second".to_string());
+        lines.push("third`;".to_string());
+        lines.push("let y = 2;".to_string());
+
+        let line_contents = tokenize_lines("javascript", &theme, &lines);
+        assert_eq!(line_contents.len(), lines.len());
+
+        let second = &line_contents[TOKENIZE_CHUNK_SIZE];
+        assert_eq!(second.tokens.len(), 1);
+        assert_eq!(second.tokens[0].token_type, TokenType::StringLiteral);
+
+        let third = &line_contents[TOKENIZE_CHUNK_SIZE + 1];
+        assert_eq!(third.tokens[0].value, "third`");
+        assert_eq!(third.state, LineState::Normal);
+
+        // sequential tokenizing must give the same result
+        assert_eq!(
+            line_contents, 
+            tokenize_chunk(&JavaScriptLanguage, &theme, &lines, &LineState::Normal)
+        );
     }
 
     #[test]
@@ -166,9 +334,9 @@ mod tests {
     #[test]
     fn test_line_processing_not_empty() {
         let language = "javascript";
-        let settings_config = Settings::init_default_values();
+        let theme = Theme::new(&Settings::init_default_values());
         let content = "dummy dummy";
-        let tokens = tokenize(language, &settings_config, content);
+        let tokens = tokenize(language, &theme, content, &LineState::Normal).tokens;
 
         // #26ffd7 default color for identifier
         let result = line_processing(&tokens);
@@ -178,6 +346,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_line_processing_token_styles() {
+        let mut settings_config = Settings::init_default_values();
+        for language_syntax in settings_config.syntax_highlight.languages.iter_mut() {
+            language_syntax.token_styles.insert(TokenType::Keyword, TokenStyle { bold: true, ..TokenStyle::default() });
+            language_syntax.token_styles.insert(TokenType::Comment, TokenStyle { italic: true, underline: true, ..TokenStyle::default() });
+        }
+        let theme = Theme::new(&settings_config);
+        let tokens = tokenize("python", &theme, "pass  # ok", &LineState::Normal).tokens;
+
+        let result = line_processing(&tokens);
+        assert_eq!(
+            result,
+            "<span style=\"color: #bd00ff; font-weight: bold\">pass</span><span style=\"color: white\">&nbsp;&nbsp;</span><span style=\"color: grey; font-style: italic; text-decoration: underline\">#&nbsp;ok</span>"
+        );
+    }
+
+    #[test]
+    fn test_line_processing_escapes_html() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let tokens = tokenize("html", &theme, "<b>&</b>", &LineState::Normal).tokens;
+
+        let result = line_processing(&tokens);
+        assert!(!result.contains("<b>"));
+        assert!(result.contains("&lt;"));
+        assert!(result.contains("&amp;"));
+        assert_eq!(escape_html("a < b && c > \"d\""), "a &lt; b &amp;&amp; c &gt; &quot;d&quot;");
+
+        // colors and styles stay inside the style attribute
+        let token = Token {
+            token_type: TokenType::Keyword,
+            value: "if".to_string(),
+            color: Arc::from("red\"><img src=x onerror=alert(1)>"),
+            style: Some(Arc::from("font-weight: bold\" onclick=\"x")),
+        };
+        let result = line_processing(&vec![token]);
+        assert!(!result.contains("<img"));
+        assert!(!result.contains("\" onclick"));
+        assert_eq!(result.matches('"').count(), 2);
+    }
+
     #[test]
     fn test_insert_file_content_into_cache() {
         let mut file_content_caches_state: AppStateFileContentCaches = HashMap::new();
@@ -189,12 +398,14 @@ mod tests {
         ];
         let language = "javascript".to_string();
         let file_path = "path/to/file.js";
+        let line_contents = tokenize_lines(&language, &Theme::new(&Settings::init_default_values()), &line_contents_string);
 
         insert_file_content_into_cache(
             &mut file_content_caches_state,
             &line_contents_string,
             &line_contents_dom,
             &language,
+            line_contents.clone(),
             file_path,
         );
         let cached_data = file_content_caches_state.get(file_path).expect("File path not found in cache");
@@ -202,6 +413,7 @@ mod tests {
         assert_eq!(*cached_data.0, **Arc::new(line_contents_string));
         assert_eq!(*cached_data.1, **Arc::new(line_contents_dom));
         assert_eq!(*cached_data.2, **Arc::new(language));
+        assert_eq!(*cached_data.3, line_contents);
     }
 
     #[test]
@@ -215,12 +427,14 @@ mod tests {
         ];
         let language = "javascript".to_string();
         let file_path = "path/to/file.js";
+        let line_contents = tokenize_lines(&language, &Theme::new(&Settings::init_default_values()), &line_contents_string);
 
         insert_file_content_into_cache(
             &mut file_content_caches_state,
             &line_contents_string,
             &line_contents_dom,
             &language,
+            line_contents.clone(),
             file_path,
         );
 
@@ -250,16 +464,152 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_retokenize_lines_within_line() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let mut lines: Vec<String> = vec!["let a = 1;".to_string(), "let b = 2;".to_string(), "let c = 3;".to_string()];
+        let mut line_contents = tokenize_lines("javascript", &theme, &lines);
+
+        let changed = retokenize_lines(
+            "javascript", &theme, &mut lines, &mut line_contents, 
+            1, 2, vec!["let b = foo(2);".to_string()]
+        );
+        // lexer state did not change so only the edited line gets re-tokenized
+        assert_eq!(changed, 1);
+        assert_eq!(lines[1], "let b = foo(2);");
+        assert_eq!(line_contents, tokenize_lines("javascript", &theme, &lines));
+    }
+
+    #[test]
+    fn test_retokenize_lines_propagates_state() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let mut lines: Vec<String> = vec![
+            "let a = 1;".to_string(), 
+            "let b = 2;".to_string(), 
+            "let c = 3; */".to_string(), 
+            "let d = 4;".to_string()
+        ];
+        let mut line_contents = tokenize_lines("javascript", &theme, &lines);
+
+        // opening a block comment changes the highlighting until the closing */
+        let changed = retokenize_lines(
+            "javascript", &theme, &mut lines, &mut line_contents, 
+            0, 1, vec!["/* let a = 1;".to_string()]
+        );
+        // the line after the closing */ starts in the same state as before and is left alone
+        assert_eq!(changed, 3);
+        assert_eq!(line_contents[1].tokens[0].token_type, TokenType::Comment);
+        assert_eq!(line_contents[3].tokens[0].token_type, TokenType::Keyword);
+        assert_eq!(line_contents, tokenize_lines("javascript", &theme, &lines));
+
+        // inserting and removing lines
+        let changed = retokenize_lines(
+            "javascript", &theme, &mut lines, &mut line_contents, 
+            0, 3, vec!["let x = `".to_string(), "`;".to_string()]
+        );
+        assert_eq!(changed, 2);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(line_contents, tokenize_lines("javascript", &theme, &lines));
+    }
+
     #[test]
     fn test_detect_language() {
         let file_path_1 = "/path/to/v8engine.rs";
         let file_path_2 = "/path/to/v8engine.js";
         let file_path_3 = "/path/to/v8engine.jesuschrist";
-        let result_1 = detect_language(file_path_1);
-        let result_2 = detect_language(file_path_2);
-        let result_3 = detect_language(file_path_3);
+        let file_path_4 = "/path/to/v8engine.go";
+        let file_path_5 = "/path/to/v8engine.h";
+        let file_path_6 = "/path/to/v8engine.cc";
+        let file_path_7 = "/home/user/BaCE/settings.json";
+        let file_path_8 = "/path/to/Cargo.lock";
+        let file_path_9 = "/path/to/README.md";
+        let file_path_10 = "/path/to/App.vue";
+        let file_path_11 = "/path/to/index.html";
+        let file_path_12 = "/path/to/style.css";
+        let file_path_13 = "/path/to/Makefile";
+        let file_path_14 = "/path/to/Dockerfile.dev";
+        let file_path_15 = "/home/user/.bashrc";
+        let result_1 = detect_language(file_path_1, &[], &HashMap::new());
+        let result_2 = detect_language(file_path_2, &[], &HashMap::new());
+        let result_3 = detect_language(file_path_3, &[], &HashMap::new());
+        let result_4 = detect_language(file_path_4, &[], &HashMap::new());
+        let result_5 = detect_language(file_path_5, &[], &HashMap::new());
+        let result_6 = detect_language(file_path_6, &[], &HashMap::new());
+        let result_7 = detect_language(file_path_7, &[], &HashMap::new());
+        let result_8 = detect_language(file_path_8, &[], &HashMap::new());
+        let result_9 = detect_language(file_path_9, &[], &HashMap::new());
+        let result_10 = detect_language(file_path_10, &[], &HashMap::new());
+        let result_11 = detect_language(file_path_11, &[], &HashMap::new());
+        let result_12 = detect_language(file_path_12, &[], &HashMap::new());
+        let result_13 = detect_language(file_path_13, &[], &HashMap::new());
+        let result_14 = detect_language(file_path_14, &[], &HashMap::new());
+        let result_15 = detect_language(file_path_15, &[], &HashMap::new());
         assert_eq!(result_1, "rust");
         assert_eq!(result_2, "javascript");
-        assert_eq!(result_3, "python");
+        assert_eq!(result_3, "plaintext");
+        assert_eq!(result_4, "go");
+        assert_eq!(result_5, "c");
+        assert_eq!(result_6, "cpp");
+        assert_eq!(result_7, "json");
+        assert_eq!(result_8, "toml");
+        assert_eq!(result_9, "markdown");
+        assert_eq!(result_10, "vue");
+        assert_eq!(result_11, "html");
+        assert_eq!(result_12, "css");
+        assert_eq!(result_13, "makefile");
+        assert_eq!(result_14, "dockerfile");
+        assert_eq!(result_15, "shell");
+        assert_eq!(detect_language("/path/to/App.tsx", &[], &HashMap::new()), "tsx");
+        assert_eq!(detect_language("/path/to/App.jsx", &[], &HashMap::new()), "javascript");
+        assert_eq!(detect_language("/path/to/config.mts", &[], &HashMap::new()), "typescript");
+    }
+
+    #[test]
+    fn test_detect_language_from_content() {
+        let lines = |content: &[&str]| -> Vec<String> { content.iter().map(|line| line.to_string()).collect() };
+
+        assert_eq!(detect_language("/usr/local/bin/deploy", &lines(&["#!/bin/bash", "set -e"]), &HashMap::new()), "shell");
+        assert_eq!(detect_language("/path/to/script", &lines(&["#!/usr/bin/env -S python3.12 -u"]), &HashMap::new()), "python");
+        assert_eq!(detect_language("/path/to/server", &lines(&["#!/usr/bin/env node"]), &HashMap::new()), "javascript");
+        // the shebang wins over the extension, the file name over the shebang
+        assert_eq!(detect_language("/path/to/tool.txt", &lines(&["#!/bin/sh"]), &HashMap::new()), "shell");
+        assert_eq!(detect_language("/path/to/Makefile", &lines(&["#!/bin/sh"]), &HashMap::new()), "makefile");
+        // modelines at the start or the end of the file
+        assert_eq!(detect_language("/path/to/build", &lines(&["// vim: set ft=rust:", "fn main() {}"]), &HashMap::new()), "rust");
+        assert_eq!(detect_language("/path/to/notes", &lines(&["# -*- mode: markdown; coding: utf-8 -*-"]), &HashMap::new()), "markdown");
+        assert_eq!(detect_language("/path/to/vector.h", &lines(&["// -*- c++ -*-"]), &HashMap::new()), "cpp");
+        assert_eq!(detect_language("/path/to/vector.h", &lines(&["/* vim: set ft=c++: */"]), &HashMap::new()), "cpp");
+        let mut long_file = vec!["x".to_string(); 20];
+        long_file.push("# vi: filetype=sh".to_string());
+        assert_eq!(detect_language("/path/to/env", &long_file, &HashMap::new()), "shell");
+        // a modeline in the middle of a file doesn't count
+        long_file.insert(10, "# vim: ft=yaml".to_string());
+        long_file.pop();
+        assert_eq!(detect_language("/path/to/env", &long_file, &HashMap::new()), "plaintext");
+        assert_eq!(detect_language("/path/to/.gitignore", &[], &HashMap::new()), "plaintext");
     }
+
+    #[test]
+    fn test_detect_language_from_file_associations() {
+        let file_associations: HashMap<String, String> = HashMap::from([
+            ("jsm".to_string(), "javascript".to_string()),
+            (".pyi".to_string(), "python".to_string()),
+            ("*.mts".to_string(), "typescript".to_string()),
+            ("Jenkinsfile".to_string(), "shell".to_string()),
+            ("*.conf".to_string(), "plaintext".to_string()),
+            ("nginx/*.conf".to_string(), "yaml".to_string()),
+            ("*.rs".to_string(), "no-such-language".to_string()),
+        ]);
+
+        assert_eq!(detect_language("/path/to/module.jsm", &[], &file_associations), "javascript");
+        assert_eq!(detect_language("/path/to/stubs.pyi", &[], &file_associations), "python");
+        assert_eq!(detect_language("/path/to/index.mts", &[], &file_associations), "typescript");
+        assert_eq!(detect_language("/path/to/Jenkinsfile", &[], &file_associations), "shell");
+        // associations win over shebangs, the longest matching pattern wins
+        assert_eq!(detect_language("/etc/app.conf", &["#!/bin/sh".to_string()], &file_associations), "plaintext");
+        assert_eq!(detect_language("/etc/nginx/site.conf", &[], &file_associations), "yaml");
+        // entries naming unknown languages are ignored
+        assert_eq!(detect_language("/path/to/main.rs", &[], &file_associations), "rust");
+    }
+
 }
@@ -1,8 +1,9 @@
 pub mod cmd_utils;
 
+use std::collections::BTreeMap;
 use std::fs::{File, create_dir};
 use tauri::State;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io::{self, BufRead, BufReader, Write};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
@@ -10,10 +11,18 @@ use std::sync::{Arc};
 use rayon::prelude::*;
 
 use crate::settings::*;
+use crate::presets::{THEME_PRESETS, find_preset};
 use crate::folder;
 use crate::{AppState, AppStateStartUpErrors, AppStateSettingConfig};
 use crate::file_handler;
-use crate::syntax_highlight::LineContent;
+use crate::syntax_highlight::{LineContent, LineState, TokenType, TokenPalette, TokenSpan, token_spans};
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::theme_file;
+use crate::syntax_highlight::brackets::{self, Bracket, BracketDepth, BracketPairs, BracketPosition};
+use crate::syntax_highlight::folding::{self, FoldRange};
+use crate::syntax_highlight::outline::{self, Symbol};
+use crate::syntax_highlight::syntax_tree::SyntaxTree;
+use crate::syntax_highlight::languages::language_registry;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ReadFileContentSuccessResult {
@@ -22,6 +31,35 @@ pub struct ReadFileContentSuccessResult {
     pub language: Arc<String>,
 }
 
+// tokens of each line as spans into line_contents_string, the frontend
+// renders them with the colors of palette instead of pre-rendered DOM.
+// line_bracket_depths give the rainbow bracket spans their depth
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ReadFileTokensSuccessResult {
+    pub line_contents_string: Arc<Vec<String>>,
+    pub line_tokens: Vec<Vec<TokenSpan>>,
+    pub line_bracket_depths: Vec<Vec<BracketDepth>>,
+    pub palette: TokenPalette,
+    pub token_styles: BTreeMap<TokenType, TokenStyle>,
+    pub language: Arc<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ReadTokenPaletteResult {
+    pub palette: TokenPalette,
+    pub token_styles: BTreeMap<TokenType, TokenStyle>,
+}
+
+// line_contents_dom, line_tokens and line_bracket_depths hold the new DOM, tokens
+// and bracket depths of the lines starting at start_line, every other line keeps its DOM
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RehighlightLinesResult {
+    pub start_line: usize,
+    pub line_contents_dom: Vec<String>,
+    pub line_tokens: Vec<Vec<TokenSpan>>,
+    pub line_bracket_depths: Vec<Vec<BracketDepth>>,
+}
+
 // first command to get invoked by frontend when app started
 #[tauri::command]
 pub fn check_app_startup_errs(state: State<AppState>) -> AppStateStartUpErrors {
@@ -58,32 +96,21 @@ pub async fn read_file_content(
             language: Arc::clone(&language),
         });
     }
-    let language: String = cmd_utils::detect_language(file_path);
-    let lines: Vec<String> = cmd_utils::get_lines_from_file(file_path);
+    let theme = Arc::clone(state.theme.lock().unwrap().as_ref().unwrap());
 
-    // clone from mutexguard and get underlying value of Option<Settings>
-    let settings_config = state.settings_config.lock().unwrap().clone().unwrap();
+    let lines: Vec<String> = cmd_utils::get_lines_from_file(file_path);
+    let language: String = detect_file_language(&state, file_path, &lines);
 
-    let line_contents: Vec<(LineContent, String)> = lines.into_par_iter()
-        .map(|content| {
-            let processed_content = content.replace("\u{a0}", " ").replace("\t", "    ");
+    let line_contents_string: Vec<String> = lines.into_par_iter()
+        .map(|content| cmd_utils::process_line_content(&content))
+        .collect();
 
-            let mut line_content = LineContent { tokens: Vec::new() }; 
-            line_content.tokens = cmd_utils::tokenize(language.as_str(), &settings_config, &processed_content);
+    let line_contents = tokenize_file(&state, &theme, file_path, &language, &line_contents_string);
 
-            (line_content, processed_content)
-        })
+    let line_contents_dom: Vec<String> = line_contents.par_iter()
+        .map(|line_content| cmd_utils::line_processing(&line_content.tokens))
         .collect();
 
-    let (line_contents_string, line_contents_dom): (Vec<_>, Vec<_>) = line_contents.par_iter()
-        .map(|(line_content, processed_content)| {
-            (
-                processed_content.clone(),
-                cmd_utils::line_processing(&line_content.tokens),
-            )
-        })
-        .unzip();
-
     let result = ReadFileContentSuccessResult {
         line_contents_string: Arc::new(line_contents_string.clone()),
         line_contents_dom: Arc::new(line_contents_dom.clone()),
@@ -99,6 +126,7 @@ pub async fn read_file_content(
             &line_contents_string,
             &line_contents_dom,
             &language,
+            line_contents,
             &file_path
         );
     });
@@ -106,6 +134,115 @@ pub async fn read_file_content(
     Ok(result)
 }
 
+fn detect_file_language(state: &AppState, file_path: &str, lines: &[String]) -> String {
+    let settings_config = state.settings_config.lock().unwrap();
+    cmd_utils::detect_language(file_path, lines, &settings_config.as_ref().unwrap().file_associations)
+}
+
+// A tree-sitter grammar (tree-sitter feature) takes over from the lexer when there is one.
+// lexer state has to flow from line to line, tokenize_lines keeps that while
+// still tokenizing chunks of the file in parallel
+fn tokenize_file(
+    state: &AppState, 
+    theme: &Theme, 
+    file_path: &str, 
+    language: &str, 
+    lines: &[String]
+) -> Vec<LineContent> {
+    let syntax_tree = SyntaxTree::parse(language, lines);
+    let mut line_contents: Vec<LineContent> = match syntax_tree {
+        Some(ref syntax_tree) => syntax_tree.tokenize_lines(theme, 0..lines.len()),
+        None => cmd_utils::tokenize_lines(language, theme, lines),
+    };
+    let bracket_pairs = BracketPairs::analyze(&line_contents);
+    brackets::colorize_brackets(theme, language, &bracket_pairs, &mut line_contents);
+    if let Some(syntax_tree) = syntax_tree {
+        state.syntax_trees.lock().unwrap().insert(file_path.to_string(), syntax_tree);
+    }
+    state.bracket_pairs.lock().unwrap().insert(file_path.to_string(), bracket_pairs);
+    line_contents
+}
+
+// Same as read_file_content, but the lines come back as token spans plus the
+// palette of the file's language rather than as HTML
+#[tauri::command]
+pub async fn read_file_tokens(
+    app: AppHandle,
+    state: State<'_, AppState>, 
+    file_path: &str
+) -> Result<ReadFileTokensSuccessResult, ()> {
+
+    if !file_handler::is_readable_text_file(file_path) {
+        app.emit("internal_error", "File is not Editable").unwrap();
+        return Err(());
+    }
+    let theme = Arc::clone(state.theme.lock().unwrap().as_ref().unwrap());
+
+    let cached = state.file_content_caches.lock().unwrap()
+        .get(file_path)
+        .map(|(line_contents, _, language, tokenized_lines)| {
+            (Arc::clone(line_contents), Arc::clone(language), Arc::clone(tokenized_lines))
+        });
+
+    let (line_contents_string, language, line_contents) = match cached {
+        Some(cached) => cached,
+        None => {
+            let lines: Vec<String> = cmd_utils::get_lines_from_file(file_path);
+            let language: String = detect_file_language(&state, file_path, &lines);
+            let line_contents_string: Vec<String> = lines.into_par_iter()
+                .map(|content| cmd_utils::process_line_content(&content))
+                .collect();
+            let line_contents = tokenize_file(&state, &theme, file_path, &language, &line_contents_string);
+
+            // cached together with its DOM, so the other commands keep working on this file
+            let line_contents_dom: Vec<String> = line_contents.par_iter()
+                .map(|line_content| cmd_utils::line_processing(&line_content.tokens))
+                .collect();
+            let mut file_content_caches = state.file_content_caches.lock().unwrap();
+            cmd_utils::insert_file_content_into_cache(
+                &mut file_content_caches,
+                &line_contents_string,
+                &line_contents_dom,
+                &language,
+                line_contents,
+                file_path
+            );
+            let (line_contents_string, _, language, line_contents) = &file_content_caches[file_path];
+            (Arc::clone(line_contents_string), Arc::clone(language), Arc::clone(line_contents))
+        },
+    };
+    let line_bracket_depths = match state.bracket_pairs.lock().unwrap().get(file_path) {
+        Some(bracket_pairs) => brackets::bracket_depths(bracket_pairs, &line_contents, 0..line_contents.len()),
+        None => vec![Vec::new(); line_contents.len()],
+    };
+
+    Ok(ReadFileTokensSuccessResult {
+        line_contents_string,
+        line_tokens: line_contents.iter().map(|line_content| token_spans(&line_content.tokens)).collect(),
+        line_bracket_depths,
+        palette: theme.palette(&language),
+        token_styles: theme.token_styles(&language),
+        language,
+    })
+}
+
+// Colors and font styles of each token type for a language, refetched when the theme changes
+#[tauri::command]
+pub fn read_token_palette(
+    state: State<AppState>, 
+    language: &str
+) -> Result<ReadTokenPaletteResult, String> {
+    let theme = state.theme.lock().unwrap();
+
+    let Some(ref theme) = *theme else {
+        return Err("Settings are not loaded".to_string());
+    };
+    Ok(ReadTokenPaletteResult {
+        palette: theme.palette(language),
+        token_styles: theme.token_styles(language),
+    })
+}
+
 #[tauri::command]
 pub async fn update_file_content_cache(
     state: State<'_, AppState>,
@@ -113,9 +250,27 @@ pub async fn update_file_content_cache(
     line_contents_string: Vec<String>,
     line_contents_dom: Vec<String>
 ) -> Result<(), String> {
+    let theme = state.theme.lock().unwrap().clone();
     let mut file_content_caches = state.file_content_caches.lock().unwrap();
+    let mut syntax_trees = state.syntax_trees.lock().unwrap();
+    let mut bracket_pairs = state.bracket_pairs.lock().unwrap();
 
-    if let Some((line_contents, content_dom, _)) = file_content_caches.get_mut(file_path) {
+    if let Some((line_contents, content_dom, language, tokenized_lines)) = file_content_caches.get_mut(file_path) {
+        // keep cached tokens, lexer states and the syntax tree in sync with the new content.
+        // Without settings there is no theme, the default colors do until they load
+        let theme = theme.unwrap_or_else(|| Arc::new(Theme::new(&Settings::init_default_values())));
+        match SyntaxTree::parse(language, &line_contents_string) {
+            Some(syntax_tree) => {
+                *tokenized_lines = Arc::new(syntax_tree.tokenize_lines(&theme, 0..line_contents_string.len()));
+                syntax_trees.insert(file_path.to_string(), syntax_tree);
+            },
+            None => {
+                *tokenized_lines = Arc::new(cmd_utils::tokenize_lines(language, &theme, &line_contents_string));
+            },
+        }
+        let file_bracket_pairs = BracketPairs::analyze(tokenized_lines);
+        brackets::colorize_brackets(&theme, language, &file_bracket_pairs, Arc::make_mut(tokenized_lines).as_mut_slice());
+        bracket_pairs.insert(file_path.to_string(), file_bracket_pairs);
         *line_contents = Arc::new(line_contents_string.clone());
         *content_dom = Arc::new(line_contents_dom.clone());
     } else {
@@ -124,6 +279,266 @@ pub async fn update_file_content_cache(
     Ok(())
 }
 
+// Re-highlight an edited range of a cached file.
+// Lines start_line..end_line are replaced by new_lines, only those lines and the
+// following lines whose lexer state changed are re-tokenized and returned.
+// With a syntax tree the changed lines can also start before start_line.
+#[tauri::command]
+pub async fn rehighlight_lines(
+    state: State<'_, AppState>,
+    file_path: &str,
+    start_line: usize,
+    end_line: usize,
+    new_lines: Vec<String>
+) -> Result<RehighlightLinesResult, String> {
+    let theme = state.theme.lock().unwrap().clone();
+    let mut file_content_caches = state.file_content_caches.lock().unwrap();
+    let mut syntax_trees = state.syntax_trees.lock().unwrap();
+    let mut bracket_pairs = state.bracket_pairs.lock().unwrap();
+
+    let Some(ref theme) = theme else {
+        return Err("Settings are not loaded".to_string());
+    };
+    let Some((line_contents, content_dom, language, tokenized_lines)) = file_content_caches.get_mut(file_path) else {
+        return Err(format!("File '{}' not found in cache", file_path));
+    };
+    if start_line > end_line || end_line > line_contents.len() {
+        return Err(format!("Invalid line range {}..{} for file '{}'", start_line, end_line, file_path));
+    }
+
+    let new_lines: Vec<String> = new_lines.iter()
+        .map(|content| cmd_utils::process_line_content(content))
+        .collect();
+    let new_line_count = new_lines.len();
+
+    let changed_lines = match syntax_trees.get_mut(file_path) {
+        Some(syntax_tree) => {
+            let changed_lines = syntax_tree.edit(start_line, end_line, &new_lines);
+            Arc::make_mut(line_contents).splice(start_line..end_line, new_lines);
+
+            let tokenized_lines = Arc::make_mut(tokenized_lines);
+            let placeholders = (0..new_line_count).map(|_| LineContent { tokens: Vec::new(), state: LineState::Normal });
+            tokenized_lines.splice(start_line..end_line, placeholders);
+            let retokenized = syntax_tree.tokenize_lines(theme, changed_lines.clone());
+            tokenized_lines.splice(changed_lines.clone(), retokenized);
+            changed_lines
+        },
+        None => {
+            let changed_line_count = cmd_utils::retokenize_lines(
+                language, 
+                theme, 
+                Arc::make_mut(line_contents), 
+                Arc::make_mut(tokenized_lines), 
+                start_line, 
+                end_line, 
+                new_lines
+            );
+            start_line..start_line + changed_line_count
+        },
+    };
+    // an added or removed bracket changes the depth, and with rainbow brackets
+    // the color, of brackets on lines that weren't retokenized
+    let file_bracket_pairs = BracketPairs::analyze(tokenized_lines);
+    let changed_lines = match brackets::colorize_brackets(theme, language, &file_bracket_pairs, Arc::make_mut(tokenized_lines).as_mut_slice()) {
+        Some(recolored_lines) => {
+            changed_lines.start.min(recolored_lines.start)..changed_lines.end.max(recolored_lines.end)
+        },
+        None => changed_lines,
+    };
+    let line_bracket_depths = brackets::bracket_depths(&file_bracket_pairs, tokenized_lines, changed_lines.clone());
+    bracket_pairs.insert(file_path.to_string(), file_bracket_pairs);
+    let changed_lines_dom: Vec<String> = tokenized_lines[changed_lines.clone()]
+        .par_iter()
+        .map(|line_content| cmd_utils::line_processing(&line_content.tokens))
+        .collect();
+
+    let content_dom = Arc::make_mut(content_dom);
+    content_dom.splice(start_line..end_line, vec![String::new(); new_line_count]);
+    content_dom[changed_lines.clone()].clone_from_slice(&changed_lines_dom);
+
+    Ok(RehighlightLinesResult {
+        start_line: changed_lines.start,
+        line_contents_dom: changed_lines_dom,
+        line_tokens: tokenized_lines[changed_lines].iter()
+            .map(|line_content| token_spans(&line_content.tokens))
+            .collect(),
+        line_bracket_depths,
+    })
+}
+
+// Re-tokenize a cached file as `language`, chosen by the user for this file only.
+// Returns the DOM of every line.
+#[tauri::command]
+pub async fn set_file_language(
+    state: State<'_, AppState>,
+    file_path: &str,
+    language: &str
+) -> Result<Vec<String>, String> {
+    let theme = state.theme.lock().unwrap().clone();
+    let mut file_content_caches = state.file_content_caches.lock().unwrap();
+    let mut syntax_trees = state.syntax_trees.lock().unwrap();
+    let mut bracket_pairs = state.bracket_pairs.lock().unwrap();
+
+    let Some(ref theme) = theme else {
+        return Err("Settings are not loaded".to_string());
+    };
+    if language_registry().read().unwrap().get(language).is_none() {
+        return Err(format!("Unknown language '{}'", language));
+    }
+    let Some((line_contents, content_dom, cached_language, tokenized_lines)) = file_content_caches.get_mut(file_path) else {
+        return Err(format!("File '{}' not found in cache", file_path));
+    };
+
+    // the old language's syntax tree doesn't apply anymore
+    syntax_trees.remove(file_path);
+    match SyntaxTree::parse(language, line_contents) {
+        Some(syntax_tree) => {
+            *tokenized_lines = Arc::new(syntax_tree.tokenize_lines(theme, 0..line_contents.len()));
+            syntax_trees.insert(file_path.to_string(), syntax_tree);
+        },
+        None => {
+            *tokenized_lines = Arc::new(cmd_utils::tokenize_lines(language, theme, line_contents));
+        },
+    }
+    let file_bracket_pairs = BracketPairs::analyze(tokenized_lines);
+    brackets::colorize_brackets(theme, language, &file_bracket_pairs, Arc::make_mut(tokenized_lines).as_mut_slice());
+    bracket_pairs.insert(file_path.to_string(), file_bracket_pairs);
+    let line_contents_dom: Vec<String> = tokenized_lines.par_iter()
+        .map(|line_content| cmd_utils::line_processing(&line_content.tokens))
+        .collect();
+
+    *cached_language = Arc::new(language.to_string());
+    *content_dom = Arc::new(line_contents_dom.clone());
+    Ok(line_contents_dom)
+}
+
+// Tokenize every cached file again with `theme`, so open files get its colors
+fn rehighlight_file_content_caches(state: &AppState, theme: &Theme) {
+    let mut file_content_caches = state.file_content_caches.lock().unwrap();
+    let syntax_trees = state.syntax_trees.lock().unwrap();
+    let mut bracket_pairs = state.bracket_pairs.lock().unwrap();
+
+    for (file_path, (line_contents, content_dom, language, tokenized_lines)) in file_content_caches.iter_mut() {
+        let mut line_contents: Vec<LineContent> = match syntax_trees.get(file_path) {
+            Some(syntax_tree) => syntax_tree.tokenize_lines(theme, 0..line_contents.len()),
+            None => cmd_utils::tokenize_lines(language, theme, line_contents),
+        };
+        let file_bracket_pairs = BracketPairs::analyze(&line_contents);
+        brackets::colorize_brackets(theme, language, &file_bracket_pairs, &mut line_contents);
+        bracket_pairs.insert(file_path.to_string(), file_bracket_pairs);
+        let line_contents_dom: Vec<String> = line_contents.par_iter()
+            .map(|line_content| cmd_utils::line_processing(&line_content.tokens))
+            .collect();
+
+        *content_dom = Arc::new(line_contents_dom);
+        *tokenized_lines = Arc::new(line_contents);
+    }
+}
+
+// The bracket at line/column of a cached file, or else the next one on that line the way
+// Vim's `%` picks it, along with its partner and depth. Columns count UTF-16 code units.
+// None when the line has no bracket with a partner from there on.
+// The pairs are the ones analyzed when the file's tokens last changed
+#[tauri::command]
+pub fn find_matching_bracket(
+    state: State<AppState>, 
+    file_path: &str, 
+    line: usize, 
+    column: usize
+) -> Result<Option<Bracket>, String> {
+    let bracket_pairs = state.bracket_pairs.lock().unwrap();
+
+    let Some(bracket_pairs) = bracket_pairs.get(file_path) else {
+        return Err(format!("File '{}' not found in cache", file_path));
+    };
+    Ok(bracket_pairs.find_pair(BracketPosition { line, column }).copied())
+}
+
+// Foldable ranges of a cached file. They are computed from the cached tokens, so they
+// follow every update_file_content_cache and rehighlight_lines of the file
+#[tauri::command]
+pub fn read_fold_ranges(
+    state: State<AppState>, 
+    file_path: &str
+) -> Result<Vec<FoldRange>, String> {
+    let file_content_caches = state.file_content_caches.lock().unwrap();
+
+    let Some((_, _, language, tokenized_lines)) = file_content_caches.get(file_path) else {
+        return Err(format!("File '{}' not found in cache", file_path));
+    };
+    Ok(folding::fold_ranges(language, tokenized_lines))
+}
+
+// Outline of a cached file, its functions, types, impl blocks and top-level constants
+// with the symbols declared inside them as children
+#[tauri::command]
+pub fn read_outline(
+    state: State<AppState>, 
+    file_path: &str
+) -> Result<Vec<Symbol>, String> {
+    let file_content_caches = state.file_content_caches.lock().unwrap();
+
+    let Some((_, _, language, tokenized_lines)) = file_content_caches.get(file_path) else {
+        return Err(format!("File '{}' not found in cache", file_path));
+    };
+    Ok(outline::outline(language, tokenized_lines))
+}
+
+#[tauri::command]
+pub fn list_presets() -> Vec<String> {
+    THEME_PRESETS.iter().map(|preset| preset.name.to_string()).collect()
+}
+
+// Switch to a built-in color preset and save it to settings.json.
+// Cached files are highlighted with the new colors, returns the new settings
+#[tauri::command]
+pub fn switch_preset(
+    state: State<AppState>, 
+    name: &str
+) -> Result<Settings, String> {
+    let Some(preset) = find_preset(name) else {
+        return Err(format!("Unknown preset '{}'", name));
+    };
+    // the settings are unlocked before the caches are locked, read_file_content locks
+    // the settings while it holds the caches
+    let new_settings_config = {
+        let mut settings_state = state.settings_config.lock().unwrap();
+        let Some(ref mut settings) = *settings_state else {
+            return Err("Settings are not loaded".to_string());
+        };
+        let mut new_settings_config = settings.clone();
+        preset.apply(&mut new_settings_config);
+
+        // the background image is encoded from bg_image_path again whenever settings are read
+        let mut settings_file = new_settings_config.clone();
+        settings_file.app.bg_image_base64 = String::new();
+        update_settings_file(&settings_file).map_err(|e| format!("{e}"))?;
+
+        *settings = new_settings_config.clone();
+        new_settings_config
+    };
+
+    // files read while the caches are rehighlighted get the new colors too
+    let theme = Arc::new(Theme::new(&new_settings_config));
+    *state.theme.lock().unwrap() = Some(Arc::clone(&theme));
+    rehighlight_file_content_caches(&state, &theme);
+    Ok(new_settings_config)
+}
+
+// Names of the theme files in $HOME/BaCE/themes, any of them can be the `theme` setting
+#[tauri::command]
+pub fn list_themes() -> Result<Vec<String>, String> {
+    theme_file::list_themes(&theme_file::get_themes_dir_path()).map_err(|e| format!("{e}"))
+}
+
+// Convert a VS Code (.json) or TextMate (.tmTheme) theme into $HOME/BaCE/themes.
+// Returns the name to select it by
+#[tauri::command]
+pub fn import_theme(file_path: &str) -> Result<String, String> {
+    theme_file::import_theme_file(Path::new(file_path), &theme_file::get_themes_dir_path())
+        .map_err(|e| format!("{e}"))
+}
+
 #[tauri::command]
 pub fn delete_file_content_cache(
     state: State<AppState>, 
@@ -131,6 +546,8 @@ pub fn delete_file_content_cache(
 ) -> Result<(), ()> {
     let mut file_content_caches = state.file_content_caches.lock().unwrap();
     file_content_caches.remove(file_path);
+    state.syntax_trees.lock().unwrap().remove(file_path);
+    state.bracket_pairs.lock().unwrap().remove(file_path);
     Ok(())
 }
 
@@ -177,7 +594,6 @@ pub async fn save_file(
     match file.write_all(content.as_bytes()) {
         Ok(_) => {
             if file_path.to_string() == get_settings_file_path_cmd() {
-                let mut settings_state = state.settings_config.lock().unwrap();
                 let mut new_settings_config: Settings = match read_settings_file() {
                     Ok(s) => s,
                     Err(e) => {
@@ -186,10 +602,28 @@ pub async fn save_file(
                     }
                 };
 
-                if let Some(ref mut settings) = *settings_state {
-                    cmd_utils::detect_bg_image_path_change(&app, &settings, &mut new_settings_config);
-                    cmd_utils::detect_bg_type_change(&app, &settings, &mut new_settings_config);
-                    *settings = new_settings_config;
+                // the settings are unlocked before the caches are locked, read_file_content
+                // locks the settings while it holds the caches
+                let settings_loaded = {
+                    let mut settings_state = state.settings_config.lock().unwrap();
+                    if let Some(ref mut settings) = *settings_state {
+                        cmd_utils::detect_bg_image_path_change(&app, &settings, &mut new_settings_config);
+                        cmd_utils::detect_bg_type_change(&app, &settings, &mut new_settings_config);
+                        *settings = new_settings_config.clone();
+                    }
+                    settings_state.is_some()
+                };
+
+                if settings_loaded {
+                    // colors may have changed, files tokenized from now on use the new ones,
+                    // including those read while the caches are rehighlighted
+                    let theme = theme_file::load_theme(&new_settings_config, &theme_file::get_themes_dir_path()).unwrap_or_else(|e| {
+                        app.emit("internal_error", format!("{e}")).unwrap();
+                        Theme::new(&new_settings_config)
+                    });
+                    let theme = Arc::new(theme);
+                    *state.theme.lock().unwrap() = Some(Arc::clone(&theme));
+                    rehighlight_file_content_caches(&state, &theme);
                 }
             }
             return Ok("success".to_string());
@@ -48,6 +48,36 @@ pub enum PVTEditorDirectoryError {
     ReadPVTEditorDirectoryFail,
 }
 
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum LanguageDefinitionError {
+    #[error("Failed to read languages folder in BaCE directory")]
+    ReadLanguagesDirectoryFail,
+
+    #[error("Failed to read language definition file {0}")]
+    ReadDefinitionFileFail(String),
+
+    #[error("Invalid language definition in {0}: {1}")]
+    InvalidDefinition(String, String),
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ThemeError {
+    #[error("Failed to read themes folder in BaCE directory")]
+    ReadThemesDirectoryFail,
+
+    #[error("Couldn't find theme {0} in themes folder in BaCE directory")]
+    ThemeNotFound(String),
+
+    #[error("Failed to read theme file {0}")]
+    ReadThemeFileFail(String),
+
+    #[error("Invalid theme in {0}: {1}")]
+    InvalidTheme(String, String),
+
+    #[error("Failed to write theme file {0}")]
+    WriteThemeFileFail(String),
+}
+
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum FolderError {
     #[error("Failed to read folder content")]
@@ -11,23 +11,40 @@ pub mod syntax_highlight;
 pub mod file_handler;
 pub mod commands;
 pub mod settings;
+pub mod presets;
 pub mod folder;
 pub mod errors;
 
 use crate::settings::*;
+use crate::syntax_highlight::LineContent;
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::theme_file::{load_theme, get_themes_dir_path};
+use crate::syntax_highlight::syntax_tree::SyntaxTree;
+use crate::syntax_highlight::brackets::BracketPairs;
+use crate::syntax_highlight::languages::{language_registry, load_language_definitions, get_languages_dir_path};
 
 pub type AppStateStartUpErrors = Vec<String>;
 pub type AppStateSettingConfig = Option<Settings>;
+// colors of settings_config resolved for the tokenizers, None while settings_config is
+pub type AppStateTheme = Option<Arc<Theme>>;
 pub type LineContentsString = Arc<Vec<String>>;
 pub type ContentDOM = Arc<Vec<String>>;
 pub type Langauge = Arc<String>;
-pub type AppStateFileContentCaches = HashMap<String, (LineContentsString, ContentDOM, Langauge)>;
+pub type LineContents = Arc<Vec<LineContent>>;
+pub type AppStateFileContentCaches = HashMap<String, (LineContentsString, ContentDOM, Langauge, LineContents)>;
+// parsed files when built with the tree-sitter feature, keyed by file path like the caches
+pub type AppStateSyntaxTrees = HashMap<String, SyntaxTree>;
+// bracket pairs of the cached tokens, analyzed again whenever the tokens change
+pub type AppStateBracketPairs = HashMap<String, BracketPairs>;
 
 #[derive(Debug)]
 pub struct AppState {
     pub settings_config: Arc<Mutex<AppStateSettingConfig>>,
+    pub theme: Arc<Mutex<AppStateTheme>>,
     pub app_startup_errors: Arc<Mutex<AppStateStartUpErrors>>,
-    pub file_content_caches: Arc<Mutex<AppStateFileContentCaches>>
+    pub file_content_caches: Arc<Mutex<AppStateFileContentCaches>>,
+    pub syntax_trees: Arc<Mutex<AppStateSyntaxTrees>>,
+    pub bracket_pairs: Arc<Mutex<AppStateBracketPairs>>
 }
 
 fn handle_create_bace_directory(startup_errors: &mut AppStateStartUpErrors) {
@@ -49,11 +66,28 @@ fn handle_create_bace_directory(startup_errors: &mut AppStateStartUpErrors) {
     }
 }
 
+// user language definitions have to be registered before settings.json
+// gets created so they receive default syntax highlight colors
+fn handle_load_language_definitions(startup_errors: &mut AppStateStartUpErrors) {
+    let (languages, errors) = load_language_definitions(&get_languages_dir_path());
+
+    for e in errors {
+        startup_errors.push(format!("{e}"));
+    }
+    let mut registry = language_registry().write().unwrap();
+    for language in languages {
+        registry.register(Arc::new(language));
+    }
+}
+
 pub fn run() {
     let mut startup_errors: AppStateStartUpErrors = Vec::new();
     let file_content_caches: AppStateFileContentCaches = HashMap::new();
+    let syntax_trees: AppStateSyntaxTrees = HashMap::new();
+    let bracket_pairs: AppStateBracketPairs = HashMap::new();
 
     handle_create_bace_directory(&mut startup_errors); 
+    handle_load_language_definitions(&mut startup_errors);
 
     init_settings_file().unwrap_or_else(|e| {
         startup_errors.push(format!("{e}"));
@@ -75,10 +109,22 @@ pub fn run() {
             }
         }
     }
+    // a theme file that can't be loaded leaves the syntax_highlight colors
+    let theme: AppStateTheme = settings_config.as_ref().map(|settings| {
+        let theme = load_theme(settings, &get_themes_dir_path()).unwrap_or_else(|e| {
+            startup_errors.push(format!("{e}"));
+            Theme::new(settings)
+        });
+        Arc::new(theme)
+    });
+
     let app_state = AppState {
         settings_config: Arc::new(Mutex::new(settings_config)),
+        theme: Arc::new(Mutex::new(theme)),
         app_startup_errors: Arc::new(Mutex::new(startup_errors)),
-        file_content_caches: Arc::new(Mutex::new(file_content_caches))
+        file_content_caches: Arc::new(Mutex::new(file_content_caches)),
+        syntax_trees: Arc::new(Mutex::new(syntax_trees)),
+        bracket_pairs: Arc::new(Mutex::new(bracket_pairs))
     };
     tauri::Builder::default()
       .manage(app_state)
@@ -86,10 +132,21 @@ pub fn run() {
         commands::check_app_startup_errs, // first command to get invoked by frontend when app started
         commands::read_setting_configs, 
         commands::read_file_content,
+        commands::read_file_tokens,
+        commands::read_token_palette,
         commands::read_folder_content,
         commands::get_settings_file_path_cmd,
         commands::save_file,
         commands::update_file_content_cache,
+        commands::rehighlight_lines,
+        commands::set_file_language,
+        commands::list_themes,
+        commands::import_theme,
+        commands::find_matching_bracket,
+        commands::read_fold_ranges,
+        commands::read_outline,
+        commands::list_presets,
+        commands::switch_preset,
         commands::delete_file_content_cache,
         commands::create_empty_file,
         commands::create_empty_dir,
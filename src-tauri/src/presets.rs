@@ -0,0 +1,213 @@
+// Built-in color presets, each one sets the app background, the sidebar and tab bar
+// text and every token color at once. The `preset` setting records the one in use.
+
+use std::collections::BTreeMap;
+
+use crate::settings::{LanguageSyntaxHighlight, Settings};
+use crate::syntax_highlight::TokenType;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ThemePreset {
+    pub name: &'static str,
+    pub bg_color: &'static str,
+    pub sidebar_color: &'static str,
+    pub tab_bar_color: &'static str,
+    // one color for each of TokenType::ALL, in the same order
+    pub token_colors: [&'static str; TokenType::ALL.len()],
+}
+
+// the colors BaCE always had, settings.json files start with these
+pub const DARK_PRESET: ThemePreset = ThemePreset {
+    name: "dark",
+    bg_color: "#212121",
+    sidebar_color: "white",
+    tab_bar_color: "white",
+    token_colors: [
+        "white",   // Operator
+        "#bd00ff", // Keyword
+        "#26ffd7", // Identifier
+        "#ffb521", // Number
+        "white",   // Punctuation
+        "#70ff00", // StringLiteral
+        "white",   // Whitespace
+        "#ff7cf9", // Unknown
+        "grey",    // Comment
+        "#ffe000", // ClassName
+        "#4bb5ff", // FunctionName
+        "#ff8c5a", // Preprocessor
+        "#ff5c8a", // Lifetime
+        "#00c2ff", // Builtin
+        "#ff4d4d", // Annotation
+        "#7cc4ff", // Url
+        "#ffd866", // EscapeSequence
+        "#c792ea", // Placeholder
+    ],
+};
+
+pub const LIGHT_PRESET: ThemePreset = ThemePreset {
+    name: "light",
+    bg_color: "#fafafa",
+    sidebar_color: "#383a42",
+    tab_bar_color: "#383a42",
+    token_colors: [
+        "#383a42", // Operator
+        "#a626a4", // Keyword
+        "#383a42", // Identifier
+        "#986801", // Number
+        "#383a42", // Punctuation
+        "#50a14f", // StringLiteral
+        "#383a42", // Whitespace
+        "#e45649", // Unknown
+        "#a0a1a7", // Comment
+        "#c18401", // ClassName
+        "#4078f2", // FunctionName
+        "#ca1243", // Preprocessor
+        "#e45649", // Lifetime
+        "#0184bc", // Builtin
+        "#d73a49", // Annotation
+        "#0366d6", // Url
+        "#0184bc", // EscapeSequence
+        "#986801", // Placeholder
+    ],
+};
+
+pub const SOLARIZED_PRESET: ThemePreset = ThemePreset {
+    name: "solarized",
+    bg_color: "#002b36",
+    sidebar_color: "#93a1a1",
+    tab_bar_color: "#93a1a1",
+    token_colors: [
+        "#93a1a1", // Operator
+        "#859900", // Keyword
+        "#839496", // Identifier
+        "#d33682", // Number
+        "#93a1a1", // Punctuation
+        "#2aa198", // StringLiteral
+        "#839496", // Whitespace
+        "#dc322f", // Unknown
+        "#586e75", // Comment
+        "#b58900", // ClassName
+        "#268bd2", // FunctionName
+        "#cb4b16", // Preprocessor
+        "#6c71c4", // Lifetime
+        "#268bd2", // Builtin
+        "#dc322f", // Annotation
+        "#268bd2", // Url
+        "#cb4b16", // EscapeSequence
+        "#6c71c4", // Placeholder
+    ],
+};
+
+// pure black and white with saturated colors, every color passes WCAG AAA on the background
+pub const HIGH_CONTRAST_PRESET: ThemePreset = ThemePreset {
+    name: "high-contrast",
+    bg_color: "#000000",
+    sidebar_color: "#ffffff",
+    tab_bar_color: "#ffffff",
+    token_colors: [
+        "#ffffff", // Operator
+        "#ffd700", // Keyword
+        "#ffffff", // Identifier
+        "#00ffff", // Number
+        "#ffffff", // Punctuation
+        "#00ff00", // StringLiteral
+        "#ffffff", // Whitespace
+        "#ff6b6b", // Unknown
+        "#c0c0c0", // Comment
+        "#ffa500", // ClassName
+        "#87cefa", // FunctionName
+        "#ff8c00", // Preprocessor
+        "#ff69b4", // Lifetime
+        "#7fffd4", // Builtin
+        "#ff8080", // Annotation
+        "#87cefa", // Url
+        "#ffff00", // EscapeSequence
+        "#ff80ff", // Placeholder
+    ],
+};
+
+pub const THEME_PRESETS: [ThemePreset; 4] = [DARK_PRESET, LIGHT_PRESET, SOLARIZED_PRESET, HIGH_CONTRAST_PRESET];
+
+pub fn find_preset(name: &str) -> Option<&'static ThemePreset> {
+    THEME_PRESETS.iter().find(|preset| preset.name == name)
+}
+
+impl ThemePreset {
+    pub fn color(&self, token_type: TokenType) -> &'static str {
+        self.token_colors[token_type as usize]
+    }
+
+    pub fn language_syntax(&self, language: String) -> LanguageSyntaxHighlight {
+        LanguageSyntaxHighlight {
+            language,
+            keyword_color: self.color(TokenType::Keyword).to_string(),
+            identifier_color: self.color(TokenType::Identifier).to_string(),
+            number_color: self.color(TokenType::Number).to_string(),
+            operator_color: self.color(TokenType::Operator).to_string(),
+            punctuation_color: self.color(TokenType::Punctuation).to_string(),
+            string_literal_color: self.color(TokenType::StringLiteral).to_string(),
+            whitespace_color: self.color(TokenType::Whitespace).to_string(),
+            unknown_color: self.color(TokenType::Unknown).to_string(),
+            comment_color: self.color(TokenType::Comment).to_string(),
+            class_name_color: self.color(TokenType::ClassName).to_string(),
+            function_name_color: self.color(TokenType::FunctionName).to_string(),
+            preprocessor_color: self.color(TokenType::Preprocessor).to_string(),
+            lifetime_color: self.color(TokenType::Lifetime).to_string(),
+            builtin_color: self.color(TokenType::Builtin).to_string(),
+            annotation_color: self.color(TokenType::Annotation).to_string(),
+            url_color: self.color(TokenType::Url).to_string(),
+            escape_sequence_color: self.color(TokenType::EscapeSequence).to_string(),
+            placeholder_color: self.color(TokenType::Placeholder).to_string(),
+            token_styles: BTreeMap::new(),
+        }
+    }
+
+    // A theme file would paint over the preset's token colors, so selecting a preset unselects it
+    pub fn apply(&self, settings_config: &mut Settings) {
+        settings_config.app.bg_color = self.bg_color.to_string();
+        settings_config.side_bar.color = self.sidebar_color.to_string();
+        settings_config.tab_bar.color = self.tab_bar_color.to_string();
+
+        // font styles stay, presets only have colors
+        for language_syntax in settings_config.syntax_highlight.languages.iter_mut() {
+            let token_styles = std::mem::take(&mut language_syntax.token_styles);
+            *language_syntax = LanguageSyntaxHighlight {
+                token_styles,
+                ..self.language_syntax(language_syntax.language.clone())
+            };
+        }
+        settings_config.theme = None;
+        settings_config.preset = Some(self.name.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_preset() {
+        let mut settings_config = Settings::init_default_values();
+        settings_config.theme = Some("monokai".to_string());
+        let languages = settings_config.syntax_highlight.languages.len();
+
+        find_preset("light").unwrap().apply(&mut settings_config);
+        assert_eq!(settings_config.preset, Some("light".to_string()));
+        assert_eq!(settings_config.theme, None);
+        assert_eq!(settings_config.app.bg_color, "#fafafa");
+        assert_eq!(settings_config.side_bar.color, "#383a42");
+        assert_eq!(settings_config.tab_bar.color, "#383a42");
+        assert_eq!(settings_config.syntax_highlight.languages.len(), languages);
+        for language_syntax in &settings_config.syntax_highlight.languages {
+            assert_eq!(language_syntax.keyword_color, "#a626a4");
+            assert_eq!(language_syntax.color(TokenType::Builtin), "#0184bc");
+        }
+
+        // the dark preset gives back the defaults
+        find_preset("dark").unwrap().apply(&mut settings_config);
+        let default_settings = Settings::init_default_values();
+        assert_eq!(settings_config.app, default_settings.app);
+        assert_eq!(settings_config.syntax_highlight, default_settings.syntax_highlight);
+        assert!(find_preset("sepia").is_none());
+    }
+}
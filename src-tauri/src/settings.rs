@@ -1,6 +1,7 @@
 use std::io::{Read, Write, Cursor};
 use std::path::{Path, PathBuf};
 use std::env;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{File, read};
 
 use serde::{Deserialize, Serialize};
@@ -8,6 +9,9 @@ use serde::{Deserialize, Serialize};
 use base64::encode;
 
 use crate::errors::{FileError, SettingsError};
+use crate::presets::DARK_PRESET;
+use crate::syntax_highlight::TokenType;
+use crate::syntax_highlight::languages::{language_registry, DEFAULT_LANGUAGE};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -56,6 +60,45 @@ pub struct LanguageSyntaxHighlight {
     pub comment_color: String,
     pub class_name_color: String,
     pub function_name_color: String,
+    // added after the first release, settings.json files written before it don't have it
+    #[serde(default = "default_preprocessor_color")]
+    pub preprocessor_color: String,
+    #[serde(default = "default_lifetime_color")]
+    pub lifetime_color: String,
+    #[serde(default = "default_builtin_color")]
+    pub builtin_color: String,
+    #[serde(default = "default_annotation_color")]
+    pub annotation_color: String,
+    #[serde(default = "default_url_color")]
+    pub url_color: String,
+    #[serde(default = "default_escape_sequence_color")]
+    pub escape_sequence_color: String,
+    #[serde(default = "default_placeholder_color")]
+    pub placeholder_color: String,
+    // font styles and backgrounds of token types, `{ "Comment": { "italic": true } }`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub token_styles: BTreeMap<TokenType, TokenStyle>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenStyle {
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub underline: bool,
+    #[serde(default)]
+    pub background: Option<String>,
+}
+
+// rainbow brackets, `colors` are used in turn for each level of nesting
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketSetting {
+    pub rainbow: bool,
+    pub colors: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -70,7 +113,21 @@ pub struct Settings {
     pub side_bar: SidebarSetting,
     pub tab_bar: TabBarSetting,
     pub editor: EditorSetting,
-    pub syntax_highlight: SyntaxHighlight
+    pub syntax_highlight: SyntaxHighlight,
+    // extension (`pyi`) or glob (`*.jsm`, `scripts/*`) to language name,
+    // consulted before any other language detection
+    #[serde(default)]
+    pub file_associations: HashMap<String, String>,
+    // name of a theme file in $HOME/BaCE/themes, its colors replace the
+    // ones in syntax_highlight. None keeps syntax_highlight as is
+    #[serde(default)]
+    pub theme: Option<String>,
+    // built-in preset the colors were last set from, None when it isn't known
+    // (settings.json written before presets existed)
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default = "BracketSetting::init_default_values")]
+    pub brackets: BracketSetting,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -83,7 +140,7 @@ impl AppSetting {
     fn init_default_values() -> AppSetting {
         AppSetting {
             bg_type: "color".to_string(),
-            bg_color: "#212121".to_string(),
+            bg_color: DARK_PRESET.bg_color.to_string(),
             bg_image_path: "".to_string(),
             bg_image_base64: "".to_string(),
         }
@@ -94,7 +151,7 @@ impl SidebarSetting {
     fn init_default_values() -> SidebarSetting {
         SidebarSetting {
             font_size: "14px".to_string(),
-            color: "white".to_string(),
+            color: DARK_PRESET.sidebar_color.to_string(),
             width: "100px".to_string(),
             width_adjustable: false
         }
@@ -105,7 +162,7 @@ impl TabBarSetting {
     fn init_default_values() -> TabBarSetting {
         TabBarSetting {
             font_size: "13px".to_string(),
-            color: "white".to_string(),
+            color: DARK_PRESET.tab_bar_color.to_string(),
         }
     }
 }
@@ -119,31 +176,140 @@ impl EditorSetting {
     }
 }
 
+impl BracketSetting {
+    fn init_default_values() -> BracketSetting {
+        BracketSetting {
+            rainbow: false,
+            colors: vec!["#ffd700".to_string(), "#da70d6".to_string(), "#179fff".to_string()],
+        }
+    }
+}
+
 impl LanguageSyntaxHighlight {
     fn init_default_values(language: String) -> LanguageSyntaxHighlight {
-        LanguageSyntaxHighlight {
-            language: language,
-            keyword_color: "#bd00ff".to_string(),
-            identifier_color: "#26ffd7".to_string(),
-            number_color: "#ffb521".to_string(),
-            operator_color: "white".to_string(),
-            punctuation_color: "white".to_string(),
-            string_literal_color: "#70ff00".to_string(),
-            whitespace_color: "white".to_string(),
-            unknown_color: "#ff7cf9".to_string(),
-            comment_color: "grey".to_string(),
-            class_name_color: "#ffe000".to_string(),
-            function_name_color: "#4bb5ff".to_string()
+        DARK_PRESET.language_syntax(language)
+    }
+
+    pub fn color(&self, token_type: TokenType) -> &str {
+        match token_type {
+            TokenType::Keyword => &self.keyword_color,
+            TokenType::Identifier => &self.identifier_color,
+            TokenType::Number => &self.number_color,
+            TokenType::Operator => &self.operator_color,
+            TokenType::Punctuation => &self.punctuation_color,
+            TokenType::StringLiteral => &self.string_literal_color,
+            TokenType::Whitespace => &self.whitespace_color,
+            TokenType::Unknown => &self.unknown_color,
+            TokenType::Comment => &self.comment_color,
+            TokenType::ClassName => &self.class_name_color,
+            TokenType::FunctionName => &self.function_name_color,
+            TokenType::Preprocessor => &self.preprocessor_color,
+            TokenType::Lifetime => &self.lifetime_color,
+            TokenType::Builtin => &self.builtin_color,
+            TokenType::Annotation => &self.annotation_color,
+            TokenType::Url => &self.url_color,
+            TokenType::EscapeSequence => &self.escape_sequence_color,
+            TokenType::Placeholder => &self.placeholder_color,
+        }
+    }
+
+    pub fn set_color(&mut self, token_type: TokenType, color: &str) {
+        let field = match token_type {
+            TokenType::Keyword => &mut self.keyword_color,
+            TokenType::Identifier => &mut self.identifier_color,
+            TokenType::Number => &mut self.number_color,
+            TokenType::Operator => &mut self.operator_color,
+            TokenType::Punctuation => &mut self.punctuation_color,
+            TokenType::StringLiteral => &mut self.string_literal_color,
+            TokenType::Whitespace => &mut self.whitespace_color,
+            TokenType::Unknown => &mut self.unknown_color,
+            TokenType::Comment => &mut self.comment_color,
+            TokenType::ClassName => &mut self.class_name_color,
+            TokenType::FunctionName => &mut self.function_name_color,
+            TokenType::Preprocessor => &mut self.preprocessor_color,
+            TokenType::Lifetime => &mut self.lifetime_color,
+            TokenType::Builtin => &mut self.builtin_color,
+            TokenType::Annotation => &mut self.annotation_color,
+            TokenType::Url => &mut self.url_color,
+            TokenType::EscapeSequence => &mut self.escape_sequence_color,
+            TokenType::Placeholder => &mut self.placeholder_color,
+        };
+        *field = color.to_string();
+    }
+}
+
+fn default_preprocessor_color() -> String {
+    DARK_PRESET.color(TokenType::Preprocessor).to_string()
+}
+
+fn default_lifetime_color() -> String {
+    DARK_PRESET.color(TokenType::Lifetime).to_string()
+}
+
+fn default_builtin_color() -> String {
+    DARK_PRESET.color(TokenType::Builtin).to_string()
+}
+
+fn default_annotation_color() -> String {
+    DARK_PRESET.color(TokenType::Annotation).to_string()
+}
+
+fn default_url_color() -> String {
+    DARK_PRESET.color(TokenType::Url).to_string()
+}
+
+fn default_escape_sequence_color() -> String {
+    DARK_PRESET.color(TokenType::EscapeSequence).to_string()
+}
+
+fn default_placeholder_color() -> String {
+    DARK_PRESET.color(TokenType::Placeholder).to_string()
+}
+
+impl TokenStyle {
+    // CSS declarations of the style, empty when it changes nothing
+    pub fn css(&self) -> String {
+        let mut declarations: Vec<String> = Vec::new();
+
+        if self.bold {
+            declarations.push("font-weight: bold".to_string());
         }
+        if self.italic {
+            declarations.push("font-style: italic".to_string());
+        }
+        if self.underline {
+            declarations.push("text-decoration: underline".to_string());
+        }
+        if let Some(ref background) = self.background {
+            declarations.push(format!("background-color: {background}"));
+        }
+        declarations.join("; ")
     }
 }
 
+// `#rgb`, `#rrggbbaa`, `rgba(0, 0, 0, 0.2)`, `hsl(...)` or a color name like `transparent`
+pub fn is_valid_color(color: &str) -> bool {
+    if let Some(hex) = color.strip_prefix('#') {
+        return [3, 4, 6, 8].contains(&hex.len()) && hex.chars().all(|ch| ch.is_ascii_hexdigit());
+    }
+    let functions = ["rgb(", "rgba(", "hsl(", "hsla("];
+    if let Some(function) = functions.iter().find(|function| color.starts_with(*function)) {
+        let Some(arguments) = color[function.len()..].strip_suffix(')') else {
+            return false;
+        };
+        // numbers with units like `120deg`, nothing that could end the declaration
+        return arguments.chars().all(|ch| ch.is_ascii_alphanumeric() || " .,%/+-".contains(ch));
+    }
+    !color.is_empty() && color.chars().all(|ch| ch.is_ascii_alphabetic())
+}
+
 impl SyntaxHighlight {
     fn init_default_values() -> SyntaxHighlight {
         let mut languages: Vec<LanguageSyntaxHighlight> = Vec::new();
-        let support_languages = ["python", "javascript", "typescript", "rust"];
+        let support_languages = language_registry().read().unwrap().names();
 
-        for language in &support_languages {
+        // plain text has no highlight colors, it takes the editor's text color
+        for language in support_languages.iter().filter(|language| language.as_str() != DEFAULT_LANGUAGE) {
             let language_syntax = LanguageSyntaxHighlight::init_default_values(
                 language.to_string()
             );
@@ -163,6 +329,10 @@ impl Settings {
             tab_bar: TabBarSetting::init_default_values(),
             editor: EditorSetting::init_default_values(),
             syntax_highlight: SyntaxHighlight::init_default_values(),
+            file_associations: HashMap::new(),
+            theme: None,
+            preset: Some(DARK_PRESET.name.to_string()),
+            brackets: BracketSetting::init_default_values(),
         }
     }
 
@@ -194,6 +364,34 @@ impl Settings {
                 return SettingsValidation { passed: false, msg };
             }
         }
+        // colors and backgrounds end up in a style attribute, only plain colors are allowed
+        for language_syntax in &self.syntax_highlight.languages {
+            let invalid_color = TokenType::ALL.iter()
+                .map(|&token_type| (token_type, language_syntax.color(token_type)))
+                .find(|(_, color)| !is_valid_color(color));
+            if let Some((token_type, color)) = invalid_color {
+                return SettingsValidation {
+                    passed: false,
+                    msg: format!("Color '{}' of {:?} in {} is not a valid color.", color, token_type, language_syntax.language)
+                };
+            }
+            for (token_type, token_style) in &language_syntax.token_styles {
+                if let Some(ref background) = token_style.background {
+                    if !is_valid_color(background) {
+                        return SettingsValidation {
+                            passed: false,
+                            msg: format!("Background '{}' of {:?} in {} is not a valid color.", background, token_type, language_syntax.language)
+                        };
+                    }
+                }
+            }
+        }
+        if let Some(color) = self.brackets.colors.iter().find(|color| !is_valid_color(color)) {
+            return SettingsValidation {
+                passed: false,
+                msg: format!("Bracket color '{}' is not a valid color.", color)
+            };
+        }
 
         SettingsValidation { 
             passed: true, 
@@ -344,15 +542,71 @@ mod tests {
         assert_eq!(default_syntax.comment_color, "grey");
         assert_eq!(default_syntax.class_name_color, "#ffe000");
         assert_eq!(default_syntax.function_name_color, "#4bb5ff");
+        assert_eq!(default_syntax.preprocessor_color, "#ff8c5a");
+        assert_eq!(default_syntax.lifetime_color, "#ff5c8a");
+        assert_eq!(default_syntax.builtin_color, "#00c2ff");
+        assert_eq!(default_syntax.annotation_color, "#ff4d4d");
+        assert_eq!(default_syntax.url_color, "#7cc4ff");
+        assert_eq!(default_syntax.escape_sequence_color, "#ffd866");
+        assert_eq!(default_syntax.placeholder_color, "#c792ea");
+    }
+
+    #[test]
+    fn test_token_styles() {
+        let mut settings_config = Settings::init_default_values();
+        let token_style = TokenStyle { bold: true, italic: true, underline: false, background: Some("rgba(112, 255, 0, 0.15)".to_string()) };
+        assert_eq!(token_style.css(), "font-weight: bold; font-style: italic; background-color: rgba(112, 255, 0, 0.15)");
+        assert_eq!(TokenStyle::default().css(), "");
+
+        settings_config.syntax_highlight.languages[0].token_styles.insert(TokenType::StringLiteral, token_style);
+        assert!(settings_config.validate_settings().passed);
+
+        // anything that could leave the style attribute is rejected
+        for background in ["red; color: blue", "#12345", "url(x.png)", "rgb(1, 2, 3", "\"><script>", ""] {
+            settings_config.syntax_highlight.languages[0].token_styles.insert(
+                TokenType::Comment, 
+                TokenStyle { background: Some(background.to_string()), ..TokenStyle::default() }
+            );
+            assert!(!settings_config.validate_settings().passed, "{background}");
+        }
+        assert!(is_valid_color("#fff") && is_valid_color("transparent") && is_valid_color("hsl(120deg 75% 25% / 60%)"));
+
+        // token styles are optional in settings.json
+        let language_syntax: LanguageSyntaxHighlight = serde_json::from_str(
+            &serde_json::to_string(&LanguageSyntaxHighlight::init_default_values("go".to_string())).unwrap()
+        ).unwrap();
+        assert!(language_syntax.token_styles.is_empty());
+    }
+
+    #[test]
+    fn test_bracket_setting() {
+        let mut settings_config = Settings::init_default_values();
+        assert!(!settings_config.brackets.rainbow);
+        assert!(settings_config.validate_settings().passed);
+
+        settings_config.brackets.colors.push("red\" onclick=\"x".to_string());
+        assert!(!settings_config.validate_settings().passed);
+    }
+
+    #[test]
+    fn test_validate_token_colors() {
+        let mut settings_config = Settings::init_default_values();
+        assert!(settings_config.validate_settings().passed);
+
+        settings_config.syntax_highlight.languages[1].set_color(TokenType::Keyword, "red\"><img src=x onerror=alert(1)>");
+        let validation = settings_config.validate_settings();
+        assert!(!validation.passed);
+        assert!(validation.msg.contains("Keyword"));
     }
 
     #[test]
     fn test_syntax_highlight_defaults() {
         let default_syntax_highlight = SyntaxHighlight::init_default_values();
-        let expected_languages = ["python", "javascript", "typescript", "rust"];
+        let expected_languages = ["python", "javascript", "typescript", "tsx", "rust", "go", "c", "cpp", "json", "toml", "yaml", "markdown", "css", "html", "vue", "shell", "dockerfile", "makefile"];
 
         for (i, language) in expected_languages.iter().enumerate() {
             assert_eq!(default_syntax_highlight.languages[i].language, *language);
         }
+        assert_eq!(default_syntax_highlight.languages.len(), expected_languages.len());
     }
 }
@@ -0,0 +1,294 @@
+// Which `(`, `[` and `{` of a tokenized file pair up. Only Punctuation tokens count,
+// so brackets inside strings and comments are left out the way the tokenizer saw them.
+// Columns are UTF-16 code units into the line, like TokenSpan.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde::{Serialize, Deserialize};
+
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{LineContent, TokenType};
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct Bracket {
+    pub position: BracketPosition,
+    pub bracket: char,
+    // brackets around it, 0 for a bracket at the top level. A pair shares its depth
+    pub depth: usize,
+    // None for a bracket nothing closes or opens
+    pub partner: Option<BracketPosition>,
+    // token of the line the bracket is in
+    #[serde(skip)]
+    token_index: usize,
+}
+
+// A bracket token of a line as sent to span clients, (start, depth). start is the
+// start of its TokenSpan, depth picks the color out of the `brackets.colors` setting
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub struct BracketDepth(pub usize, pub usize);
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct BracketPairs {
+    // in the order they appear in the file
+    brackets: Vec<Bracket>,
+}
+
+fn closing_bracket(bracket: char) -> Option<char> {
+    match bracket {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+fn is_closing_bracket(bracket: char) -> bool {
+    matches!(bracket, ')' | ']' | '}')
+}
+
+impl BracketPairs {
+    pub fn analyze(line_contents: &[LineContent]) -> BracketPairs {
+        let mut brackets: Vec<Bracket> = Vec::new();
+        // indexes into brackets of the brackets still open
+        let mut open_brackets: Vec<usize> = Vec::new();
+
+        for (line, line_content) in line_contents.iter().enumerate() {
+            let mut column = 0;
+
+            for (token_index, token) in line_content.tokens.iter().enumerate() {
+                if token.token_type != TokenType::Punctuation {
+                    column += token.value.encode_utf16().count();
+                    continue;
+                }
+                // a token can hold more than one bracket, `{{` in Vue templates or `${` in JavaScript
+                for bracket in token.value.chars() {
+                    let position = BracketPosition { line, column };
+                    column += bracket.len_utf16();
+
+                    if closing_bracket(bracket).is_some() {
+                        open_brackets.push(brackets.len());
+                        brackets.push(Bracket { position, bracket, depth: open_brackets.len() - 1, partner: None, token_index });
+                        continue;
+                    }
+                    if !is_closing_bracket(bracket) {
+                        continue;
+                    }
+                    // brackets left open inside the pair stay unmatched, `( [ )` closes the `(`.
+                    // A closing bracket no open one matches is unmatched itself
+                    let opening = open_brackets.iter()
+                        .rposition(|&index| closing_bracket(brackets[index].bracket) == Some(bracket));
+                    let mut closing = Bracket { position, bracket, depth: open_brackets.len(), partner: None, token_index };
+
+                    if let Some(opening) = opening {
+                        let opening_index = open_brackets[opening];
+                        open_brackets.truncate(opening);
+                        brackets[opening_index].partner = Some(position);
+                        closing.depth = brackets[opening_index].depth;
+                        closing.partner = Some(brackets[opening_index].position);
+                    }
+                    brackets.push(closing);
+                }
+            }
+        }
+        BracketPairs { brackets }
+    }
+
+    pub fn brackets(&self) -> &[Bracket] {
+        &self.brackets
+    }
+
+    // the bracket at `position`
+    pub fn get(&self, position: BracketPosition) -> Option<&Bracket> {
+        let index = self.brackets.binary_search_by_key(&(position.line, position.column), |bracket| {
+            (bracket.position.line, bracket.position.column)
+        });
+        index.ok().map(|index| &self.brackets[index])
+    }
+
+    // The bracket at `position` or else the next one after it on the same line, as
+    // Vim's `%` finds it. Only brackets with a partner are returned
+    pub fn find_pair(&self, position: BracketPosition) -> Option<&Bracket> {
+        let start = self.brackets.partition_point(|bracket| {
+            (bracket.position.line, bracket.position.column) < (position.line, position.column)
+        });
+        self.brackets[start..].iter()
+            .take_while(|bracket| bracket.position.line == position.line)
+            .find(|bracket| bracket.partner.is_some())
+    }
+}
+
+// Color the brackets of `line_contents` by depth with the theme's bracket colors.
+// Unmatched brackets keep the Punctuation color. A token with more than one bracket
+// takes the color of its first one. `bracket_pairs` are those of `line_contents`.
+// Returns the lines whose colors changed, from the first to the last of them
+pub fn colorize_brackets(
+    theme: &Theme, 
+    language: &str, 
+    bracket_pairs: &BracketPairs, 
+    line_contents: &mut [LineContent]
+) -> Option<Range<usize>> {
+    let bracket_colors = theme.bracket_colors();
+    if bracket_colors.is_empty() {
+        return None;
+    }
+    let mut changed_lines: Option<Range<usize>> = None;
+    let mut previous_token = None;
+
+    for bracket in bracket_pairs.brackets() {
+        let line = bracket.position.line;
+        if previous_token == Some((line, bracket.token_index)) {
+            continue;
+        }
+        previous_token = Some((line, bracket.token_index));
+
+        let token = &mut line_contents[line].tokens[bracket.token_index];
+        let color = match bracket.partner {
+            Some(_) => &bracket_colors[bracket.depth % bracket_colors.len()],
+            None => theme.color(language, TokenType::Punctuation),
+        };
+        if token.color != *color {
+            token.color = Arc::clone(color);
+            changed_lines = Some(match changed_lines {
+                Some(lines) => lines.start..line + 1,
+                None => line..line + 1,
+            });
+        }
+    }
+    changed_lines
+}
+
+// Depths of the bracket tokens of `lines`, one list per line. These are the tokens
+// colorize_brackets colors by depth, a token whose first bracket has a partner
+pub fn bracket_depths(
+    bracket_pairs: &BracketPairs, 
+    line_contents: &[LineContent], 
+    lines: Range<usize>
+) -> Vec<Vec<BracketDepth>> {
+    let mut bracket_depths = vec![Vec::new(); lines.len()];
+    let start = bracket_pairs.brackets.partition_point(|bracket| bracket.position.line < lines.start);
+    let mut previous_token = None;
+
+    for bracket in bracket_pairs.brackets[start..].iter().take_while(|bracket| bracket.position.line < lines.end) {
+        let line = bracket.position.line;
+        if previous_token == Some((line, bracket.token_index)) {
+            continue;
+        }
+        previous_token = Some((line, bracket.token_index));
+        if bracket.partner.is_none() {
+            continue;
+        }
+        // the token can start with something else, `${` in JavaScript
+        let token = &line_contents[line].tokens[bracket.token_index];
+        let prefix_length: usize = token.value.chars()
+            .take_while(|&c| closing_bracket(c).is_none() && !is_closing_bracket(c))
+            .map(char::len_utf16)
+            .sum();
+        bracket_depths[line - lines.start].push(BracketDepth(bracket.position.column - prefix_length, bracket.depth));
+    }
+    bracket_depths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize_lines, retokenize_lines};
+    use crate::settings::Settings;
+    use crate::syntax_highlight::token_spans;
+
+    #[test]
+    fn test_bracket_pairs() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = vec![
+            "fn f(a: [u8; 2]) {".to_string(), 
+            "    let s = \"(\"; // )".to_string(), 
+            "    g(a[0], { x })".to_string(), 
+            "}".to_string(), 
+            ")".to_string()
+        ];
+        let bracket_pairs = BracketPairs::analyze(&tokenize_lines("rust", &theme, &lines));
+        let position = |line: usize, column: usize| BracketPosition { line, column };
+        let bracket_at = |line: usize, column: usize| *bracket_pairs.get(position(line, column)).unwrap();
+
+        assert_eq!(bracket_at(0, 4).partner, Some(position(0, 15)));
+        assert_eq!(bracket_at(0, 8).depth, 1);
+        assert_eq!(bracket_at(0, 17).partner, Some(position(3, 0)));
+        assert_eq!(bracket_at(3, 0).partner, Some(position(0, 17)));
+        assert_eq!((bracket_at(2, 5).depth, bracket_at(2, 5).partner), (1, Some(position(2, 17))));
+        assert_eq!((bracket_at(2, 12).depth, bracket_at(2, 12).partner), (2, Some(position(2, 16))));
+        // brackets in strings and comments don't count
+        assert!(bracket_pairs.brackets().iter().all(|bracket| bracket.position.line != 1));
+        assert_eq!(bracket_at(4, 0).partner, None);
+
+        // `%` jumps from the next bracket on the line
+        assert_eq!(bracket_pairs.find_pair(position(2, 0)).unwrap().position, position(2, 5));
+        assert_eq!(bracket_pairs.find_pair(position(2, 6)).unwrap().bracket, '[');
+        assert_eq!(bracket_pairs.find_pair(position(1, 0)), None);
+        assert_eq!(bracket_pairs.find_pair(position(4, 0)), None);
+
+        // an unclosed `[` inside a pair doesn't take its closing bracket
+        let mismatched = BracketPairs::analyze(&tokenize_lines("javascript", &theme, &["( [ )".to_string()]));
+        assert_eq!(mismatched.get(position(0, 0)).unwrap().partner, Some(position(0, 4)));
+        assert_eq!(mismatched.get(position(0, 2)).unwrap().partner, None);
+    }
+
+    #[test]
+    fn test_colorize_brackets() {
+        let mut settings_config = Settings::init_default_values();
+        let theme = Theme::new(&settings_config);
+        let mut lines: Vec<String> = vec!["f(a[0], {".to_string(), "  g(x)".to_string(), "})".to_string(), ")".to_string()];
+        let mut line_contents = tokenize_lines("javascript", &theme, &lines);
+        // rainbow brackets are off by default
+        assert_eq!(colorize_brackets(&theme, "javascript", &BracketPairs::analyze(&line_contents), &mut line_contents), None);
+
+        settings_config.brackets.rainbow = true;
+        let theme = Theme::new(&settings_config);
+        let mut line_contents = tokenize_lines("javascript", &theme, &lines);
+        assert_eq!(colorize_brackets(&theme, "javascript", &BracketPairs::analyze(&line_contents), &mut line_contents), Some(0..3));
+        let color_of = |line_contents: &Vec<LineContent>, line: usize, value: &str| {
+            line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().color.to_string()
+        };
+        assert_eq!(color_of(&line_contents, 0, "("), "#ffd700");
+        assert_eq!(color_of(&line_contents, 0, "["), "#da70d6");
+        assert_eq!(color_of(&line_contents, 1, "("), "#179fff");
+        assert_eq!(color_of(&line_contents, 2, ")"), "#ffd700");
+        assert_eq!(color_of(&line_contents, 3, ")"), "white");
+        assert_eq!(colorize_brackets(&theme, "javascript", &BracketPairs::analyze(&line_contents), &mut line_contents), None);
+
+        // wrapping the code in a block shifts the colors of the lines after the edit
+        retokenize_lines("javascript", &theme, &mut lines, &mut line_contents, 0, 1, vec!["{ f(a[0], {".to_string()]);
+        assert_eq!(colorize_brackets(&theme, "javascript", &BracketPairs::analyze(&line_contents), &mut line_contents), Some(0..3));
+        assert_eq!(color_of(&line_contents, 1, "("), "#ffd700");
+        assert_eq!(color_of(&line_contents, 3, ")"), "white");
+    }
+
+    #[test]
+    fn test_bracket_depths() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = vec!["f(a[0], {".to_string(), "  `${x}`".to_string(), "})".to_string(), ")".to_string()];
+        let line_contents = tokenize_lines("javascript", &theme, &lines);
+        let bracket_pairs = BracketPairs::analyze(&line_contents);
+
+        assert_eq!(bracket_depths(&bracket_pairs, &line_contents, 0..4), [
+            vec![BracketDepth(1, 0), BracketDepth(3, 1), BracketDepth(5, 1), BracketDepth(8, 1)], 
+            vec![BracketDepth(3, 2), BracketDepth(6, 2)], 
+            vec![BracketDepth(0, 1), BracketDepth(1, 0)], 
+            vec![]
+        ]);
+        // the spans of the bracket tokens start where the depths do
+        let spans = token_spans(&line_contents[1].tokens);
+        assert!(spans.iter().any(|span| span.0 == TokenType::Punctuation && span.1 == 3));
+        assert_eq!(bracket_depths(&bracket_pairs, &line_contents, 1..3), [
+            vec![BracketDepth(3, 2), BracketDepth(6, 2)], 
+            vec![BracketDepth(0, 1), BracketDepth(1, 0)]
+        ]);
+    }
+}
@@ -0,0 +1,200 @@
+// Lines of a tokenized file that can be folded. Blocks come from the bracket pairs,
+// or from indentation in languages that nest by it. Runs of comment lines and of
+// import lines fold as one range each.
+
+use serde::{Serialize, Deserialize};
+
+use crate::syntax_highlight::brackets::BracketPairs;
+use crate::syntax_highlight::{LineContent, LineState, Token, TokenType};
+
+// languages whose blocks are the lines indented deeper than the line opening them
+const INDENTATION_LANGUAGES: [&str; 2] = ["python", "yaml"];
+const IMPORT_KEYWORDS: [&str; 4] = ["use", "import", "from", "extern"];
+const INCLUDE_DIRECTIVES: [&str; 3] = ["include", "include_next", "import"];
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum FoldKind {
+    // a function, class, impl block or any other block of code
+    Region,
+    Imports,
+    Comment,
+}
+
+// start_line stays visible when folded, the lines after it up to end_line are hidden
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldRange {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub kind: FoldKind,
+}
+
+// Fold ranges sorted by start line, at most one per start line, the longest one
+pub fn fold_ranges(language: &str, line_contents: &[LineContent]) -> Vec<FoldRange> {
+    let mut fold_ranges: Vec<FoldRange> = bracket_ranges(line_contents);
+
+    if INDENTATION_LANGUAGES.contains(&language) {
+        fold_ranges.extend(indentation_ranges(line_contents));
+    }
+    fold_ranges.extend(line_runs(line_contents, FoldKind::Comment, |line| is_comment_line(&line_contents[line].tokens)));
+
+    // a multi-line `use a::{...};` belongs to the import group around it
+    let mut import_lines: Vec<bool> = line_contents.iter().map(|line_content| is_import_line(&line_content.tokens)).collect();
+    for fold_range in &fold_ranges {
+        if fold_range.kind == FoldKind::Region && import_lines[fold_range.start_line] {
+            import_lines[fold_range.start_line + 1..=fold_range.end_line].fill(true);
+        }
+    }
+    fold_ranges.extend(line_runs(line_contents, FoldKind::Imports, |line| import_lines[line]));
+
+    fold_ranges.sort_by(|a, b| a.start_line.cmp(&b.start_line).then(b.end_line.cmp(&a.end_line)));
+    fold_ranges.dedup_by_key(|fold_range| fold_range.start_line);
+    fold_ranges
+}
+
+// from the line of an opening bracket to the line of its partner
+fn bracket_ranges(line_contents: &[LineContent]) -> Vec<FoldRange> {
+    BracketPairs::analyze(line_contents).brackets().iter()
+        .filter_map(|bracket| {
+            let partner = bracket.partner?;
+            (partner.line > bracket.position.line).then_some(FoldRange {
+                start_line: bracket.position.line,
+                end_line: partner.line,
+                kind: FoldKind::Region,
+            })
+        })
+        .collect()
+}
+
+// None for blank lines, they neither end a block nor belong to one on their own.
+// Lines continuing a multi-line string or comment are deeper than any line
+fn indentation(line_content: &LineContent, start_state: &LineState) -> Option<usize> {
+    if *start_state != LineState::Normal {
+        return Some(usize::MAX);
+    }
+    let first_token = line_content.tokens.first()?;
+
+    match first_token.token_type {
+        TokenType::Whitespace if line_content.tokens.len() == 1 => None,
+        TokenType::Whitespace => Some(first_token.value.chars().count()),
+        _ => Some(0),
+    }
+}
+
+// from a line to the last of the lines after it that are indented deeper
+fn indentation_ranges(line_contents: &[LineContent]) -> Vec<FoldRange> {
+    let indents: Vec<Option<usize>> = line_contents.iter()
+        .enumerate()
+        .map(|(line, line_content)| {
+            let start_state = line.checked_sub(1).map_or(LineState::Normal, |previous| line_contents[previous].state.clone());
+            indentation(line_content, &start_state)
+        })
+        .collect();
+    let mut fold_ranges = Vec::new();
+
+    for (start_line, indent) in indents.iter().enumerate() {
+        let Some(indent) = *indent else {
+            continue;
+        };
+        let mut end_line = start_line;
+        for (line, line_indent) in indents.iter().enumerate().skip(start_line + 1) {
+            match line_indent {
+                None => continue,
+                Some(line_indent) if *line_indent > indent => end_line = line,
+                Some(_) => break,
+            }
+        }
+        if end_line > start_line {
+            fold_ranges.push(FoldRange { start_line, end_line, kind: FoldKind::Region });
+        }
+    }
+    fold_ranges
+}
+
+// runs of two or more consecutive lines `is_part` holds for
+fn line_runs(
+    line_contents: &[LineContent], 
+    kind: FoldKind, 
+    is_part: impl Fn(usize) -> bool
+) -> Vec<FoldRange> {
+    let mut fold_ranges = Vec::new();
+    let mut run_start: Option<usize> = None;
+
+    for line in 0..=line_contents.len() {
+        let part = line < line_contents.len() && is_part(line);
+        match (run_start, part) {
+            (None, true) => run_start = Some(line),
+            (Some(start_line), false) => {
+                if line - 1 > start_line {
+                    fold_ranges.push(FoldRange { start_line, end_line: line - 1, kind });
+                }
+                run_start = None;
+            },
+            _ => {},
+        }
+    }
+    fold_ranges
+}
+
+// nothing but a comment, with any TODOs and URLs split out of it
+fn is_comment_line(tokens: &[Token]) -> bool {
+    let mut code_tokens = tokens.iter().filter(|token| token.token_type != TokenType::Whitespace).peekable();
+
+    code_tokens.peek().is_some()
+        && code_tokens.all(|token| matches!(token.token_type, TokenType::Comment | TokenType::Annotation | TokenType::Url))
+}
+
+// `use`, `import`, `from ... import`, `extern crate` or `#include`, optionally after `pub` or `export`
+fn is_import_line(tokens: &[Token]) -> bool {
+    let mut code_tokens = tokens.iter()
+        .filter(|token| token.token_type != TokenType::Whitespace)
+        .skip_while(|token| token.token_type == TokenType::Keyword && matches!(token.value.as_str(), "pub" | "export"));
+
+    match code_tokens.next() {
+        Some(token) if token.token_type == TokenType::Keyword => IMPORT_KEYWORDS.contains(&token.value.as_str()),
+        Some(token) if token.token_type == TokenType::Preprocessor => {
+            let directive = token.value.trim_start_matches(|c: char| c == '#' || c.is_whitespace());
+            INCLUDE_DIRECTIVES.contains(&directive)
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize_lines, retokenize_lines};
+    use crate::settings::Settings;
+    use crate::syntax_highlight::theme::Theme;
+
+    #[test]
+    fn test_fold_ranges() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let fold = |start_line: usize, end_line: usize, kind: FoldKind| FoldRange { start_line, end_line, kind };
+        let to_lines = |source: &str| -> Vec<String> { source.lines().map(|line| line.to_string()).collect() };
+
+        let mut lines = to_lines("use std::fs;\nuse std::io::{\n    Read, Write,\n};\n\n// Reads a file.\n// TODO: errors\npub fn read(path: &str) -> String {\n    let mut s = String::new();\n    s\n}");
+        let mut line_contents = tokenize_lines("rust", &theme, &lines);
+        assert_eq!(fold_ranges("rust", &line_contents), [
+            fold(0, 3, FoldKind::Imports), 
+            fold(1, 3, FoldKind::Region), 
+            fold(5, 6, FoldKind::Comment), 
+            fold(7, 10, FoldKind::Region)
+        ]);
+
+        // ranges follow the cached tokens as they are edited
+        retokenize_lines("rust", &theme, &mut lines, &mut line_contents, 9, 9, vec!["    s.push('{');".to_string()]);
+        assert_eq!(fold_ranges("rust", &line_contents).last(), Some(&fold(7, 11, FoldKind::Region)));
+
+        let lines = to_lines("import os\nfrom sys import argv\nclass A:\n    def f(self):\n        \"\"\"doc\ntext\"\"\"\n\n        return [\n            1,\n        ]\n\nx = 1");
+        let line_contents = tokenize_lines("python", &theme, &lines);
+        assert_eq!(fold_ranges("python", &line_contents), [
+            fold(0, 1, FoldKind::Imports), 
+            fold(2, 9, FoldKind::Region), 
+            fold(3, 9, FoldKind::Region), 
+            fold(4, 5, FoldKind::Region), 
+            fold(7, 9, FoldKind::Region)
+        ]);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const C_KEYWORDS: [&str; 54] = [
+    "auto", "break", "case", "char", "const", "continue", "default", "do",
+    "double", "else", "enum", "extern", "float", "for", "goto", "if", "inline",
+    "int", "long", "register", "restrict", "return", "short", "signed", "sizeof",
+    "static", "struct", "switch", "typedef", "union", "unsigned", "void",
+    "volatile", "while", "_Alignas", "_Alignof", "_Atomic", "_Bool", "_Complex",
+    "_Generic", "_Imaginary", "_Noreturn", "_Static_assert", "_Thread_local",
+    "alignas", "alignof", "bool", "constexpr", "false", "nullptr", "static_assert",
+    "thread_local", "true", "typeof"
+];
+
+// longest first so `<<=` wins over `<<` and `<`
+const C_OPERATORS: [&str; 39] = [
+    "<<=", ">>=", "->*", "<=>", "...",
+    "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||",
+    "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "+", "-", "*", "/", "%", "&", "|", "^", "~", "!", "=", "<", ">", "?", "#"
+];
+
+// the type name follows these keywords, e.g. `struct point` or `template <typename T>`
+const TYPE_INTRODUCERS: [&str; 7] = ["class", "struct", "union", "enum", "typename", "namespace", "concept"];
+
+// directives whose argument is a header path
+const INCLUDE_DIRECTIVES: [&str; 3] = ["include", "include_next", "import"];
+
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+// what differs between the languages sharing the C tokenizer
+pub struct CFamilyDialect {
+    pub language: &'static str,
+    pub keywords: &'static [&'static str],
+    pub raw_strings: bool, // C++11 R"delimiter(...)delimiter"
+}
+
+pub const C_DIALECT: CFamilyDialect = CFamilyDialect {
+    language: "c",
+    keywords: &C_KEYWORDS,
+    raw_strings: false,
+};
+
+// `#` and the directive name, e.g. `#include` or `#  define`.
+// Returns true when a header path follows.
+fn handle_preprocessor(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let start_pos = *pos;
+    *pos += 1; // Move past the `#`
+
+    while *pos < input_chars.len() && (input_chars[*pos] == ' ' || input_chars[*pos] == '\t') {
+        *pos += 1;
+    }
+    let name_pos = *pos;
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
+    let name: String = input_chars[name_pos..*pos].iter().collect();
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Preprocessor, &value));
+
+    INCLUDE_DIRECTIVES.contains(&name.as_str())
+}
+
+// `<stdio.h>` after an include directive
+fn handle_header_path(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1; // Move past the `<`
+    while *pos < input_chars.len() && input_chars[*pos] != '>' {
+        *pos += 1;
+    }
+    *pos = (*pos + 1).min(input_chars.len()); // Move past the `>`
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::StringLiteral, &value));
+}
+
+// length of an encoding prefix (L, u, U, u8) or raw string prefix (R, LR, u8R, ...)
+// when a string or character literal starts at `pos`
+fn string_prefix_len(input_chars: &[char], pos: usize, raw_strings: bool) -> Option<usize> {
+    let mut end = pos;
+    while end < input_chars.len() && input_chars[end].is_alphanumeric() && end - pos < 3 {
+        end += 1;
+    }
+    let prefix: String = input_chars[pos..end].iter().collect();
+    let quote = *input_chars.get(end)?;
+
+    let is_raw = raw_strings && quote == '"' && matches!(prefix.as_str(), "R" | "LR" | "uR" | "UR" | "u8R");
+    let is_encoding = (quote == '"' || quote == '\'') && matches!(prefix.as_str(), "L" | "u" | "U" | "u8");
+    if is_raw || is_encoding {
+        Some(end - pos)
+    } else {
+        None
+    }
+}
+
+// strings and character literals with an optional prefix of `prefix_len` characters.
+// Raw strings may span lines, other literals only continue through a trailing backslash.
+fn handle_string_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    prefix_len: usize, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_pos = *pos;
+    *pos += prefix_len;
+    let raw = prefix_len > 0 && input_chars[*pos - 1] == 'R';
+    let quote = input_chars[*pos];
+    *pos += 1; // Move past the opening quote
+
+    if raw {
+        let delimiter_pos = *pos;
+        while *pos < input_chars.len() && input_chars[*pos] != '(' && !input_chars[*pos].is_whitespace() {
+            *pos += 1;
+        }
+        let delimiter: String = format!("){}\"", input_chars[delimiter_pos..*pos].iter().collect::<String>());
+
+        if input_chars.get(*pos) == Some(&'(') {
+            *pos += 1;
+            if !scan_string_body(input_chars, pos, &delimiter, true) {
+                *state = LineState::InString { delimiter, raw: true };
+            }
+        }
+    } else {
+        let delimiter = quote.to_string();
+        if !scan_string_body(input_chars, pos, &delimiter, false) && input_chars.last() == Some(&'\\') {
+            *state = LineState::InString { delimiter, raw: false };
+        }
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::StringLiteral, &value));
+}
+
+// decimal, hex (0x), binary (0b) and octal integers with `'` digit separators,
+// decimal and hex floats with exponents, then any suffix like `ull`, `f` or a
+// C++ user-defined literal suffix like `ms`
+fn handle_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    let prefix = input_chars.get(*pos + 1).map(|ch| ch.to_ascii_lowercase());
+
+    let (base, exponent) = match (input_chars[*pos], prefix) {
+        ('0', Some('x')) => (16, Some('p')),
+        ('0', Some('b')) => (2, None),
+        _ => (10, Some('e')),
+    };
+    if base != 10 {
+        *pos += 2; // Move past the base prefix
+    }
+
+    let is_digit = |index: usize| match input_chars.get(index) {
+        Some('\'') => input_chars.get(index + 1).is_some_and(|ch| ch.is_digit(base)),
+        Some(ch) => ch.is_digit(base),
+        None => false,
+    };
+    while is_digit(*pos) {
+        *pos += 1;
+    }
+    if base != 2 && input_chars.get(*pos) == Some(&'.') {
+        *pos += 1;
+        while is_digit(*pos) {
+            *pos += 1;
+        }
+    }
+    if exponent.is_some() && input_chars.get(*pos).map(|ch| ch.to_ascii_lowercase()) == exponent {
+        *pos += 1;
+        if matches!(input_chars.get(*pos), Some('+') | Some('-')) {
+            *pos += 1;
+        }
+        while *pos < input_chars.len() && input_chars[*pos].is_ascii_digit() {
+            *pos += 1;
+        }
+    }
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Number, &value));
+}
+
+// like scanner::handle_identifier, but the name after `struct`, `class`, `typename`, ...
+// is a class name whatever its case
+fn handle_identifier(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &CFamilyDialect, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let next_char = input_chars.get(*pos).unwrap_or(&' ');
+    let after_type_introducer = tokens.iter()
+        .rev()
+        .find(|token| token.token_type != TokenType::Whitespace)
+        .is_some_and(|token| token.token_type == TokenType::Keyword && TYPE_INTRODUCERS.contains(&token.value.as_str()));
+
+    let token_type = if dialect.keywords.contains(&value.as_str()) {
+        TokenType::Keyword
+    } else if after_type_introducer {
+        TokenType::ClassName
+    } else if *next_char == '(' {
+        TokenType::FunctionName
+    } else if value.chars().next().unwrap().is_uppercase() {
+        TokenType::ClassName
+    } else {
+        TokenType::Identifier
+    };
+    tokens.push(create_token(theme, dialect.language, token_type, &value));
+}
+
+fn handle_operator(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let operator = C_OPERATORS.iter()
+        .find(|operator| input_chars[*pos..].starts_with(&operator.chars().collect::<Vec<char>>()))
+        .expect("called on an operator character");
+    *pos += operator.len();
+    tokens.push(create_token(theme, language, TokenType::Operator, operator));
+}
+
+// tokenizer shared by C and C++ (see cpp.rs)
+pub fn tokenize_c_family_code(
+    theme: &Theme, 
+    dialect: &CFamilyDialect, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+    let language = dialect.language;
+    let mut expect_header_path = false;
+
+    handle_line_state(
+        &mut tokens, theme, language, Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+            continue;
+        }
+        let header_path = std::mem::take(&mut expect_header_path);
+
+        if current_char == '/' && next_char == '/' {
+            handle_line_comment(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && next_char == '*' {
+            handle_block_comment(&mut tokens, theme, language, &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        // a directive is the first thing on its line
+        else if current_char == '#' && tokens.iter().all(|token| token.token_type == TokenType::Whitespace) {
+            expect_header_path = handle_preprocessor(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '<' && header_path {
+            handle_header_path(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '"' || current_char == '\'' {
+            handle_string_literal(&mut tokens, theme, language, 0, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char.is_ascii_digit() || (current_char == '.' && next_char.is_ascii_digit()) {
+            handle_number(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char.is_alphabetic() || current_char == '_' {
+            match string_prefix_len(input_chars.as_slice(), pos, dialect.raw_strings) {
+                Some(prefix_len) => handle_string_literal(
+                    &mut tokens, theme, language, prefix_len, 
+                    input_chars.as_slice(), &mut pos, &mut state
+                ),
+                None => handle_identifier(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos),
+            }
+        }
+        else if current_char == ':' && next_char == ':' {
+            tokens.push(create_token(theme, language, TokenType::Punctuation, "::"));
+            pos += 2;
+        }
+        else if current_char == '.' && !input_chars[pos..].starts_with(&['.', '.', '.']) {
+            handle_punctuation(&mut tokens, theme, language, current_char, &mut pos);
+        }
+        else if "+-*/%&|^~!=<>?#.".contains(current_char) {
+            handle_operator(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if ",;:()[]{}".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, language, current_char, &mut pos);
+        }
+        else {
+            handle_unknown(&mut tokens, theme, language, current_char, &mut pos);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct CLanguage;
+
+impl Language for CLanguage {
+    fn name(&self) -> &str {
+        "c"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["c", "h"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_c_family_code(theme, &C_DIALECT, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_c_preprocessor() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let line_content = tokenize("c", &theme, "#include <stdio.h>", &LineState::Normal);
+        assert_eq!(line_content.tokens[0].token_type, TokenType::Preprocessor);
+        assert_eq!(line_content.tokens[0].value, "#include");
+        assert_eq!(line_content.tokens[2].token_type, TokenType::StringLiteral);
+        assert_eq!(line_content.tokens[2].value, "<stdio.h>");
+
+        let line_content = tokenize("c", &theme, "  # define MAX(a, b) ((a) > (b) ? 'a' : 0x1FUL)", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of("# define"), TokenType::Preprocessor);
+        assert_eq!(token_of(">"), TokenType::Operator);
+        assert_eq!(token_of("'a'"), TokenType::StringLiteral);
+        assert_eq!(token_of("0x1FUL"), TokenType::Number);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, LineState, BlockCommentSyntax};
+use crate::syntax_highlight::languages::{Language, CFamilyDialect, tokenize_c_family_code};
+
+const CPP_KEYWORDS: [&str; 92] = [
+    "alignas", "alignof", "and", "and_eq", "asm", "auto", "bitand", "bitor",
+    "bool", "break", "case", "catch", "char", "char8_t", "char16_t", "char32_t",
+    "class", "compl", "concept", "const", "consteval", "constexpr", "constinit",
+    "const_cast", "continue", "co_await", "co_return", "co_yield", "decltype",
+    "default", "delete", "do", "double", "dynamic_cast", "else", "enum",
+    "explicit", "export", "extern", "false", "final", "float", "for", "friend",
+    "goto", "if", "import", "inline", "int", "long", "module", "mutable",
+    "namespace", "new", "noexcept", "not", "not_eq", "nullptr", "operator", "or",
+    "or_eq", "override", "private", "protected", "public", "register",
+    "reinterpret_cast", "requires", "return", "short", "signed", "sizeof",
+    "static", "static_assert", "static_cast", "struct", "switch", "template",
+    "this", "thread_local", "throw", "true", "try", "typedef", "typeid",
+    "typename", "union", "unsigned", "using", "virtual", "void", "volatile"
+];
+
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+pub const CPP_DIALECT: CFamilyDialect = CFamilyDialect {
+    language: "cpp",
+    keywords: &CPP_KEYWORDS,
+    raw_strings: true,
+};
+
+pub struct CppLanguage;
+
+impl Language for CppLanguage {
+    fn name(&self) -> &str {
+        "cpp"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["cpp", "hpp", "cc", "hh", "cxx", "hxx"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_c_family_code(theme, &CPP_DIALECT, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+    use crate::syntax_highlight::TokenType;
+
+    #[test]
+    fn test_tokenize_cpp() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let line_content = tokenize(
+            "cpp", 
+            &theme, 
+            "template <typename value_type> auto n = 1'000'000ull + u8\"x\";", 
+            &LineState::Normal
+        );
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of("template"), TokenType::Keyword);
+        assert_eq!(token_of("value_type"), TokenType::ClassName);
+        assert_eq!(token_of("1'000'000ull"), TokenType::Number);
+        assert_eq!(token_of("u8\"x\""), TokenType::StringLiteral);
+
+        // raw string literals continue until `)delimiter"`
+        let first = tokenize("cpp", &theme, "auto s = R\"sql(select \"a\"", &LineState::Normal);
+        assert_eq!(first.state, LineState::InString { delimiter: ")sql\"".to_string(), raw: true });
+
+        let second = tokenize("cpp", &theme, "from t)\" )sql\";", &first.state);
+        assert_eq!(second.tokens[0].value, "from t)\" )sql\"");
+        assert_eq!(second.state, LineState::Normal);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '-' || ch == '_' || !ch.is_ascii()
+}
+
+fn is_ident_start(input_chars: &[char], pos: usize) -> bool {
+    match input_chars.get(pos) {
+        Some('-') => input_chars.get(pos + 1).is_some_and(|ch| *ch == '-' || ch.is_alphabetic()),
+        Some(ch) => ch.is_alphabetic() || *ch == '_' || !ch.is_ascii(),
+        None => false,
+    }
+}
+
+fn scan_ident(input_chars: &[char], pos: &mut usize) {
+    while *pos < input_chars.len() && is_ident_char(input_chars[*pos]) {
+        *pos += 1;
+    }
+}
+
+// `name:` is a property unless the line goes on to open a block (`a:hover {`)
+// or continues a selector list (`a:hover,`)
+fn is_property(input_chars: &[char], pos: usize) -> bool {
+    if peek_non_whitespace(input_chars, pos) != Some(':') {
+        return false;
+    }
+    let rest: String = input_chars[pos..].iter().collect();
+    let colon = rest.find(':').unwrap();
+    !rest[colon..].starts_with("::") && !rest.contains('{') && !rest.trim_end().ends_with(',')
+}
+
+// 10px, 1.5em, -2, 50%, .5s
+fn handle_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    if input_chars[*pos] == '-' || input_chars[*pos] == '+' {
+        *pos += 1;
+    }
+    while *pos < input_chars.len() && (input_chars[*pos].is_ascii_digit() || input_chars[*pos] == '.') {
+        *pos += 1;
+    }
+    // unit
+    if input_chars.get(*pos) == Some(&'%') {
+        *pos += 1;
+    } else {
+        while *pos < input_chars.len() && input_chars[*pos].is_alphabetic() {
+            *pos += 1;
+        }
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "css", TokenType::Number, &value));
+}
+
+// `@media`, `@import`, ...
+fn handle_at_rule(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1; // Move past the `@`
+    scan_ident(input_chars, pos);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "css", TokenType::Preprocessor, &value));
+}
+
+// `.class` and `#id` selectors, `#fff` colors in values
+fn handle_prefixed_name(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    token_type: TokenType, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1; // Move past the `.` or `#`
+    scan_ident(input_chars, pos);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "css", token_type, &value));
+}
+
+// `:hover`, `::before`
+fn handle_pseudo(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && input_chars[*pos] == ':' {
+        *pos += 1;
+    }
+    scan_ident(input_chars, pos);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "css", TokenType::FunctionName, &value));
+}
+
+fn handle_string_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    let delimiter = input_chars[*pos].to_string();
+    *pos += 1; // Move past the opening quote
+    scan_string_body(input_chars, pos, &delimiter, false);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "css", TokenType::StringLiteral, &value));
+}
+
+// properties, functions like `rgb(`, value keywords and element selectors.
+// An unquoted `url(...)` argument is a string.
+fn handle_ident(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    in_value: bool, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    scan_ident(input_chars, pos);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+
+    if input_chars.get(*pos) == Some(&'(') {
+        tokens.push(create_token(theme, "css", TokenType::FunctionName, &value));
+        let quoted = matches!(peek_non_whitespace(input_chars, *pos + 1), Some('"') | Some('\''));
+        if value.eq_ignore_ascii_case("url") && !quoted {
+            handle_punctuation(tokens, theme, "css", '(', pos);
+            let url_start = *pos;
+            while *pos < input_chars.len() && input_chars[*pos] != ')' {
+                *pos += 1;
+            }
+            let url: String = input_chars[url_start..*pos].iter().collect();
+            tokens.push(create_token(theme, "css", TokenType::StringLiteral, &url));
+        }
+        return;
+    }
+    // media features, `@media (max-width: 600px) {`
+    let in_parens = tokens.last().is_some_and(|token| token.value == "(");
+    let is_feature = in_parens && peek_non_whitespace(input_chars, *pos) == Some(':');
+    let token_type = if (!in_value && is_property(input_chars, *pos)) || is_feature {
+        TokenType::Identifier
+    } else {
+        TokenType::Keyword
+    };
+    tokens.push(create_token(theme, "css", token_type, &value));
+}
+
+pub fn tokenize_css_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+    // after a property's `:` until `;`, only tracked within the line
+    let mut in_value = false;
+
+    handle_line_state(
+        &mut tokens, theme, "css", Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+        let last_token_type = tokens.iter()
+            .rev()
+            .find(|token| token.token_type != TokenType::Whitespace)
+            .map(|token| token.token_type);
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "css", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && next_char == '*' {
+            handle_block_comment(&mut tokens, theme, "css", &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '"' || current_char == '\'' {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '@' {
+            handle_at_rule(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '#' {
+            let token_type = if in_value { TokenType::Number } else { TokenType::ClassName };
+            handle_prefixed_name(&mut tokens, theme, token_type, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char.is_ascii_digit()
+            || ("+-.".contains(current_char) && next_char.is_ascii_digit())
+        {
+            handle_number(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '.' && is_ident_start(input_chars.as_slice(), pos + 1) {
+            handle_prefixed_name(&mut tokens, theme, TokenType::ClassName, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == ':' && !in_value && last_token_type == Some(TokenType::Identifier) {
+            handle_punctuation(&mut tokens, theme, "css", current_char, &mut pos);
+            in_value = true;
+        }
+        else if current_char == ':' && !in_value {
+            handle_pseudo(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if is_ident_start(input_chars.as_slice(), pos) {
+            handle_ident(&mut tokens, theme, in_value, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '!' && is_ident_start(input_chars.as_slice(), pos + 1) {
+            // !important
+            handle_prefixed_name(&mut tokens, theme, TokenType::Keyword, input_chars.as_slice(), &mut pos);
+        }
+        else if ";{}".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, "css", current_char, &mut pos);
+            in_value = false;
+        }
+        else if ",:()[]".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, "css", current_char, &mut pos);
+        }
+        else if ">+~*=|^$/".contains(current_char) {
+            handle_operator(&mut tokens, theme, "css", current_char, input_chars.as_slice(), &mut pos);
+        }
+        else {
+            handle_unknown(&mut tokens, theme, "css", current_char, &mut pos);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct CssLanguage;
+
+impl Language for CssLanguage {
+    fn name(&self) -> &str {
+        "css"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["css"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        None
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_css_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize_lines;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_css() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [".card > a:hover, #main {", "  margin: -2px 1.5em !important;", "  color: #fff;", "}", "@media (max-width: 600px) {"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("css", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of(0, ".card"), TokenType::ClassName);
+        assert_eq!(token_of(0, "a"), TokenType::Keyword);
+        assert_eq!(token_of(0, ":hover"), TokenType::FunctionName);
+        assert_eq!(token_of(0, "#main"), TokenType::ClassName);
+        assert_eq!(token_of(1, "margin"), TokenType::Identifier);
+        assert_eq!(token_of(1, "-2px"), TokenType::Number);
+        assert_eq!(token_of(1, "1.5em"), TokenType::Number);
+        assert_eq!(token_of(1, "!important"), TokenType::Keyword);
+        // in a value `#` starts a color, not an id selector
+        assert_eq!(token_of(2, "#fff"), TokenType::Number);
+        assert_eq!(token_of(4, "@media"), TokenType::Preprocessor);
+        assert_eq!(token_of(4, "max-width"), TokenType::Identifier);
+    }
+}
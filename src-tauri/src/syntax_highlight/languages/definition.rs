@@ -0,0 +1,446 @@
+// Languages described by JSON files in $HOME/BaCE/languages instead of Rust code.
+// Every *.json file in that folder holds one LanguageDefinition, for example
+//
+// {
+//   "name": "go",
+//   "extensions": ["go"],
+//   "keywords": ["func", "package", "import", "return"],
+//   "lineComment": "//",
+//   "blockComment": { "open": "/*", "close": "*/" },
+//   "strings": [
+//     { "delimiter": "\"" },
+//     { "delimiter": "`", "raw": true, "multiline": true }
+//   ],
+//   "numberPattern": "0[xX][0-9a-fA-F_]+|[0-9][0-9_]*(\\.[0-9_]+)?",
+//   "rules": [{ "pattern": "\\b(int|string|bool)\\b", "tokenType": "ClassName" }]
+// }
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::LanguageDefinitionError;
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+fn default_number_pattern() -> String {
+    "[0-9]+(\\.[0-9]+)?".to_string()
+}
+
+fn default_operators() -> String {
+    "+-*/=%<>!&|^~?".to_string()
+}
+
+fn default_punctuation() -> String {
+    ",.:;()[]{}".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCommentDefinition {
+    pub open: String,
+    pub close: String,
+    #[serde(default)]
+    pub nested: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StringDefinition {
+    pub delimiter: String,
+    #[serde(default)]
+    pub raw: bool, // no backslash escapes
+    #[serde(default)]
+    pub multiline: bool, // string may continue on the next lines
+}
+
+// regex matched at the current position before numbers, identifiers and keywords
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRuleDefinition {
+    pub pattern: String,
+    pub token_type: TokenType,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub filenames: Vec<String>,
+    #[serde(default)]
+    pub interpreters: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub line_comment: Option<String>,
+    #[serde(default)]
+    pub block_comment: Option<BlockCommentDefinition>,
+    #[serde(default)]
+    pub strings: Vec<StringDefinition>,
+    #[serde(default = "default_number_pattern")]
+    pub number_pattern: String,
+    #[serde(default)]
+    pub rules: Vec<TokenRuleDefinition>,
+    #[serde(default = "default_operators")]
+    pub operators: String,
+    #[serde(default = "default_punctuation")]
+    pub punctuation: String,
+}
+
+// LanguageDefinition with its patterns compiled, ready to tokenize
+#[derive(Debug)]
+pub struct DefinedLanguage {
+    definition: LanguageDefinition,
+    number_regex: Regex,
+    rules: Vec<(Regex, TokenType)>,
+    // the markers as chars, compared against the line at every position
+    line_comment: Option<Vec<char>>,
+    block_comment_open: Option<Vec<char>>,
+    // delimiter of each of definition.strings
+    string_delimiters: Vec<Vec<char>>,
+}
+
+// anchor the pattern so it only matches at the current position
+fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    Regex::new(&format!("^(?:{})", pattern)).map_err(|e| format!("{e}"))
+}
+
+impl DefinedLanguage {
+    pub fn compile(mut definition: LanguageDefinition) -> Result<DefinedLanguage, String> {
+        if definition.name.trim().is_empty() {
+            return Err("language name is empty".to_string());
+        }
+        if definition.strings.iter().any(|string| string.delimiter.is_empty()) {
+            return Err("string delimiter is empty".to_string());
+        }
+        if let Some(ref block_comment) = definition.block_comment {
+            if block_comment.open.is_empty() || block_comment.close.is_empty() {
+                return Err("block comment markers are empty".to_string());
+            }
+        }
+        let number_regex = compile_pattern(&definition.number_pattern)?;
+        let rules = definition.rules.iter()
+            .map(|rule| Ok((compile_pattern(&rule.pattern)?, rule.token_type)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // longest delimiter first so """ wins over "
+        definition.strings.sort_by_key(|string| std::cmp::Reverse(string.delimiter.len()));
+
+        let line_comment = definition.line_comment.as_ref().map(|marker| marker.chars().collect());
+        let block_comment_open = definition.block_comment.as_ref().map(|block_comment| block_comment.open.chars().collect());
+        let string_delimiters = definition.strings.iter().map(|string| string.delimiter.chars().collect()).collect();
+
+        Ok(DefinedLanguage { definition, number_regex, rules, line_comment, block_comment_open, string_delimiters })
+    }
+
+    fn handle_string_literal(
+        &self, 
+        tokens: &mut Vec<Token>, 
+        theme: &Theme, 
+        string: &StringDefinition, 
+        input_chars: &[char], 
+        pos: &mut usize, 
+        state: &mut LineState
+    ) {
+        let start_pos = *pos;
+        *pos += string.delimiter.chars().count(); // Move past the opening delimiter
+
+        let closed = scan_string_body(input_chars, pos, &string.delimiter, string.raw);
+        if !closed && string.multiline {
+            *state = LineState::InString { delimiter: string.delimiter.clone(), raw: string.raw };
+        }
+        let value: String = input_chars[start_pos..*pos].iter().collect();
+        tokens.push(create_token(theme, self.name(), TokenType::StringLiteral, &value));
+    }
+
+    // try `regex` on `rest`, the input from `pos` on. On a match push a token of `token_type` and move past it
+    fn handle_pattern(
+        &self, 
+        tokens: &mut Vec<Token>, 
+        theme: &Theme, 
+        regex: &Regex, 
+        token_type: TokenType, 
+        rest: &str, 
+        pos: &mut usize
+    ) -> bool {
+        let Some(found) = regex.find(rest) else {
+            return false;
+        };
+        if found.as_str().is_empty() {
+            return false;
+        }
+        *pos += found.as_str().chars().count();
+        tokens.push(create_token(theme, self.name(), token_type, found.as_str()));
+        true
+    }
+}
+
+impl Language for DefinedLanguage {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        self.definition.extensions.iter().map(|ext| ext.as_str()).collect()
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        self.definition.filenames.iter().map(|name| name.as_str()).collect()
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        self.definition.interpreters.iter().map(|name| name.as_str()).collect()
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        self.definition.line_comment.as_deref()
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        self.definition.block_comment.as_ref().map(|block_comment| BlockCommentSyntax {
+            open: &block_comment.open,
+            close: &block_comment.close,
+            nested: block_comment.nested,
+        })
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        let mut state = state.clone();
+        let input_chars: Vec<char> = input.chars().collect();
+        let language = self.name();
+        let block_comment = self.block_comment();
+
+        // byte offset of every char so regex rules can run on the &str
+        let mut byte_offsets: Vec<usize> = input.char_indices().map(|(offset, _)| offset).collect();
+        byte_offsets.push(input.len());
+
+        handle_line_state(
+            &mut tokens, theme, language, block_comment.as_ref(),
+            input_chars.as_slice(), &mut pos, &mut state
+        );
+
+        while pos < input_chars.len() {
+            let current_char = input_chars[pos];
+            let rest = &input_chars[pos..];
+
+            if current_char.is_whitespace() {
+                handle_whitespace(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+                continue;
+            }
+            if let Some(ref line_comment) = self.line_comment {
+                if rest.starts_with(line_comment) {
+                    handle_line_comment(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+                    continue;
+                }
+            }
+            if let (Some(syntax), Some(open)) = (&block_comment, &self.block_comment_open) {
+                if rest.starts_with(open) {
+                    handle_block_comment(&mut tokens, theme, language, syntax, input_chars.as_slice(), &mut pos, &mut state);
+                    continue;
+                }
+            }
+            let string = self.string_delimiters.iter()
+                .position(|delimiter| rest.starts_with(delimiter))
+                .map(|index| &self.definition.strings[index]);
+            if let Some(string) = string {
+                self.handle_string_literal(&mut tokens, theme, string, input_chars.as_slice(), &mut pos, &mut state);
+                continue;
+            }
+            let rest_str = &input[byte_offsets[pos]..];
+            let rule_matched = self.rules.iter().any(|(regex, token_type)| {
+                self.handle_pattern(&mut tokens, theme, regex, *token_type, rest_str, &mut pos)
+            });
+            if rule_matched {
+                continue;
+            }
+
+            if current_char.is_ascii_digit() && self.handle_pattern(
+                &mut tokens, theme, &self.number_regex, TokenType::Number, rest_str, &mut pos
+            ) {
+                continue;
+            }
+            if current_char.is_alphabetic() || current_char == '_' {
+                handle_identifier(&mut tokens, theme, language, &self.definition.keywords, input_chars.as_slice(), &mut pos);
+            }
+            else if self.definition.operators.contains(current_char) {
+                handle_operator(&mut tokens, theme, language, current_char, input_chars.as_slice(), &mut pos);
+            }
+            else if self.definition.punctuation.contains(current_char) {
+                handle_punctuation(&mut tokens, theme, language, current_char, &mut pos);
+            }
+            else {
+                handle_unknown(&mut tokens, theme, language, current_char, &mut pos);
+            }
+        }
+
+        (tokens, state)
+    }
+}
+
+pub fn get_languages_dir_path() -> PathBuf {
+    let mut dir_path = PathBuf::from(env::var("HOME").unwrap());
+    dir_path.push("BaCE/languages");
+    dir_path
+}
+
+pub fn read_language_definition_file(file_path: &Path) -> Result<DefinedLanguage, LanguageDefinitionError> {
+    let file_name = file_path.to_string_lossy().into_owned();
+
+    let data = fs::read_to_string(file_path)
+        .map_err(|_| LanguageDefinitionError::ReadDefinitionFileFail(file_name.clone()))?;
+
+    let definition: LanguageDefinition = serde_json::from_str(&data)
+        .map_err(|e| LanguageDefinitionError::InvalidDefinition(file_name.clone(), format!("{e}")))?;
+
+    DefinedLanguage::compile(definition)
+        .map_err(|e| LanguageDefinitionError::InvalidDefinition(file_name, e))
+}
+
+// Load every *.json definition in `dir_path`.
+// A broken file doesn't stop the others from loading, its error is returned next to the languages.
+pub fn load_language_definitions(dir_path: &Path) -> (Vec<DefinedLanguage>, Vec<LanguageDefinitionError>) {
+    let mut languages: Vec<DefinedLanguage> = Vec::new();
+    let mut errors: Vec<LanguageDefinitionError> = Vec::new();
+
+    // the languages folder is optional
+    if !dir_path.exists() {
+        return (languages, errors);
+    }
+    let entries = match fs::read_dir(dir_path) {
+        Ok(entries) => entries,
+        Err(_) => {
+            errors.push(LanguageDefinitionError::ReadLanguagesDirectoryFail);
+            return (languages, errors);
+        }
+    };
+    let mut file_paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    file_paths.sort();
+
+    for file_path in file_paths {
+        match read_language_definition_file(&file_path) {
+            Ok(language) => languages.push(language),
+            Err(e) => errors.push(e),
+        }
+    }
+    (languages, errors)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    const GO_DEFINITION: &str = r#"{
+        "name": "go",
+        "extensions": ["go"],
+        "keywords": ["func", "package", "return"],
+        "lineComment": "//",
+        "blockComment": { "open": "/*", "close": "*/" },
+        "strings": [
+            { "delimiter": "\"" },
+            { "delimiter": "`", "raw": true, "multiline": true }
+        ],
+        "numberPattern": "0[xX][0-9a-fA-F_]+|[0-9][0-9_]*",
+        "rules": [{ "pattern": "\\b(int|string)\\b", "tokenType": "ClassName" }]
+    }"#;
+
+    fn go_language() -> DefinedLanguage {
+        DefinedLanguage::compile(serde_json::from_str(GO_DEFINITION).unwrap()).unwrap()
+    }
+
+    fn token_types(tokens: &[Token]) -> Vec<TokenType> {
+        tokens.iter()
+            .filter(|token| token.token_type != TokenType::Whitespace)
+            .map(|token| token.token_type)
+            .collect()
+    }
+
+    #[test]
+    fn test_defined_language_tokenize_line() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let (tokens, state) = go_language().tokenize_line(
+            &theme,
+            "func f(x int) string { return 0xFF_FF } // done",
+            &LineState::Normal
+        );
+
+        assert_eq!(state, LineState::Normal);
+        assert_eq!(
+            token_types(&tokens),
+            vec![
+                TokenType::Keyword, TokenType::FunctionName, TokenType::Punctuation,
+                TokenType::Identifier, TokenType::ClassName, TokenType::Punctuation,
+                TokenType::ClassName, TokenType::Punctuation, TokenType::Keyword,
+                TokenType::Number, TokenType::Punctuation, TokenType::Comment
+            ]
+        );
+        assert!(tokens.iter().any(|token| token.value == "0xFF_FF"));
+    }
+
+    #[test]
+    fn test_defined_language_multiline_raw_string() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let language = go_language();
+
+        let (_, state) = language.tokenize_line(&theme, "s := `first \\", &LineState::Normal);
+        assert_eq!(state, LineState::InString { delimiter: "`".to_string(), raw: true });
+
+        let (tokens, state) = language.tokenize_line(&theme, "second` + x", &state);
+        assert_eq!(tokens[0].value, "second`");
+        assert_eq!(state, LineState::Normal);
+    }
+
+    #[test]
+    fn test_compile_invalid_pattern() {
+        let mut definition: LanguageDefinition = serde_json::from_str(GO_DEFINITION).unwrap();
+        definition.rules[0].pattern = "(unclosed".to_string();
+        assert!(DefinedLanguage::compile(definition).is_err());
+    }
+
+    #[test]
+    fn test_load_language_definitions() {
+        let temp_dir = tempdir().unwrap();
+
+        let mut go_file = File::create(temp_dir.path().join("go.json")).unwrap();
+        go_file.write_all(GO_DEFINITION.as_bytes()).unwrap();
+        let mut broken_file = File::create(temp_dir.path().join("broken.json")).unwrap();
+        broken_file.write_all(b"{ \"extensions\": [] }").unwrap();
+        File::create(temp_dir.path().join("notes.txt")).unwrap();
+
+        let (languages, errors) = load_language_definitions(temp_dir.path());
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages[0].name(), "go");
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], LanguageDefinitionError::InvalidDefinition(_, _)));
+    }
+
+    #[test]
+    fn test_load_language_definitions_missing_dir() {
+        let temp_dir = tempdir().unwrap();
+        let (languages, errors) = load_language_definitions(&temp_dir.path().join("languages"));
+        assert!(languages.is_empty());
+        assert!(errors.is_empty());
+    }
+}
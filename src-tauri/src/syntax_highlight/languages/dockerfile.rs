@@ -0,0 +1,274 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const DOCKERFILE_INSTRUCTIONS: [&str; 18] = [
+    "FROM", "RUN", "CMD", "LABEL", "MAINTAINER", "EXPOSE", "ENV", "ADD", "COPY",
+    "ENTRYPOINT", "VOLUME", "USER", "WORKDIR", "ARG", "ONBUILD", "STOPSIGNAL", "HEALTHCHECK", "SHELL"
+];
+
+// instructions taking a command, either in shell form or as a JSON array (exec form)
+const COMMAND_INSTRUCTIONS: [&str; 4] = ["RUN", "CMD", "ENTRYPOINT", "SHELL"];
+
+// `# syntax=docker/dockerfile:1` and friends, only at the top of the file but
+// nobody writes a comment looking like one elsewhere
+const PARSER_DIRECTIVES: [&str; 3] = ["syntax", "escape", "check"];
+
+fn is_continued(input_chars: &[char]) -> bool {
+    input_chars.iter().rev().find(|ch| !ch.is_whitespace()) == Some(&'\\')
+}
+
+fn is_parser_directive(input_chars: &[char], pos: usize) -> bool {
+    let comment: String = input_chars[pos + 1..].iter().collect();
+    let Some((name, _)) = comment.split_once('=') else {
+        return false;
+    };
+    PARSER_DIRECTIVES.contains(&name.trim().to_lowercase().as_str())
+}
+
+// Hand the rest of the line to the command's language. A command goes on while its
+// lines end with a backslash or the embedded language has a construct open (heredocs).
+fn handle_command(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    embedded_state: &LineState
+) -> LineState {
+    let embedded_state = tokenize_embedded(
+        tokens, theme, language, input_chars, pos, input_chars.len(), embedded_state
+    );
+    if is_continued(input_chars) || embedded_state != LineState::Normal {
+        // the end of the region is decided by the rule above, not a marker
+        LineState::InEmbedded { language: language.to_string(), end: String::new(), state: Box::new(embedded_state) }
+    } else {
+        LineState::Normal
+    }
+}
+
+// `$VAR` and `${VAR:-default}`
+fn handle_variable(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1; // Move past the `$`
+    if input_chars.get(*pos) == Some(&'{') {
+        while *pos < input_chars.len() && input_chars[*pos] != '}' {
+            *pos += 1;
+        }
+        *pos = (*pos + 1).min(input_chars.len());
+    } else {
+        while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+            *pos += 1;
+        }
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "dockerfile", TokenType::ClassName, &value));
+}
+
+// An argument word: `--flag`, `KEY=` of ENV/ARG/LABEL, `AS` of FROM, a port number or
+// anything else (images, paths, values), which is a string.
+fn handle_argument(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && !input_chars[*pos].is_whitespace() && !"=$\"'".contains(input_chars[*pos]) {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+
+    let token_type = if value.starts_with("--") || input_chars.get(*pos) == Some(&'=') {
+        TokenType::Identifier
+    } else if value.eq_ignore_ascii_case("as") {
+        TokenType::Keyword
+    } else if value.starts_with(|ch: char| ch.is_ascii_digit()) && value.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '/' || ch == '-') {
+        // 8080, 8080/tcp, 3000-3005
+        TokenType::Number
+    } else if value == "\\" {
+        TokenType::Punctuation
+    } else {
+        TokenType::StringLiteral
+    };
+    tokens.push(create_token(theme, "dockerfile", token_type, &value));
+}
+
+fn handle_arguments(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    while *pos < input_chars.len() {
+        let current_char = input_chars[*pos];
+
+        if current_char.is_whitespace() {
+            handle_whitespace(tokens, theme, "dockerfile", input_chars, pos);
+        }
+        else if current_char == '"' || current_char == '\'' {
+            let start_pos = *pos;
+            *pos += 1; // Move past the opening quote
+            scan_string_body(input_chars, pos, &current_char.to_string(), current_char == '\'');
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+            tokens.push(create_token(theme, "dockerfile", TokenType::StringLiteral, &value));
+        }
+        else if current_char == '$' {
+            handle_variable(tokens, theme, input_chars, pos);
+        }
+        else if current_char == '=' {
+            handle_operator(tokens, theme, "dockerfile", current_char, input_chars, pos);
+        }
+        else {
+            handle_argument(tokens, theme, input_chars, pos);
+        }
+    }
+}
+
+pub fn tokenize_dockerfile_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    // a continued RUN, CMD, ... line
+    if let LineState::InEmbedded { language, state: embedded_state, .. } = &state {
+        let state = handle_command(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, embedded_state);
+        return (tokens, state);
+    }
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "dockerfile", input_chars.as_slice(), &mut pos);
+            continue;
+        }
+        if current_char == '#' {
+            let token_type = if is_parser_directive(input_chars.as_slice(), pos) { TokenType::Preprocessor } else { TokenType::Comment };
+            let value: String = input_chars[pos..].iter().collect();
+            tokens.push(create_token(theme, "dockerfile", token_type, &value));
+            break;
+        }
+
+        let word_length = input_chars[pos..].iter().take_while(|ch| ch.is_ascii_alphabetic()).count();
+        let word: String = input_chars[pos..pos + word_length].iter().collect::<String>().to_uppercase();
+        // lines not starting with an instruction continue the previous one's arguments
+        if !DOCKERFILE_INSTRUCTIONS.contains(&word.as_str()) {
+            handle_arguments(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+            break;
+        }
+
+        let value: String = input_chars[pos..pos + word_length].iter().collect();
+        tokens.push(create_token(theme, "dockerfile", TokenType::Keyword, &value));
+        pos += word_length;
+
+        // ONBUILD is followed by another instruction
+        if word == "ONBUILD" {
+            continue;
+        }
+        if COMMAND_INSTRUCTIONS.contains(&word.as_str()) {
+            if pos < input_chars.len() && input_chars[pos].is_whitespace() {
+                handle_whitespace(&mut tokens, theme, "dockerfile", input_chars.as_slice(), &mut pos);
+            }
+            let language = if input_chars.get(pos) == Some(&'[') { "json" } else { "shell" };
+            let state = handle_command(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, &LineState::Normal);
+            return (tokens, state);
+        }
+        handle_arguments(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+    }
+
+    (tokens, state)
+}
+
+pub struct DockerfileLanguage;
+
+impl Language for DockerfileLanguage {
+    fn name(&self) -> &str {
+        "dockerfile"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["dockerfile"]
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        vec!["Dockerfile", "Containerfile", "Dockerfile.*", "Containerfile.*"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_dockerfile_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize_lines;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_dockerfile() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "# syntax=docker/dockerfile:1",
+            "FROM rust:1.80 AS builder",
+            "ENV APP_HOME=/app",
+            "RUN cargo build --release && \\",
+            "    strip target/release/app",
+            "EXPOSE 8080/tcp",
+            "CMD [\"./app\", \"--port\", \"8080\"]",
+        ]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("dockerfile", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(line_contents[0].tokens[0].token_type, TokenType::Preprocessor);
+        assert_eq!(token_of(1, "FROM"), TokenType::Keyword);
+        assert_eq!(token_of(1, "rust:1.80"), TokenType::StringLiteral);
+        assert_eq!(token_of(1, "AS"), TokenType::Keyword);
+        assert_eq!(token_of(2, "APP_HOME"), TokenType::Identifier);
+        // RUN is shell, continued on the next line
+        assert_eq!(token_of(3, "cargo"), TokenType::FunctionName);
+        assert_eq!(token_of(3, "&&"), TokenType::Operator);
+        assert_eq!(
+            line_contents[3].state, 
+            LineState::InEmbedded { 
+                language: "shell".to_string(), 
+                end: String::new(), 
+                state: Box::new(LineState::Normal) 
+            }
+        );
+        assert_eq!(token_of(4, "strip"), TokenType::FunctionName);
+        assert_eq!(line_contents[4].state, LineState::Normal);
+        assert_eq!(token_of(5, "8080/tcp"), TokenType::Number);
+        // exec form is a JSON array
+        assert_eq!(token_of(6, "\"./app\""), TokenType::StringLiteral);
+        assert_eq!(token_of(6, "["), TokenType::Punctuation);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const GO_KEYWORDS: [&str; 29] = [
+    "break", "case", "chan", "const", "continue", "default", "defer", "else",
+    "fallthrough", "for", "func", "go", "goto", "if", "import", "interface",
+    "map", "package", "range", "return", "select", "struct", "switch", "type",
+    "var", "true", "false", "nil", "iota"
+];
+
+const GO_BUILTIN_TYPES: [&str; 22] = [
+    "any", "bool", "byte", "comparable", "complex64", "complex128", "error",
+    "float32", "float64", "int", "int8", "int16", "int32", "int64", "rune",
+    "string", "uint", "uint8", "uint16", "uint32", "uint64", "uintptr"
+];
+
+const GO_BUILTIN_FUNCTIONS: [&str; 18] = [
+    "append", "cap", "clear", "close", "complex", "copy", "delete", "imag",
+    "len", "make", "max", "min", "new", "panic", "print", "println", "real",
+    "recover"
+];
+
+// longest first so `<<=` wins over `<<` and `<`
+const GO_OPERATORS: [&str; 47] = [
+    "<<=", ">>=", "&^=", "...",
+    "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<", ">>", "&^", "&&",
+    "||", "<-", "++", "--", "==", "!=", "<=", ">=", ":=",
+    "+", "-", "*", "/", "%", "&", "|", "^", "<", ">", "=", "!", "~",
+    "(", ")", "[", "]", "{", "}", ",", ";", ":"
+];
+
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+// raw strings (`...`) may span lines and have no escapes,
+// interpreted strings ("...") and runes ('...') end on the same line
+fn handle_string_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_char = input_chars[*pos];
+    let start_pos = *pos;
+    *pos += 1; // Move past the opening quote
+
+    let delimiter = start_char.to_string();
+    let raw = start_char == '`';
+    if !scan_string_body(input_chars, pos, &delimiter, raw) && raw {
+        *state = LineState::InString { delimiter, raw };
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "go", TokenType::StringLiteral, &value));
+}
+
+fn is_digit_in_base(ch: char, base: u32) -> bool {
+    ch == '_' || ch.is_digit(base)
+}
+
+// decimal, hex (0x), octal (0o or leading 0), binary (0b) integers with `_` separators,
+// decimal and hex floats with exponents, and the imaginary suffix `i`
+fn handle_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    let prefix = input_chars.get(*pos + 1).map(|ch| ch.to_ascii_lowercase());
+
+    let (base, exponent) = match (input_chars[*pos], prefix) {
+        ('0', Some('x')) => (16, Some('p')),
+        ('0', Some('o')) => (8, None),
+        ('0', Some('b')) => (2, None),
+        _ => (10, Some('e')),
+    };
+    if base != 10 {
+        *pos += 2; // Move past the base prefix
+    }
+
+    while *pos < input_chars.len() && is_digit_in_base(input_chars[*pos], base) {
+        *pos += 1;
+    }
+    // fraction, `..` is never part of a number
+    if (base == 10 || base == 16)
+        && input_chars.get(*pos) == Some(&'.')
+        && input_chars.get(*pos + 1) != Some(&'.')
+    {
+        *pos += 1;
+        while *pos < input_chars.len() && is_digit_in_base(input_chars[*pos], base) {
+            *pos += 1;
+        }
+    }
+    if exponent.is_some() && input_chars.get(*pos).map(|ch| ch.to_ascii_lowercase()) == exponent {
+        *pos += 1;
+        if matches!(input_chars.get(*pos), Some('+') | Some('-')) {
+            *pos += 1;
+        }
+        while *pos < input_chars.len() && is_digit_in_base(input_chars[*pos], 10) {
+            *pos += 1;
+        }
+    }
+    if input_chars.get(*pos) == Some(&'i') {
+        *pos += 1;
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "go", TokenType::Number, &value));
+}
+
+// builtin types are class names and builtin functions are function names even when
+// they are not called, exported identifiers (uppercase first letter) are class names
+fn handle_identifier(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let next_char = input_chars.get(*pos).unwrap_or(&' ');
+
+    let token_type = if GO_KEYWORDS.contains(&value.as_str()) {
+        TokenType::Keyword
+    } else if GO_BUILTIN_TYPES.contains(&value.as_str()) {
+        TokenType::ClassName
+    } else if *next_char == '(' || GO_BUILTIN_FUNCTIONS.contains(&value.as_str()) {
+        TokenType::FunctionName
+    } else if value.chars().next().unwrap().is_uppercase() {
+        TokenType::ClassName
+    } else {
+        TokenType::Identifier
+    };
+    tokens.push(create_token(theme, "go", token_type, &value));
+}
+
+fn handle_operator(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let operator = GO_OPERATORS.iter()
+        .find(|operator| input_chars[*pos..].starts_with(&operator.chars().collect::<Vec<char>>()))
+        .expect("called on an operator character");
+    *pos += operator.len();
+
+    let token_type = if "()[]{},;:".contains(*operator) {
+        TokenType::Punctuation
+    } else {
+        TokenType::Operator
+    };
+    tokens.push(create_token(theme, "go", token_type, operator));
+}
+
+pub fn tokenize_go_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    handle_line_state(
+        &mut tokens, theme, "go", Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "go", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && next_char == '/' {
+            handle_line_comment(&mut tokens, theme, "go", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && next_char == '*' {
+            handle_block_comment(&mut tokens, theme, "go", &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '"' || current_char == '\'' || current_char == '`' {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char.is_ascii_digit() || (current_char == '.' && next_char.is_ascii_digit()) {
+            handle_number(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char.is_alphabetic() || current_char == '_' {
+            handle_identifier(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '.' && input_chars[pos..].starts_with(&['.', '.', '.']) {
+            handle_operator(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '.' {
+            handle_punctuation(&mut tokens, theme, "go", current_char, &mut pos);
+        }
+        else if "+-*/%&|^<>=!~()[]{},;:".contains(current_char) {
+            handle_operator(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else {
+            handle_unknown(&mut tokens, theme, "go", current_char, &mut pos);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct GoLanguage;
+
+impl Language for GoLanguage {
+    fn name(&self) -> &str {
+        "go"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["go"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_go_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_go() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize(
+            "go", 
+            &theme, 
+            "x := 0x1F_FF + 1.5e-3i + len(s) // Exported", 
+            &LineState::Normal
+        );
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of(":="), TokenType::Operator);
+        assert_eq!(token_of("0x1F_FF"), TokenType::Number);
+        assert_eq!(token_of("1.5e-3i"), TokenType::Number);
+        assert_eq!(token_of("len"), TokenType::FunctionName);
+        assert_eq!(token_of("// Exported"), TokenType::Comment);
+
+        let line_content = tokenize("go", &theme, "var r rune = '\\''; type T Point", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of("rune"), TokenType::ClassName);
+        assert_eq!(token_of("'"), TokenType::StringLiteral);
+        assert_eq!(token_of("\\'"), TokenType::EscapeSequence);
+        assert_eq!(token_of("Point"), TokenType::ClassName);
+    }
+
+    #[test]
+    fn test_tokenize_go_raw_string_state() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let first = tokenize("go", &theme, "s := `raw \\", &LineState::Normal);
+        assert_eq!(first.state, LineState::InString { delimiter: "`".to_string(), raw: true });
+
+        let second = tokenize("go", &theme, "end` + \"x\"", &first.state);
+        assert_eq!(second.tokens[0].value, "end`");
+        assert_eq!(second.state, LineState::Normal);
+
+        // interpreted strings never continue on the next line
+        let third = tokenize("go", &theme, "s := \"open", &LineState::Normal);
+        assert_eq!(third.state, LineState::Normal);
+    }
+}
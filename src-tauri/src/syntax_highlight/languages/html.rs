@@ -0,0 +1,409 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "<!--", close: "-->", nested: false };
+
+// what differs between HTML and the HTML based formats sharing its tokenizer
+pub struct MarkupDialect {
+    pub language: &'static str,
+    // `{{ expression }}` in text and directive attributes (`v-if`, `:prop`, `@event`, `#slot`)
+    pub template_syntax: bool,
+}
+
+pub const HTML_DIALECT: MarkupDialect = MarkupDialect { language: "html", template_syntax: false };
+
+fn is_tag_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == ':' || ch == '.'
+}
+
+fn is_attribute_name_char(ch: char) -> bool {
+    !ch.is_whitespace() && !"\"'<>/=".contains(ch)
+}
+
+// position of `marker` at or after `from`, ignoring ASCII case
+fn find_marker(input_chars: &[char], from: usize, marker: &str) -> Option<usize> {
+    let marker_chars: Vec<char> = marker.chars().collect();
+    (from..input_chars.len()).find(|&start| {
+        input_chars[start..].len() >= marker_chars.len()
+            && input_chars[start..start + marker_chars.len()].iter()
+                .zip(&marker_chars)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    })
+}
+
+// the language of a <script> or <style> element's content and the marker ending it
+fn embedded_language(tag: &str, lang: &str) -> Option<(&'static str, String)> {
+    let tag = tag.to_lowercase();
+    let language = match tag.as_str() {
+        "script" if lang == "tsx" => "tsx",
+        "script" if matches!(lang, "ts" | "typescript") => "typescript",
+        "script" => "javascript",
+        // scss, less and friends are close enough to CSS to highlight
+        "style" => "css",
+        _ => return None,
+    };
+    Some((language, format!("</{}", tag)))
+}
+
+// tokenize the rest of an embedded region up to its end marker, or the whole line when
+// the region goes on past it
+fn handle_embedded_region(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let LineState::InEmbedded { language, end, state: embedded_state } = state.clone() else {
+        return;
+    };
+    let end_pos = find_marker(input_chars, *pos, &end);
+    let embedded_state = tokenize_embedded(
+        tokens, theme, &language, input_chars, pos, end_pos.unwrap_or(input_chars.len()), &embedded_state
+    );
+
+    *state = match end_pos {
+        Some(_) => LineState::Normal,
+        None => LineState::InEmbedded { language, end, state: Box::new(embedded_state) },
+    };
+}
+
+// `<!DOCTYPE html>` and other `<!...>` declarations
+fn handle_declaration(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && input_chars[*pos] != '>' {
+        *pos += 1;
+    }
+    *pos = (*pos + 1).min(input_chars.len());
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, dialect.language, TokenType::Preprocessor, &value));
+}
+
+// `<name` or `</name`, the attributes and closing `>` follow in handle_tag_body.
+// Component names (`<MyButton>`) are class names.
+fn handle_tag_open(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let closing = input_chars.get(*pos + 1) == Some(&'/');
+    let bracket = if closing { "</" } else { "<" };
+    tokens.push(create_token(theme, dialect.language, TokenType::Punctuation, bracket));
+    *pos += bracket.len();
+
+    let start_pos = *pos;
+    while *pos < input_chars.len() && is_tag_name_char(input_chars[*pos]) {
+        *pos += 1;
+    }
+    let name: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if name.starts_with(|ch: char| ch.is_uppercase()) {
+        TokenType::ClassName
+    } else {
+        TokenType::Keyword
+    };
+    // `</>` or `< ` has no name
+    if !name.is_empty() {
+        tokens.push(create_token(theme, dialect.language, token_type, &name));
+    }
+
+    // a closing tag never starts an embedded region
+    let name = if closing { String::new() } else { name };
+    *state = LineState::InTag { name, lang: String::new() };
+}
+
+// attributes up to the `>` closing the tag, which may be on a later line
+fn handle_tag_body(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let LineState::InTag { name, mut lang } = state.clone() else {
+        return;
+    };
+    let mut attribute = String::new();
+
+    while *pos < input_chars.len() {
+        let current_char = input_chars[*pos];
+
+        if current_char.is_whitespace() {
+            handle_whitespace(tokens, theme, dialect.language, input_chars, pos);
+        }
+        else if current_char == '>' || (current_char == '/' && input_chars.get(*pos + 1) == Some(&'>')) {
+            let self_closing = current_char == '/';
+            let value = if self_closing { "/>" } else { ">" };
+            tokens.push(create_token(theme, dialect.language, TokenType::Punctuation, value));
+            *pos += value.len();
+
+            *state = match embedded_language(&name, &lang) {
+                Some((language, end)) if !self_closing => LineState::InEmbedded {
+                    language: language.to_string(),
+                    end,
+                    state: Box::new(LineState::Normal),
+                },
+                _ => LineState::Normal,
+            };
+            return;
+        }
+        else if current_char == '=' {
+            handle_operator(tokens, theme, dialect.language, current_char, input_chars, pos);
+        }
+        else if current_char == '"' || current_char == '\'' {
+            let start_pos = *pos;
+            *pos += 1; // Move past the opening quote
+            scan_string_body(input_chars, pos, &current_char.to_string(), true);
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+            if attribute == "lang" {
+                lang = value.trim_matches(current_char).to_lowercase();
+            }
+            tokens.push(create_token(theme, dialect.language, TokenType::StringLiteral, &value));
+        }
+        else if is_attribute_name_char(current_char) {
+            let start_pos = *pos;
+            while *pos < input_chars.len() && is_attribute_name_char(input_chars[*pos]) {
+                *pos += 1;
+            }
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+
+            let after_equals = tokens.iter()
+                .rev()
+                .find(|token| token.token_type != TokenType::Whitespace)
+                .is_some_and(|token| token.value == "=");
+            let is_directive = dialect.template_syntax
+                && (value.starts_with("v-") || value.starts_with([':', '@', '#']));
+            let token_type = if after_equals {
+                // unquoted attribute value
+                if attribute == "lang" {
+                    lang = value.to_lowercase();
+                }
+                TokenType::StringLiteral
+            } else if is_directive {
+                TokenType::FunctionName
+            } else {
+                TokenType::Identifier
+            };
+            if !after_equals {
+                attribute = value.to_lowercase();
+            }
+            tokens.push(create_token(theme, dialect.language, token_type, &value));
+        }
+        else {
+            handle_unknown(tokens, theme, dialect.language, current_char, pos);
+        }
+    }
+
+    *state = LineState::InTag { name, lang };
+}
+
+// length of the `&amp;`, `&#169;` or `&#x1F600;` entity at `pos`, 0 when there is none
+fn entity_length(input_chars: &[char], pos: usize) -> usize {
+    let name_length = input_chars[pos + 1..].iter().take_while(|ch| ch.is_alphanumeric() || **ch == '#').count();
+    if name_length == 0 || input_chars.get(pos + 1 + name_length) != Some(&';') {
+        return 0;
+    }
+    name_length + 2
+}
+
+fn handle_entity(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += entity_length(input_chars, *pos);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, dialect.language, TokenType::Number, &value));
+}
+
+// `{{ expression }}`, the expression is JavaScript
+fn handle_interpolation(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    tokens.push(create_token(theme, dialect.language, TokenType::Punctuation, "{{"));
+    *pos += 2;
+
+    let end_pos = find_marker(input_chars, *pos, "}}");
+    tokenize_embedded(
+        tokens, theme, "javascript", input_chars, pos, end_pos.unwrap_or(input_chars.len()), &LineState::Normal
+    );
+    if end_pos.is_some() {
+        tokens.push(create_token(theme, dialect.language, TokenType::Punctuation, "}}"));
+        *pos += 2;
+    }
+}
+
+fn handle_text(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1;
+    while *pos < input_chars.len() {
+        let ch = input_chars[*pos];
+        if ch.is_whitespace() || ch == '<' || ch == '&'
+            || (dialect.template_syntax && input_chars[*pos..].starts_with(&['{', '{']))
+        {
+            break;
+        }
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, dialect.language, TokenType::Identifier, &value));
+}
+
+pub fn tokenize_markup_code(
+    theme: &Theme, 
+    dialect: &MarkupDialect, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    handle_line_state(
+        &mut tokens, theme, dialect.language, Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+
+        // a start tag or <script>/<style> content, either left open by the previous line
+        // or opened earlier on this one
+        if let LineState::InTag { .. } = state {
+            handle_tag_body(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if let LineState::InEmbedded { .. } = state {
+            handle_embedded_region(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, dialect.language, input_chars.as_slice(), &mut pos);
+        }
+        else if input_chars[pos..].starts_with(&['<', '!', '-', '-']) {
+            handle_block_comment(&mut tokens, theme, dialect.language, &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '<' && next_char == '!' {
+            handle_declaration(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '<' && (next_char.is_alphabetic() || next_char == '/') {
+            handle_tag_open(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '&' && entity_length(input_chars.as_slice(), pos) > 0 {
+            handle_entity(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos);
+        }
+        else if dialect.template_syntax && current_char == '{' && next_char == '{' {
+            handle_interpolation(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos);
+        }
+        else {
+            handle_text(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct HtmlLanguage;
+
+impl Language for HtmlLanguage {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["html", "htm", "xhtml"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        None
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_markup_code(theme, &HTML_DIALECT, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_html() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = ["<!DOCTYPE html>", "<a href=\"/\"", "   class=main>Tom &amp; Jerry</a>", "<script>let x = 1; <!-- not a comment", "</script><!-- open", "close -->"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("html", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(line_contents[0].tokens[0].token_type, TokenType::Preprocessor);
+        assert_eq!(token_of(1, "a"), TokenType::Keyword);
+        assert_eq!(token_of(1, "href"), TokenType::Identifier);
+        assert_eq!(token_of(1, "\"/\""), TokenType::StringLiteral);
+        // the start tag goes on on the next line
+        assert_eq!(line_contents[1].state, LineState::InTag { name: "a".to_string(), lang: String::new() });
+        assert_eq!(token_of(2, "class"), TokenType::Identifier);
+        assert_eq!(token_of(2, "main"), TokenType::StringLiteral);
+        assert_eq!(token_of(2, "Tom"), TokenType::Identifier);
+        assert_eq!(token_of(2, "&amp;"), TokenType::Number);
+        assert_eq!(line_contents[2].state, LineState::Normal);
+        // script content belongs to javascript until </script>
+        assert_eq!(token_of(3, "let"), TokenType::Keyword);
+        assert_eq!(
+            line_contents[3].state, 
+            LineState::InEmbedded { 
+                language: "javascript".to_string(), 
+                end: "</script".to_string(), 
+                state: Box::new(LineState::Normal) 
+            }
+        );
+        assert_eq!(token_of(4, "script"), TokenType::Keyword);
+        assert_eq!(line_contents[4].state, LineState::InBlockComment { depth: 1 });
+        assert_eq!(line_contents[5].tokens[0].token_type, TokenType::Comment);
+        assert_eq!(line_contents[5].state, LineState::Normal);
+
+        // a tag bracket without a name gets no empty name token
+        for language in ["html", "vue"] {
+            for line in ["</>", "</ ", "</(", "a < b"] {
+                let line_content = tokenize(language, &theme, line, &LineState::Normal);
+                assert!(line_content.tokens.iter().all(|token| !token.value.is_empty()), "{} {:?}", language, line);
+            }
+        }
+    }
+}
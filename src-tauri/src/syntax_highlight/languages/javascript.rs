@@ -1,230 +1,667 @@
-use crate::settings::Settings;
-use crate::syntax_highlight::{Token, TokenType, create_token};
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, ScriptContext, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+use crate::syntax_highlight::languages::typescript::TYPESCRIPT_PRIMITIVE_TYPES;
 
 // JavaScript keywords
-const JAVASCRIPT_KEYWORDS: [&str; 36] = [
+const JAVASCRIPT_KEYWORDS: [&str; 41] = [
     "break", "case", "catch", "class", "const", "continue", "debugger", 
     "default", "delete", "do", "else", "export", "extends", "finally", 
     "for", "function", "if", "import", "in", "instanceof", "let", "new", 
     "return", "super", "switch", "this", "throw", "try", "typeof", 
-    "var", "void", "while", "with", "yield", "await", "async"
+    "var", "void", "while", "with", "yield", "await", "async", 
+    "of", "static", "null", "true", "false"
 ];
 
+// keywords an expression follows, so a `/` after them starts a regex and a `<` an element
+const EXPRESSION_KEYWORDS: [&str; 14] = [
+    "return", "typeof", "instanceof", "in", "of", "new", "delete",
+    "void", "throw", "case", "do", "else", "yield", "await"
+];
+
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+// what differs between JavaScript and the languages sharing its tokenizer
+pub struct ScriptDialect {
+    pub language: &'static str,
+    pub keywords: &'static [&'static str],
+    // type arguments and parameters `Map<string, T>`, primitive types
+    pub typescript: bool,
+    // JSX elements `<App title="x">{children}</App>`
+    pub jsx: bool,
+}
+
+pub const JAVASCRIPT_DIALECT: ScriptDialect = ScriptDialect {
+    language: "javascript", 
+    keywords: &JAVASCRIPT_KEYWORDS, 
+    typescript: false, 
+    jsx: true, 
+};
+
+// Whether an expression can start after the tokens of the line so far.
+// Tells a regex from a division and a JSX element from a less-than comparison.
+fn is_expression_position(tokens: &[Token]) -> bool {
+    let last_token = tokens.iter()
+        .rev()
+        .find(|token| token.token_type != TokenType::Whitespace && token.token_type != TokenType::Comment);
+
+    match last_token {
+        None => true,
+        Some(token) => match token.token_type {
+            TokenType::Operator => true,
+            TokenType::Punctuation => !matches!(token.value.as_str(), ")" | "]" | "}"),
+            TokenType::Keyword => EXPRESSION_KEYWORDS.contains(&token.value.as_str()),
+            _ => false,
+        },
+    }
+}
+
+// End of the regex literal starting at `pos`, including its flags.
+// None when the line ends before the closing `/`, the `/` is a division then.
+fn regex_end(input_chars: &[char], pos: usize) -> Option<usize> {
+    let mut pos = pos + 1;
+    let mut in_class = false;
+
+    while pos < input_chars.len() {
+        match input_chars[pos] {
+            '\\' => pos += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            // a `/` inside a character class `[/]` doesn't end the regex
+            '/' if !in_class => {
+                pos += 1;
+                while pos < input_chars.len() && input_chars[pos].is_ascii_alphabetic() {
+                    pos += 1;
+                }
+                return Some(pos);
+            },
+            _ => (),
+        }
+        pos += 1;
+    }
+    None
+}
+
+// Position of the `>` closing the type arguments or parameters opened at `pos`.
+// None when what follows can't be a type (`a < b && c > d`), the `<` is less-than then.
+fn generic_end(input_chars: &[char], pos: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut brackets = 0;
+
+    for index in pos..input_chars.len() {
+        let ch = input_chars[index];
+        let next_char = input_chars.get(index + 1);
+        match ch {
+            '<' => depth += 1,
+            // `=>` of a function type
+            '>' if input_chars[index - 1] == '=' => (),
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            },
+            '(' | '[' | '{' => brackets += 1,
+            ')' | ']' | '}' if brackets == 0 => return None,
+            ')' | ']' | '}' => brackets -= 1,
+            '&' if next_char == Some(&'&') => return None,
+            '|' if next_char == Some(&'|') => return None,
+            _ if ch.is_alphanumeric() || ch.is_whitespace() || "_$,.|&?:='\"-".contains(ch) => (),
+            _ => return None,
+        }
+    }
+    None
+}
 
-fn handle_whitespace(
+fn handle_string_literal(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
+    language: &str, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
-        *pos += 1;
-    }
+    let quote = input_chars[*pos];
+    *pos += 1; // Move past the opening quote
+
+    // single and double-quoted strings end with the line
+    scan_string_body(input_chars, pos, &quote.to_string(), false);
     let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "javascript", TokenType::Whitespace, &value));
+    tokens.push(create_token(theme, language, TokenType::StringLiteral, &value));
 }
 
-fn handle_comment(
+fn push_string_segment(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
+    language: &str, 
     input_chars: &[char], 
-    pos: &mut usize
+    start_pos: usize, 
+    end: usize
 ) {
-    let start_pos = *pos;
-    *pos += 1; // Move past the initial '/' character
+    if end > start_pos {
+        let value: String = input_chars[start_pos..end].iter().collect();
+        tokens.push(create_token(theme, language, TokenType::StringLiteral, &value));
+    }
+}
 
-    if *pos < input_chars.len() && input_chars[*pos] == '/' {
-        // Single-line comment (//)
-        while *pos < input_chars.len() && input_chars[*pos] != '\n' {
+// Text of a template literal from `pos` up to the closing backtick, which ends the
+// Template context, or an `${` which opens an Expression inside it.
+// `segment_start` is where the string token being built began, an opening backtick
+// is already behind `pos`.
+fn handle_template(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    segment_start: usize, 
+    contexts: &mut Vec<ScriptContext>
+) {
+    while *pos < input_chars.len() {
+        if input_chars[*pos] == '\\' {
+            *pos = (*pos + 2).min(input_chars.len());
+        } else if input_chars[*pos] == '`' {
             *pos += 1;
-        }
-    } else if *pos < input_chars.len() && input_chars[*pos] == '*' {
-        // Multi-line comment (/* ... */)
-        *pos += 1;
-        while *pos + 1 < input_chars.len() {
-            if input_chars[*pos] == '*' && input_chars[*pos + 1] == '/' {
-                *pos += 2; // Move past the closing */
-                break;
-            }
+            contexts.pop();
+            break;
+        } else if input_chars[*pos..].starts_with(&['$', '{']) {
+            push_string_segment(tokens, theme, language, input_chars, segment_start, *pos);
+            tokens.push(create_token(theme, language, TokenType::Punctuation, "${"));
+            *pos += 2;
+            contexts.push(ScriptContext::Expression { braces: 0 });
+            return;
+        } else {
             *pos += 1;
         }
     }
-
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "javascript", TokenType::Comment, &value));
+    push_string_segment(tokens, theme, language, input_chars, segment_start, *pos);
 }
 
-fn handle_string_literal(
+// `1_000`, `0xFF`, `0b1010`, `0o17`, `3.14`, `.5`, `1e-3`, `10n`
+fn handle_script_number(
     tokens: &mut Vec<Token>, 
-    settings: &Settings, 
+    theme: &Theme, 
+    language: &str, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
-    let start_char = input_chars[*pos];
     let start_pos = *pos;
-    *pos += 1; // Move past the opening quote
-
-    // Handle template literals for JavaScript
-    if start_char == '`' {
-        while *pos < input_chars.len() {
-            if input_chars[*pos] == '`' {
-                *pos += 1; // Move past the closing backtick
-                break;
-            }
-            // Handle escaped backticks within template literals
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
-            }
+    let scan_digits = |pos: &mut usize, radix: u32| {
+        while *pos < input_chars.len() && (input_chars[*pos].is_digit(radix) || input_chars[*pos] == '_') {
             *pos += 1;
         }
+    };
+
+    let radix = match (input_chars[*pos], input_chars.get(*pos + 1)) {
+        ('0', Some('x' | 'X')) => 16,
+        ('0', Some('o' | 'O')) => 8,
+        ('0', Some('b' | 'B')) => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        *pos += 2; // Move past the `0x`
+        scan_digits(pos, radix);
     } else {
-        // Handle single and double-quoted strings
-        while *pos < input_chars.len() && input_chars[*pos] != start_char {
-            // Handle escaped quotes within strings
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
-            }
+        scan_digits(pos, 10);
+        if input_chars.get(*pos) == Some(&'.') {
             *pos += 1;
+            scan_digits(pos, 10);
+        }
+        if matches!(input_chars.get(*pos), Some('e' | 'E')) {
+            let sign_length = usize::from(matches!(input_chars.get(*pos + 1), Some('+' | '-')));
+            if input_chars.get(*pos + 1 + sign_length).is_some_and(|ch| ch.is_ascii_digit()) {
+                *pos += 1 + sign_length;
+                scan_digits(pos, 10);
+            }
         }
-        *pos += 1; // Move past the closing quote
+    }
+    // BigInt
+    if input_chars.get(*pos) == Some(&'n') {
+        *pos += 1;
     }
 
-    // Collect the entire string value including quotes
-    let value: String = input_chars[start_pos..*pos.min(&mut input_chars.len())].iter().collect();
-    tokens.push(create_token(settings, "javascript", TokenType::StringLiteral, &value));
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Number, &value));
 }
 
-fn handle_number(
+// `@Component` or `@observable.ref`
+fn handle_decorator(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
+    language: &str, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_digit(10) {
+    *pos += 1; // Move past the `@`
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_' || input_chars[*pos] == '.') {
         *pos += 1;
     }
     let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "javascript", TokenType::Number, &value));
+    tokens.push(create_token(theme, language, TokenType::FunctionName, &value));
 }
 
-fn handle_operator(
+fn handle_script_identifier(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
+    theme: &Theme, 
+    dialect: &ScriptDialect, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
-    let mut value = current_char.to_string();
-    *pos += 1;
+    let length = input_chars[*pos..].iter().take_while(|ch| ch.is_alphanumeric() || **ch == '_').count();
+    let value: String = input_chars[*pos..*pos + length].iter().collect();
+    let is_member = *pos > 0 && input_chars[*pos - 1] == '.';
 
-    // Handle multi-character operators like ==, ===, =>, etc.
-    if let Some(next_char) = input_chars.get(*pos) {
-        if current_char == '=' && (*next_char == '=' || *next_char == '>') {
-            value.push(*next_char);
-            *pos += 1;
-
-            // Handle === case
-            if *next_char == '=' && *pos < input_chars.len() && input_chars[*pos] == '=' {
-                value.push('=');
-                *pos += 1;
-            }
-        } else if current_char == '!' && *next_char == '=' {
-            value.push(*next_char);
-            *pos += 1;
-        }
+    if dialect.typescript && !is_member && TYPESCRIPT_PRIMITIVE_TYPES.contains(&value.as_str()) {
+        tokens.push(create_token(theme, dialect.language, TokenType::Builtin, &value));
+        *pos += length;
+    } else {
+        handle_identifier(tokens, theme, dialect.language, dialect.keywords, input_chars, pos);
     }
+}
 
-    tokens.push(create_token(settings_config, "javascript", TokenType::Operator, &value));
+// `<string, Array<T>>` of `new Map<string, Array<T>>()`, the types in between are
+// tokenized as code, which gives primitive types and class names their colors
+fn handle_generic(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    dialect: &ScriptDialect, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    end: usize
+) {
+    handle_punctuation(tokens, theme, dialect.language, '<', pos);
+    let types: String = input_chars[*pos..end].iter().collect();
+    let (type_tokens, _) = tokenize_script_code(theme, dialect, &types, &LineState::Normal);
+    tokens.extend(type_tokens);
+    *pos = end;
+    handle_punctuation(tokens, theme, dialect.language, '>', pos);
 }
 
-fn handle_identifier(
+// `div` or `Foo.Bar`, lowercase names are HTML elements, anything else is a component
+fn handle_jsx_tag_name(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
+    language: &str, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || "_-.:".contains(input_chars[*pos])) {
         *pos += 1;
     }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    let next_char = input_chars.get(*pos).unwrap_or(&' ');
-
-    // Identify function calls and class names in JavaScript
-    if *next_char == '(' {
-        tokens.push(create_token(settings_config, "javascript", TokenType::FunctionName, &value));
-    } else {
-        let token_type;
-        if JAVASCRIPT_KEYWORDS.contains(&value.as_str()) {
-            token_type = TokenType::Keyword;
-        } else if value.chars().next().unwrap().is_uppercase() {
-            token_type = TokenType::ClassName;
+    if *pos > start_pos {
+        let value: String = input_chars[start_pos..*pos].iter().collect();
+        let token_type = if value.starts_with(char::is_lowercase) && !value.contains('.') {
+            TokenType::Keyword
         } else {
-            token_type = TokenType::Identifier;
-        }
-        tokens.push(create_token(settings_config, "javascript", token_type, &value));
+            TokenType::ClassName
+        };
+        tokens.push(create_token(theme, language, token_type, &value));
     }
 }
 
-fn handle_punctuation(
+// `<div` of a start tag or `<` of a fragment `<>`, the attributes follow in a JsxTag context
+fn handle_jsx_tag_open(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    contexts: &mut Vec<ScriptContext>
 ) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "javascript", TokenType::Punctuation, &value));
-    *pos += 1;
+    handle_punctuation(tokens, theme, language, '<', pos);
+    handle_jsx_tag_name(tokens, theme, language, input_chars, pos);
+    contexts.push(ScriptContext::JsxTag);
 }
 
-fn handle_unknown(
+// Attributes of a JSX start tag up to its `>`, which opens the element's children,
+// its `/>` or an `{expression}` value.
+fn handle_jsx_tag_body(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    contexts: &mut Vec<ScriptContext>
+) {
+    while *pos < input_chars.len() {
+        let current_char = input_chars[*pos];
+
+        if current_char.is_whitespace() {
+            handle_whitespace(tokens, theme, language, input_chars, pos);
+        }
+        else if input_chars[*pos..].starts_with(&['/', '>']) {
+            tokens.push(create_token(theme, language, TokenType::Punctuation, "/>"));
+            *pos += 2;
+            contexts.pop();
+            return;
+        }
+        else if current_char == '>' {
+            handle_punctuation(tokens, theme, language, current_char, pos);
+            contexts.pop();
+            contexts.push(ScriptContext::JsxChildren);
+            return;
+        }
+        else if current_char == '{' {
+            handle_punctuation(tokens, theme, language, current_char, pos);
+            contexts.push(ScriptContext::Expression { braces: 0 });
+            return;
+        }
+        else if current_char == '=' {
+            tokens.push(create_token(theme, language, TokenType::Operator, "="));
+            *pos += 1;
+        }
+        else if current_char == '"' || current_char == '\'' {
+            handle_string_literal(tokens, theme, language, input_chars, pos);
+        }
+        else if current_char.is_alphabetic() || current_char == '_' {
+            let start_pos = *pos;
+            while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || "_-:".contains(input_chars[*pos])) {
+                *pos += 1;
+            }
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+            tokens.push(create_token(theme, language, TokenType::Identifier, &value));
+        }
+        else {
+            handle_unknown(tokens, theme, language, current_char, pos);
+        }
+    }
+}
+
+// Children of a JSX element: text, nested elements, `{expression}`s and the end tag,
+// which ends the JsxChildren context.
+fn handle_jsx_children(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    contexts: &mut Vec<ScriptContext>
 ) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "javascript", TokenType::Unknown, &value));
-    *pos += 1;
+    while *pos < input_chars.len() {
+        let current_char = input_chars[*pos];
+
+        if current_char.is_whitespace() {
+            handle_whitespace(tokens, theme, language, input_chars, pos);
+        }
+        else if input_chars[*pos..].starts_with(&['<', '/']) {
+            tokens.push(create_token(theme, language, TokenType::Punctuation, "</"));
+            *pos += 2;
+            handle_jsx_tag_name(tokens, theme, language, input_chars, pos);
+            if input_chars.get(*pos) == Some(&'>') {
+                handle_punctuation(tokens, theme, language, '>', pos);
+            }
+            contexts.pop();
+            return;
+        }
+        else if current_char == '<' {
+            handle_jsx_tag_open(tokens, theme, language, input_chars, pos, contexts);
+            return;
+        }
+        else if current_char == '{' {
+            handle_punctuation(tokens, theme, language, current_char, pos);
+            contexts.push(ScriptContext::Expression { braces: 0 });
+            return;
+        }
+        else {
+            let start_pos = *pos;
+            while *pos < input_chars.len() && !input_chars[*pos].is_whitespace() && !"<{".contains(input_chars[*pos]) {
+                *pos += 1;
+            }
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+            tokens.push(create_token(theme, language, TokenType::Identifier, &value));
+        }
+    }
 }
 
-pub fn tokenize_javascript_code(settings_config: &Settings, input: &str) -> Vec<Token> {
+pub fn tokenize_script_code(
+    theme: &Theme, 
+    dialect: &ScriptDialect, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
     let mut tokens = Vec::new();
     let mut pos = 0;
+    let mut state = state.clone();
     let input_chars: Vec<char> = input.chars().collect();
+    let language = dialect.language;
+
+    // template literals and JSX elements the previous line left open
+    let mut contexts = match std::mem::take(&mut state) {
+        LineState::InScript { contexts } => contexts,
+        other_state => {
+            state = other_state;
+            Vec::new()
+        },
+    };
+
+    handle_line_state(
+        &mut tokens, theme, language, Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
 
     while pos < input_chars.len() {
         let current_char = input_chars[pos];
+        let previous_char = input_chars.get(pos.wrapping_sub(1)).copied().unwrap_or(' ');
 
-        if current_char.is_whitespace() {
-            handle_whitespace(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char == '/' && (input_chars.get(pos + 1) == Some(&'/') || input_chars.get(pos + 1) == Some(&'*')) {
-            handle_comment(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char == '"' || current_char == '\'' || current_char == '`' {
-            handle_string_literal(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char.is_digit(10) {
-            handle_number(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if "+-*/%=><!".contains(current_char) {
-            handle_operator(&mut tokens, settings_config, current_char, input_chars.as_slice(), &mut pos);
-        } 
+        if contexts.last() == Some(&ScriptContext::Template) {
+            let segment_start = pos;
+            handle_template(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, segment_start, &mut contexts);
+        }
+        else if contexts.last() == Some(&ScriptContext::JsxTag) && current_char == '<' && dialect.typescript
+            && generic_end(input_chars.as_slice(), pos).is_some() {
+            // type arguments of a generic component, `<List<Item> />`
+            let end = generic_end(input_chars.as_slice(), pos).unwrap();
+            handle_generic(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos, end);
+        }
+        else if contexts.last() == Some(&ScriptContext::JsxTag) {
+            handle_jsx_tag_body(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, &mut contexts);
+        }
+        else if contexts.last() == Some(&ScriptContext::JsxChildren) {
+            handle_jsx_children(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, &mut contexts);
+        }
+        else if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && input_chars.get(pos + 1) == Some(&'/') {
+            handle_line_comment(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && input_chars.get(pos + 1) == Some(&'*') {
+            handle_block_comment(&mut tokens, theme, language, &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '/' && is_expression_position(&tokens) && regex_end(input_chars.as_slice(), pos).is_some() {
+            let end = regex_end(input_chars.as_slice(), pos).unwrap();
+            let value: String = input_chars[pos..end].iter().collect();
+            tokens.push(create_token(theme, language, TokenType::StringLiteral, &value));
+            pos = end;
+        }
+        else if current_char == '"' || current_char == '\'' {
+            handle_string_literal(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '`' {
+            // template literals may span multiple lines
+            let start_pos = pos;
+            pos += 1;
+            contexts.push(ScriptContext::Template);
+            handle_template(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, start_pos, &mut contexts);
+        }
+        else if current_char.is_ascii_digit()
+            || (current_char == '.' && input_chars.get(pos + 1).is_some_and(|ch| ch.is_ascii_digit()) && !previous_char.is_alphanumeric() && !")]_".contains(previous_char)) {
+            handle_script_number(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '@' {
+            handle_decorator(&mut tokens, theme, language, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '<' && dialect.jsx && is_expression_position(&tokens)
+            && input_chars.get(pos + 1).is_some_and(|ch| ch.is_alphabetic() || *ch == '>') {
+            handle_jsx_tag_open(&mut tokens, theme, language, input_chars.as_slice(), &mut pos, &mut contexts);
+        }
+        else if current_char == '<' && dialect.typescript
+            && (previous_char.is_alphanumeric() || previous_char == '_' || (!dialect.jsx && is_expression_position(&tokens)))
+            && generic_end(input_chars.as_slice(), pos).is_some() {
+            let end = generic_end(input_chars.as_slice(), pos).unwrap();
+            handle_generic(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos, end);
+        }
+        else if "+-*/%=><!&|^~?".contains(current_char) {
+            handle_operator(&mut tokens, theme, language, current_char, input_chars.as_slice(), &mut pos);
+        }
         else if current_char.is_alphabetic() || current_char == '_' {
-            handle_identifier(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if ",.:;()[]{}".contains(current_char) {
-            handle_punctuation(&mut tokens, settings_config, current_char, &mut pos);
-        } 
+            handle_script_identifier(&mut tokens, theme, dialect, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '{' || current_char == '}' {
+            // `{` and `}` of blocks inside an `${...}` or `{...}`, the `}` matching its opening ends it
+            match contexts.last_mut() {
+                Some(ScriptContext::Expression { braces }) if current_char == '{' => *braces += 1,
+                Some(ScriptContext::Expression { braces }) if *braces > 0 => *braces -= 1,
+                Some(ScriptContext::Expression { .. }) => {
+                    contexts.pop();
+                },
+                _ => (),
+            }
+            handle_punctuation(&mut tokens, theme, language, current_char, &mut pos);
+        }
+        else if ",.:;()[]".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, language, current_char, &mut pos);
+        }
         else {
-            handle_unknown(&mut tokens, settings_config, current_char, &mut pos);
+            handle_unknown(&mut tokens, theme, language, current_char, &mut pos);
         }
     }
 
-    tokens
+    // a comment or string left open inside a template literal or JSX
+    // takes over the next line, the contexts around it are dropped
+    if !contexts.is_empty() && state == LineState::Normal {
+        state = LineState::InScript { contexts };
+    }
+    (tokens, state)
+}
+
+pub fn tokenize_javascript_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    tokenize_script_code(theme, &JAVASCRIPT_DIALECT, input, state)
+}
+
+pub struct JavaScriptLanguage;
+
+impl Language for JavaScriptLanguage {
+    fn name(&self) -> &str {
+        "javascript"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["js", "jsx", "mjs", "cjs"]
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        vec!["node", "nodejs"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_javascript_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_javascript_regex_literals() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("javascript", &theme, "const re = /ab+c[/]/gi; let half = total / 2 / count; if (/^\\d+$/.test(s)) return /x/;", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of("/ab+c[/]/gi"), TokenType::StringLiteral);
+        assert_eq!(token_of("/^"), TokenType::StringLiteral);
+        assert_eq!(token_of("\\d"), TokenType::EscapeSequence);
+        assert_eq!(token_of("+$/"), TokenType::StringLiteral);
+        assert_eq!(token_of("/x/"), TokenType::StringLiteral);
+        // after an operand a `/` divides
+        let divisions = line_content.tokens.iter().filter(|token| token.value == "/").count();
+        assert_eq!(divisions, 2);
+        assert_eq!(token_of("count"), TokenType::Identifier);
+    }
+
+    #[test]
+    fn test_tokenize_javascript_template_interpolation() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "const msg = `Hello ${user.name}, ${items.map(i => `<li>${i}</li>`)",
+            "  .join(\"\")} total: ${count + 1}`;",
+            "let done = true;",
+        ].iter().map(|line| line.to_string()).collect();
+        let line_contents = tokenize_lines("javascript", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of(0, "`Hello "), TokenType::StringLiteral);
+        assert_eq!(token_of(0, "${"), TokenType::Punctuation);
+        assert_eq!(token_of(0, "user"), TokenType::Identifier);
+        assert_eq!(token_of(0, "`<li>"), TokenType::StringLiteral);
+        assert_eq!(token_of(0, "i"), TokenType::Identifier);
+        // the map call is still open, inside the outer template literal
+        assert_eq!(line_contents[0].state, LineState::InScript { 
+            contexts: vec![ScriptContext::Template, ScriptContext::Expression { braces: 0 }] 
+        });
+        assert_eq!(token_of(1, "join"), TokenType::FunctionName);
+        assert_eq!(token_of(1, " total: "), TokenType::StringLiteral);
+        assert_eq!(token_of(1, "1"), TokenType::Number);
+        assert_eq!(token_of(1, "`"), TokenType::StringLiteral);
+        assert_eq!(line_contents[1].state, LineState::Normal);
+        assert_eq!(token_of(2, "let"), TokenType::Keyword);
+    }
+
+    #[test]
+    fn test_tokenize_jsx() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "const view = count < max && (",
+            "  <Layout.Page title=\"Home\"",
+            "      onClick={() => setCount(count + 1)}>",
+            "    <h1>Hello {user.name}</h1>",
+            "    <></>",
+            "    <input disabled />",
+            "  </Layout.Page>",
+            ");",
+        ].iter().map(|line| line.to_string()).collect();
+        let line_contents = tokenize_lines("javascript", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        // a comparison isn't an element
+        assert_eq!(token_of(0, "<"), TokenType::Operator);
+        assert_eq!(token_of(0, "max"), TokenType::Identifier);
+        assert_eq!(token_of(1, "Layout.Page"), TokenType::ClassName);
+        assert_eq!(token_of(1, "title"), TokenType::Identifier);
+        assert_eq!(token_of(1, "\"Home\""), TokenType::StringLiteral);
+        assert_eq!(line_contents[1].state, LineState::InScript { contexts: vec![ScriptContext::JsxTag] });
+        assert_eq!(token_of(2, "setCount"), TokenType::FunctionName);
+        assert_eq!(line_contents[2].state, LineState::InScript { contexts: vec![ScriptContext::JsxChildren] });
+        assert_eq!(token_of(3, "h1"), TokenType::Keyword);
+        assert_eq!(token_of(3, "Hello"), TokenType::Identifier);
+        assert_eq!(token_of(3, "</"), TokenType::Punctuation);
+        assert_eq!(token_of(5, "input"), TokenType::Keyword);
+        assert_eq!(token_of(5, "/>"), TokenType::Punctuation);
+        assert_eq!(line_contents[5].state, LineState::InScript { contexts: vec![ScriptContext::JsxChildren] });
+        assert_eq!(line_contents[6].state, LineState::Normal);
+        assert_eq!(token_of(7, ")"), TokenType::Punctuation);
+    }
 }
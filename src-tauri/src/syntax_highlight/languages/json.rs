@@ -0,0 +1,178 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const JSON_LITERALS: [&str; 3] = ["true", "false", "null"];
+
+// comments aren't JSON but are accepted everywhere so JSONC files
+// (tsconfig.json, VS Code settings) highlight correctly
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+// a string followed by `:` is an object key
+fn handle_string_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1; // Move past the opening quote
+    scan_string_body(input_chars, pos, "\"", false);
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if peek_non_whitespace(input_chars, *pos) == Some(':') {
+        TokenType::Identifier
+    } else {
+        TokenType::StringLiteral
+    };
+    tokens.push(create_token(theme, "json", token_type, &value));
+}
+
+// -?digits(.digits)?(e[+-]?digits)?
+fn handle_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    if input_chars[*pos] == '-' {
+        *pos += 1;
+    }
+    while *pos < input_chars.len() && (input_chars[*pos].is_ascii_digit() || ".eE".contains(input_chars[*pos])) {
+        let is_exponent = input_chars[*pos] == 'e' || input_chars[*pos] == 'E';
+        *pos += 1;
+        if is_exponent && matches!(input_chars.get(*pos), Some('+') | Some('-')) {
+            *pos += 1;
+        }
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "json", TokenType::Number, &value));
+}
+
+// true, false and null, anything else unquoted isn't valid JSON
+fn handle_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if JSON_LITERALS.contains(&value.as_str()) {
+        TokenType::Keyword
+    } else {
+        TokenType::Unknown
+    };
+    tokens.push(create_token(theme, "json", token_type, &value));
+}
+
+pub fn tokenize_json_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    handle_line_state(
+        &mut tokens, theme, "json", Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "json", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && next_char == '/' {
+            handle_line_comment(&mut tokens, theme, "json", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '/' && next_char == '*' {
+            handle_block_comment(&mut tokens, theme, "json", &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '"' {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char.is_ascii_digit() || (current_char == '-' && next_char.is_ascii_digit()) {
+            handle_number(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char.is_alphabetic() {
+            handle_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if "{}[],:".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, "json", current_char, &mut pos);
+        }
+        else {
+            handle_unknown(&mut tokens, theme, "json", current_char, &mut pos);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct JsonLanguage;
+
+impl Language for JsonLanguage {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["json", "jsonc"]
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        vec![".babelrc", ".eslintrc", ".prettierrc"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_json_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_json() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize(
+            "json", 
+            &theme, 
+            "\"fontSize\": \"15px\", \"n\": -1.5e3, \"b\": true, // comment", 
+            &LineState::Normal
+        );
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of("\"fontSize\""), TokenType::Identifier);
+        assert_eq!(token_of("\"15px\""), TokenType::StringLiteral);
+        assert_eq!(token_of("-1.5e3"), TokenType::Number);
+        assert_eq!(token_of("true"), TokenType::Keyword);
+        assert_eq!(token_of("// comment"), TokenType::Comment);
+    }
+}
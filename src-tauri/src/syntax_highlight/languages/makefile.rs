@@ -0,0 +1,341 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+const MAKE_DIRECTIVES: [&str; 17] = [
+    "include", "-include", "sinclude", "ifeq", "ifneq", "ifdef", "ifndef", "else", "endif",
+    "define", "endef", "undefine", "export", "unexport", "override", "private", "vpath"
+];
+
+const MAKE_FUNCTIONS: [&str; 36] = [
+    "subst", "patsubst", "strip", "findstring", "filter", "filter-out", "sort", "word",
+    "wordlist", "words", "firstword", "lastword", "dir", "notdir", "suffix", "basename",
+    "addsuffix", "addprefix", "join", "wildcard", "realpath", "abspath", "if", "or", "and",
+    "foreach", "file", "call", "value", "eval", "origin", "flavor", "error", "warning", "info", "shell"
+];
+
+// longest first so `::=` wins over `:=` and `:`
+const MAKE_OPERATORS: [&str; 8] = ["::=", ":=", "?=", "+=", "!=", "::", "=", ":"];
+
+// end of the `$(NAME)`, `${NAME}` or `$@` reference at `pos`, `$$` is an escaped `$`
+fn reference_end(input_chars: &[char], pos: usize) -> usize {
+    let closing = match input_chars.get(pos + 1) {
+        Some('(') => ')',
+        Some('{') => '}',
+        Some(_) => return pos + 2,
+        None => return pos + 1,
+    };
+    let opening = input_chars[pos + 1];
+    let mut depth = 0;
+    for (index, ch) in input_chars.iter().enumerate().skip(pos + 1) {
+        if *ch == opening {
+            depth += 1;
+        } else if *ch == closing {
+            depth -= 1;
+            if depth == 0 {
+                return index + 1;
+            }
+        }
+    }
+    input_chars.len()
+}
+
+// The operator making the line an assignment or a rule, `$(...)` references are skipped
+// since they may contain `:` or `=` themselves.
+// Returns its position and the operator.
+fn find_line_operator(input_chars: &[char]) -> Option<(usize, &'static str)> {
+    let mut pos = 0;
+    while pos < input_chars.len() {
+        match input_chars[pos] {
+            '$' => pos = reference_end(input_chars, pos),
+            '#' | ';' => return None,
+            _ => {
+                let operator = MAKE_OPERATORS.iter()
+                    .find(|operator| input_chars[pos..].starts_with(&operator.chars().collect::<Vec<char>>()));
+                if let Some(operator) = operator {
+                    return Some((pos, operator));
+                }
+                pos += 1;
+            },
+        }
+    }
+    None
+}
+
+// The `;` starting the recipe of a rule's line, from `pos` up to `end`. `$(...)` references
+// are skipped, `$(shell ls;pwd)` has a `;` of its own
+fn find_recipe_separator(input_chars: &[char], mut pos: usize, end: usize) -> Option<usize> {
+    while pos < end {
+        match input_chars[pos] {
+            '$' => pos = reference_end(input_chars, pos),
+            ';' => return Some(pos),
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+// `$(VAR)`, `$@`, or a function call `$(wildcard src/*.c)` whose arguments are tokenized too
+fn handle_reference(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    let end = reference_end(input_chars, *pos);
+    let name_length = input_chars[(*pos + 2).min(end)..end].iter().take_while(|ch| ch.is_alphanumeric() || **ch == '-').count();
+    let name: String = input_chars[(*pos + 2).min(end)..(*pos + 2 + name_length).min(end)].iter().collect();
+    let is_call = matches!(input_chars.get(*pos + 1), Some('(') | Some('{'))
+        && MAKE_FUNCTIONS.contains(&name.as_str())
+        && input_chars.get(*pos + 2 + name_length) == Some(&' ');
+
+    if !is_call {
+        *pos = end;
+        let value: String = input_chars[start_pos..*pos].iter().collect();
+        tokens.push(create_token(theme, "makefile", TokenType::ClassName, &value));
+        return;
+    }
+
+    *pos += 2 + name_length;
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "makefile", TokenType::FunctionName, &value));
+
+    // the closing bracket, if any, is the last character of the reference
+    let closed = end > *pos && matches!(input_chars[end - 1], ')' | '}');
+    let arguments_end = if closed { end - 1 } else { end };
+    handle_words(tokens, theme, TokenType::Identifier, input_chars, pos, arguments_end);
+    if closed {
+        handle_punctuation(tokens, theme, "makefile", input_chars[*pos], pos);
+    }
+}
+
+// words up to `end` as `token_type`, references within them are tokenized on their own
+fn handle_words(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    token_type: TokenType, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    end: usize
+) {
+    while *pos < end {
+        let current_char = input_chars[*pos];
+
+        if current_char.is_whitespace() {
+            let start_pos = *pos;
+            while *pos < end && input_chars[*pos].is_whitespace() {
+                *pos += 1;
+            }
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+            tokens.push(create_token(theme, "makefile", TokenType::Whitespace, &value));
+        }
+        else if current_char == '$' {
+            handle_reference(tokens, theme, input_chars, pos);
+        }
+        else if current_char == ',' {
+            handle_punctuation(tokens, theme, "makefile", current_char, pos);
+        }
+        else {
+            let start_pos = *pos;
+            while *pos < end && !input_chars[*pos].is_whitespace() && input_chars[*pos] != '$' && input_chars[*pos] != ',' {
+                *pos += 1;
+            }
+            let value: String = input_chars[start_pos..*pos].iter().collect();
+            let token_type = if value == "\\" { TokenType::Punctuation } else { token_type };
+            tokens.push(create_token(theme, "makefile", token_type, &value));
+        }
+    }
+}
+
+// A tab indented line is a shell command. `$(VAR)` references are expanded by make
+// before the shell sees the line, the text between them goes to the shell tokenizer.
+fn handle_recipe(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    // `@` (silent), `-` (ignore errors) and `+` (always run) prefixes
+    let start_pos = *pos;
+    while *pos < input_chars.len() && "@-+".contains(input_chars[*pos]) {
+        *pos += 1;
+    }
+    if *pos > start_pos {
+        let value: String = input_chars[start_pos..*pos].iter().collect();
+        tokens.push(create_token(theme, "makefile", TokenType::Operator, &value));
+    }
+
+    let mut shell_state = LineState::Normal;
+    while *pos < input_chars.len() {
+        if input_chars[*pos] == '$' && input_chars.get(*pos + 1) != Some(&'$') {
+            handle_reference(tokens, theme, input_chars, pos);
+            continue;
+        }
+        let mut end = *pos;
+        while end < input_chars.len() && (input_chars[end] != '$' || input_chars.get(end + 1) == Some(&'$')) {
+            end += if input_chars[end] == '$' { 2 } else { 1 };
+        }
+        shell_state = tokenize_embedded(tokens, theme, "shell", input_chars, pos, end.min(input_chars.len()), &shell_state);
+    }
+}
+
+pub fn tokenize_makefile_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    if input_chars.first() == Some(&'\t') {
+        handle_whitespace(&mut tokens, theme, "makefile", &input_chars[..1], &mut pos);
+        handle_recipe(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        return (tokens, state);
+    }
+
+    let comment_start = (0..input_chars.len())
+        .find(|&index| input_chars[index] == '#' && (index == 0 || input_chars[index - 1] != '\\'))
+        .unwrap_or(input_chars.len());
+    let line_operator = find_line_operator(&input_chars[..comment_start]);
+
+    // a leading directive, `ifeq (...)` or `export NAME = value`
+    let indent = input_chars.iter().take_while(|ch| ch.is_whitespace()).count();
+    if indent > 0 {
+        handle_whitespace(&mut tokens, theme, "makefile", input_chars.as_slice(), &mut pos);
+    }
+    let word_length = input_chars[pos..].iter().take_while(|ch| !ch.is_whitespace()).count();
+    let word: String = input_chars[pos..pos + word_length].iter().collect();
+    if MAKE_DIRECTIVES.contains(&word.as_str()) && line_operator.map_or(true, |(operator_pos, _)| operator_pos > pos + word_length) {
+        tokens.push(create_token(theme, "makefile", TokenType::Keyword, &word));
+        pos += word_length;
+    }
+
+    match line_operator {
+        Some((operator_pos, operator)) => {
+            let is_rule = operator == ":" || operator == "::";
+            let target_type = if !is_rule {
+                TokenType::ClassName
+            } else if word.starts_with('.') && word.split(':').next().unwrap().chars().skip(1).all(|ch| ch.is_ascii_uppercase() || ch == '_') {
+                // special targets like .PHONY
+                TokenType::Keyword
+            } else {
+                TokenType::FunctionName
+            };
+            handle_words(&mut tokens, theme, target_type, input_chars.as_slice(), &mut pos, operator_pos);
+            tokens.push(create_token(theme, "makefile", TokenType::Operator, operator));
+            pos += operator.len();
+
+            // `target: prerequisites ; recipe`
+            let recipe_start = if is_rule {
+                find_recipe_separator(input_chars.as_slice(), pos, comment_start)
+            } else {
+                None
+            };
+            handle_words(&mut tokens, theme, TokenType::Identifier, input_chars.as_slice(), &mut pos, recipe_start.unwrap_or(comment_start));
+            if let Some(recipe_start) = recipe_start {
+                // a reference running past the separator took it already
+                if pos == recipe_start {
+                    handle_punctuation(&mut tokens, theme, "makefile", input_chars[recipe_start], &mut pos);
+                }
+                handle_recipe(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+            }
+        },
+        None => {
+            handle_words(&mut tokens, theme, TokenType::Identifier, input_chars.as_slice(), &mut pos, comment_start);
+        },
+    }
+
+    if pos < input_chars.len() {
+        handle_line_comment(&mut tokens, theme, "makefile", input_chars.as_slice(), &mut pos);
+    }
+
+    (tokens, state)
+}
+
+pub struct MakefileLanguage;
+
+impl Language for MakefileLanguage {
+    fn name(&self) -> &str {
+        "makefile"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["mk", "mak", "make"]
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        vec!["Makefile", "makefile", "GNUmakefile"]
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        vec!["make"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_makefile_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_makefile() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "SOURCES := $(wildcard src/*.c)",
+            ".PHONY: all clean",
+            "app: $(SOURCES) # link",
+            "\t@$(CC) -o $@ $^ && echo done",
+            "ifeq ($(DEBUG),1)",
+        ]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("makefile", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of(0, "SOURCES"), TokenType::ClassName);
+        assert_eq!(token_of(0, ":="), TokenType::Operator);
+        assert_eq!(token_of(0, "$(wildcard"), TokenType::FunctionName);
+        assert_eq!(token_of(0, "src/*.c"), TokenType::Identifier);
+        assert_eq!(token_of(1, ".PHONY"), TokenType::Keyword);
+        assert_eq!(token_of(2, "app"), TokenType::FunctionName);
+        assert_eq!(token_of(2, "$(SOURCES)"), TokenType::ClassName);
+        assert_eq!(token_of(2, "# link"), TokenType::Comment);
+        // recipes are shell, with make's references kept apart
+        assert_eq!(token_of(3, "@"), TokenType::Operator);
+        assert_eq!(token_of(3, "$(CC)"), TokenType::ClassName);
+        assert_eq!(token_of(3, "$@"), TokenType::ClassName);
+        assert_eq!(token_of(3, "echo"), TokenType::FunctionName);
+        assert_eq!(token_of(4, "ifeq"), TokenType::Keyword);
+        assert_eq!(token_of(4, "$(DEBUG)"), TokenType::ClassName);
+
+        // the recipe starts at the `;` outside of the reference
+        let line = "deps: $(shell ls;pwd) ; echo hi";
+        let line_content = tokenize("makefile", &theme, line, &LineState::Normal);
+        assert_eq!(line_content.tokens.iter().map(|token| token.value.as_str()).collect::<String>(), line);
+        assert_eq!(line_content.tokens.iter().filter(|token| token.value == ";").count(), 1);
+        assert!(line_content.tokens.iter().any(|token| token.value == "echo" && token.token_type == TokenType::FunctionName));
+    }
+}
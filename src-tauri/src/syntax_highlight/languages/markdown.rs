@@ -0,0 +1,458 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::{Language, language_registry};
+
+// HTML comments
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "<!--", close: "-->", nested: false };
+
+// characters that may start inline markup, plain text runs stop before them
+const INLINE_MARKUP_CHARS: &str = "`*_[!<\\";
+
+fn run_length(input_chars: &[char], pos: usize, ch: char) -> usize {
+    input_chars[pos..].iter().take_while(|c| **c == ch).count()
+}
+
+// ``` or ~~~ (at least three) starting at `pos`
+fn fence_at(input_chars: &[char], pos: usize) -> Option<String> {
+    let fence_char = *input_chars.get(pos)?;
+    if fence_char != '`' && fence_char != '~' {
+        return None;
+    }
+    let length = run_length(input_chars, pos, fence_char);
+    if length < 3 {
+        return None;
+    }
+    Some(fence_char.to_string().repeat(length))
+}
+
+// a closing fence is at least as long as the opening one and alone on its line
+fn is_closing_fence(input_chars: &[char], fence: &str) -> bool {
+    let indent = input_chars.iter().take_while(|ch| ch.is_whitespace()).count();
+    match fence_at(input_chars, indent) {
+        Some(closing) => {
+            closing.starts_with(fence)
+                && input_chars[indent + closing.len()..].iter().all(|ch| ch.is_whitespace())
+        },
+        None => false,
+    }
+}
+
+// only `-`, `*`, `_` (thematic break) or `=` (setext heading underline) on the line
+fn is_rule_line(input_chars: &[char]) -> bool {
+    let chars: Vec<&char> = input_chars.iter().filter(|ch| !ch.is_whitespace()).collect();
+    chars.len() >= 3 && "-*_=".contains(*chars[0]) && chars.iter().all(|ch| *ch == chars[0])
+}
+
+// Opening fence and its info string. The code block's lines go to the tokenizer
+// of the language named in the info string, or stay plain strings without one.
+fn handle_opening_fence(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    fence: String, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    *pos += fence.chars().count();
+    tokens.push(create_token(theme, "markdown", TokenType::Punctuation, &fence));
+
+    if *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
+        handle_whitespace(tokens, theme, "markdown", input_chars, pos);
+    }
+    let info: String = input_chars[*pos..].iter().collect();
+    let tag = info.split_whitespace().next().unwrap_or("").trim_matches(|ch| ch == '{' || ch == '}' || ch == '.');
+    let language = language_registry().read().unwrap()
+        .find(tag)
+        .map(|language| language.name().to_string())
+        .unwrap_or_default();
+
+    if !info.is_empty() {
+        tokens.push(create_token(theme, "markdown", TokenType::Keyword, &info));
+    }
+    *pos = input_chars.len();
+    *state = LineState::InEmbedded { language, end: fence, state: Box::new(LineState::Normal) };
+}
+
+// a line inside a fenced code block, returns the state for the next line
+fn handle_code_block_line(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    state: LineState
+) -> LineState {
+    let LineState::InEmbedded { language, end, state: embedded_state } = state else {
+        return state;
+    };
+    let mut pos = 0;
+
+    if is_closing_fence(input_chars, &end) {
+        if input_chars[0].is_whitespace() {
+            handle_whitespace(tokens, theme, "markdown", input_chars, &mut pos);
+        }
+        let value: String = input_chars[pos..].iter().collect();
+        tokens.push(create_token(theme, "markdown", TokenType::Punctuation, &value));
+        return LineState::Normal;
+    }
+    if language.is_empty() {
+        if !input_chars.is_empty() {
+            let value: String = input_chars.iter().collect();
+            tokens.push(create_token(theme, "markdown", TokenType::StringLiteral, &value));
+        }
+        return LineState::InEmbedded { language, end, state: embedded_state };
+    }
+
+    let embedded_state = tokenize_embedded(
+        tokens, theme, &language, input_chars, &mut pos, input_chars.len(), &embedded_state
+    );
+    LineState::InEmbedded { language, end, state: Box::new(embedded_state) }
+}
+
+// `#` to `######` followed by a space, the whole heading is one token
+fn handle_heading(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let level = run_length(input_chars, *pos, '#');
+    let followed_by_space = input_chars.get(*pos + level).map_or(true, |ch| ch.is_whitespace());
+    if level == 0 || level > 6 || !followed_by_space {
+        return false;
+    }
+    let value: String = input_chars[*pos..].iter().collect();
+    tokens.push(create_token(theme, "markdown", TokenType::Keyword, &value));
+    *pos = input_chars.len();
+    true
+}
+
+// blockquote `>` markers and a list marker (`-`, `*`, `+`, `1.`, `1)`) with an optional
+// task box, all at the start of a line
+fn handle_block_markers(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    while input_chars.get(*pos) == Some(&'>') {
+        handle_operator(tokens, theme, "markdown", '>', input_chars, pos);
+        if *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
+            handle_whitespace(tokens, theme, "markdown", input_chars, pos);
+        }
+    }
+
+    let digits = input_chars[*pos..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+    let marker_len = match input_chars.get(*pos) {
+        Some('-') | Some('*') | Some('+') => 1,
+        Some(_) if (1..=9).contains(&digits) && matches!(input_chars.get(*pos + digits), Some('.') | Some(')')) => digits + 1,
+        _ => return,
+    };
+    if !input_chars.get(*pos + marker_len).map_or(true, |ch| ch.is_whitespace()) {
+        return;
+    }
+    let marker: String = input_chars[*pos..*pos + marker_len].iter().collect();
+    tokens.push(create_token(theme, "markdown", TokenType::Operator, &marker));
+    *pos += marker_len;
+
+    if *pos < input_chars.len() {
+        handle_whitespace(tokens, theme, "markdown", input_chars, pos);
+    }
+    let task_box: String = input_chars[*pos..(*pos + 3).min(input_chars.len())].iter().collect();
+    if task_box == "[ ]" || task_box == "[x]" || task_box == "[X]" {
+        tokens.push(create_token(theme, "markdown", TokenType::Keyword, &task_box));
+        *pos += 3;
+    }
+}
+
+// `code`, the closing backtick run has to be as long as the opening one
+fn handle_code_span(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let length = run_length(input_chars, *pos, '`');
+    let mut end = *pos + length;
+
+    while end < input_chars.len() {
+        let closing = run_length(input_chars, end, '`');
+        if closing == length {
+            let value: String = input_chars[*pos..end + closing].iter().collect();
+            tokens.push(create_token(theme, "markdown", TokenType::StringLiteral, &value));
+            *pos = end + closing;
+            return true;
+        }
+        end += closing.max(1);
+    }
+    false
+}
+
+// *emphasis*, **strong** and the `_` forms. An underscore inside a word
+// (snake_case) doesn't start or end emphasis.
+fn handle_emphasis(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let marker = input_chars[*pos];
+    let length = run_length(input_chars, *pos, marker).min(3);
+    let delimiter = vec![marker; length];
+    let is_word_char = |index: usize| input_chars.get(index).is_some_and(|ch| ch.is_alphanumeric());
+
+    let opens = input_chars.get(*pos + length).is_some_and(|ch| !ch.is_whitespace())
+        && !(marker == '_' && *pos > 0 && is_word_char(*pos - 1));
+    if !opens {
+        return false;
+    }
+
+    let mut end = *pos + length + 1;
+    while end + length <= input_chars.len() {
+        let closes = input_chars[end..].starts_with(&delimiter)
+            && !input_chars[end - 1].is_whitespace()
+            && !(marker == '_' && is_word_char(end + length));
+        if closes {
+            let value: String = input_chars[*pos..end + length].iter().collect();
+            tokens.push(create_token(theme, "markdown", TokenType::ClassName, &value));
+            *pos = end + length;
+            return true;
+        }
+        end += 1;
+    }
+    false
+}
+
+// [text](destination), ![alt](source) and reference links [text][label]
+fn handle_link(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let text_start = if input_chars[*pos] == '!' { *pos + 1 } else { *pos };
+    if input_chars.get(text_start) != Some(&'[') {
+        return false;
+    }
+    let Some(text_len) = input_chars[text_start..].iter().position(|ch| *ch == ']') else {
+        return false;
+    };
+    let text_end = text_start + text_len + 1;
+    let closing = match input_chars.get(text_end) {
+        Some('(') => ')',
+        Some('[') => ']',
+        _ => return false,
+    };
+    let Some(destination_len) = input_chars[text_end..].iter().position(|ch| *ch == closing) else {
+        return false;
+    };
+    let destination_end = text_end + destination_len + 1;
+
+    let text: String = input_chars[*pos..text_end].iter().collect();
+    let destination: String = input_chars[text_end..destination_end].iter().collect();
+    tokens.push(create_token(theme, "markdown", TokenType::FunctionName, &text));
+    tokens.push(create_token(theme, "markdown", TokenType::StringLiteral, &destination));
+    *pos = destination_end;
+    true
+}
+
+// <https://example.com> and <user@example.com>
+fn handle_autolink(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let Some(length) = input_chars[*pos..].iter().position(|ch| *ch == '>') else {
+        return false;
+    };
+    let inner: String = input_chars[*pos + 1..*pos + length].iter().collect();
+    if inner.contains(char::is_whitespace) || !(inner.contains("://") || inner.contains('@')) {
+        return false;
+    }
+    let value: String = input_chars[*pos..=*pos + length].iter().collect();
+    tokens.push(create_token(theme, "markdown", TokenType::StringLiteral, &value));
+    *pos += length + 1;
+    true
+}
+
+// prose up to the next whitespace or markup character, a backslash escape is part of it
+fn handle_text(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += if input_chars[*pos] == '\\' { 2 } else { 1 };
+    while *pos < input_chars.len() && !input_chars[*pos].is_whitespace() {
+        // `_` inside a word can't start emphasis
+        let intraword_underscore = input_chars[*pos] == '_' && input_chars[*pos - 1].is_alphanumeric();
+        if INLINE_MARKUP_CHARS.contains(input_chars[*pos]) && !intraword_underscore {
+            break;
+        }
+        *pos += 1;
+    }
+    *pos = (*pos).min(input_chars.len());
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "markdown", TokenType::Identifier, &value));
+}
+
+pub fn tokenize_markdown_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    if let LineState::InEmbedded { .. } = state {
+        let state = handle_code_block_line(&mut tokens, theme, input_chars.as_slice(), state);
+        return (tokens, state);
+    }
+
+    handle_line_state(
+        &mut tokens, theme, "markdown", Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    // block level markup only counts at the start of a line
+    if pos == 0 {
+        if is_rule_line(input_chars.as_slice()) {
+            tokens.push(create_token(theme, "markdown", TokenType::Punctuation, input));
+            return (tokens, state);
+        }
+        if input_chars.first().is_some_and(|ch| ch.is_whitespace()) {
+            handle_whitespace(&mut tokens, theme, "markdown", input_chars.as_slice(), &mut pos);
+        }
+        if let Some(fence) = fence_at(input_chars.as_slice(), pos) {
+            handle_opening_fence(&mut tokens, theme, fence, input_chars.as_slice(), &mut pos, &mut state);
+            return (tokens, state);
+        }
+        if handle_heading(&mut tokens, theme, input_chars.as_slice(), &mut pos) {
+            return (tokens, state);
+        }
+        handle_block_markers(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+    }
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "markdown", input_chars.as_slice(), &mut pos);
+        }
+        else if input_chars[pos..].starts_with(&['<', '!', '-', '-']) {
+            handle_block_comment(&mut tokens, theme, "markdown", &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else {
+            // markup that isn't closed on the line is plain text
+            let is_markup = match current_char {
+                '`' => handle_code_span(&mut tokens, theme, input_chars.as_slice(), &mut pos),
+                '*' | '_' => handle_emphasis(&mut tokens, theme, input_chars.as_slice(), &mut pos),
+                '[' | '!' => handle_link(&mut tokens, theme, input_chars.as_slice(), &mut pos),
+                '<' => handle_autolink(&mut tokens, theme, input_chars.as_slice(), &mut pos),
+                _ => false,
+            };
+            if !is_markup {
+                handle_text(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+            }
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct MarkdownLanguage;
+
+impl Language for MarkdownLanguage {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["md", "markdown"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        None
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_markdown_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_markdown() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let heading = tokenize("markdown", &theme, "## Getting started", &LineState::Normal);
+        assert_eq!(heading.tokens.len(), 1);
+        assert_eq!(heading.tokens[0].token_type, TokenType::Keyword);
+
+        let line_content = tokenize(
+            "markdown", 
+            &theme, 
+            "> - [x] run **cargo** `test` in snake_case, see [docs](https://x.dev)", 
+            &LineState::Normal
+        );
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of(">"), TokenType::Operator);
+        assert_eq!(token_of("-"), TokenType::Operator);
+        assert_eq!(token_of("[x]"), TokenType::Keyword);
+        assert_eq!(token_of("**cargo**"), TokenType::ClassName);
+        assert_eq!(token_of("`test`"), TokenType::StringLiteral);
+        assert_eq!(token_of("snake_case,"), TokenType::Identifier);
+        assert_eq!(token_of("[docs]"), TokenType::FunctionName);
+        assert_eq!(token_of("("), TokenType::StringLiteral);
+        assert_eq!(token_of("https://x.dev"), TokenType::Url);
+    }
+
+    #[test]
+    fn test_tokenize_markdown_fenced_code_block() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = ["```rust", "fn main() { /* open", "close */ }", "```", "fn"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("markdown", &theme, &lines);
+        assert_eq!(
+            line_contents[0].state, 
+            LineState::InEmbedded { 
+                language: "rust".to_string(), 
+                end: "```".to_string(), 
+                state: Box::new(LineState::Normal) 
+            }
+        );
+        // the block's lines get the rust tokenizer, including its own line state
+        assert_eq!(line_contents[1].tokens[0].token_type, TokenType::Keyword);
+        assert_eq!(
+            line_contents[1].state, 
+            LineState::InEmbedded { 
+                language: "rust".to_string(), 
+                end: "```".to_string(), 
+                state: Box::new(LineState::InBlockComment { depth: 1 }) 
+            }
+        );
+        assert_eq!(line_contents[2].tokens[0].token_type, TokenType::Comment);
+        assert_eq!(line_contents[3].state, LineState::Normal);
+        assert_eq!(line_contents[4].tokens[0].token_type, TokenType::Identifier);
+    }
+}
@@ -1,9 +1,301 @@
 pub mod python;
 pub mod javascript;
 pub mod typescript;
+pub mod tsx;
 pub mod rust;
+pub mod go;
+pub mod c;
+pub mod cpp;
+pub mod json;
+pub mod toml;
+pub mod yaml;
+pub mod markdown;
+pub mod css;
+pub mod html;
+pub mod vue;
+pub mod shell;
+pub mod dockerfile;
+pub mod makefile;
+pub mod plain_text;
+pub mod definition;
 
 pub use python::*;
 pub use javascript::*;
 pub use typescript::*;
+pub use tsx::*;
 pub use rust::*;
+pub use go::*;
+pub use c::*;
+pub use cpp::*;
+pub use json::*;
+pub use toml::*;
+pub use yaml::*;
+pub use markdown::*;
+pub use css::*;
+pub use html::*;
+pub use vue::*;
+pub use shell::*;
+pub use dockerfile::*;
+pub use makefile::*;
+pub use plain_text::*;
+pub use definition::*;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use regex::Regex;
+
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, LineState, BlockCommentSyntax};
+
+// language used when a file can't be matched to any registered language
+pub const DEFAULT_LANGUAGE: &str = "plaintext";
+
+// Everything the backend needs to know about a language.
+// Adding a language means implementing this trait in its own module
+// and registering it in LanguageRegistry::with_builtin_languages,
+// or describing it in a JSON file in $HOME/BaCE/languages (see definition.rs).
+pub trait Language: Send + Sync {
+    // name used in settings.json syntax_highlight languages and in the file content cache
+    fn name(&self) -> &str;
+
+    // file extensions without the leading dot
+    fn extensions(&self) -> Vec<&str>;
+
+    // file names, e.g. Makefile. A `*` matches any run of characters, e.g. Dockerfile.*
+    fn filenames(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    // programs named by a `#!` line, version suffixes are ignored: python matches python3.12
+    fn interpreters(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    fn line_comment(&self) -> Option<&str>;
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>>;
+
+    // tokenize one line starting in `state`, returns the tokens and the state at the end of the line
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState);
+}
+
+// `*` matches any run of characters and `?` any single character
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut pattern_pos, mut text_pos) = (0, 0);
+    // position of the last `*` and the text position it was tried at, to backtrack to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while text_pos < text.len() {
+        match pattern.get(pattern_pos) {
+            Some('*') => {
+                backtrack = Some((pattern_pos, text_pos));
+                pattern_pos += 1;
+            },
+            Some(ch) if *ch == '?' || *ch == text[text_pos] => {
+                pattern_pos += 1;
+                text_pos += 1;
+            },
+            _ => match backtrack {
+                Some((star_pos, star_text_pos)) => {
+                    // let the `*` take one more character
+                    backtrack = Some((star_pos, star_text_pos + 1));
+                    pattern_pos = star_pos + 1;
+                    text_pos = star_text_pos + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+    pattern[pattern_pos..].iter().all(|ch| *ch == '*')
+}
+
+// A file association key is an extension (`pyi` or `.pyi`) or a glob. Globs containing
+// a `/` are matched against the whole path, others against the file name.
+fn association_matches(pattern: &str, file_path: &str) -> bool {
+    let path = Path::new(file_path);
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+
+    if pattern.contains('/') {
+        let file_path = file_path.replace('\\', "/");
+        // `src/*.rs` should match wherever the project is
+        return glob_matches(pattern, &file_path) || glob_matches(&format!("*/{}", pattern.trim_start_matches('/')), &file_path);
+    }
+    (!ext.is_empty() && pattern.trim_start_matches('.') == ext) || glob_matches(pattern, file_name)
+}
+
+// `#!/usr/bin/env -S python3 -u` or `#!/bin/bash` to the program's name without
+// its version, "python" and "bash"
+fn shebang_interpreter(line: &str) -> Option<String> {
+    let command = line.strip_prefix("#!")?;
+    let mut words = command.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    Some(program.trim_end_matches(|ch: char| ch.is_ascii_digit() || ch == '.').to_string())
+}
+
+// modelines are only looked for this many lines from the start and the end of a file
+const MODELINE_LINES: usize = 5;
+
+// modeline names of languages that are neither a language's name nor one of its extensions
+const MODELINE_ALIASES: [(&str, &str); 1] = [("c++", "cpp")];
+
+static VIM_MODELINE: OnceLock<Regex> = OnceLock::new();
+static EMACS_MODELINE: OnceLock<Regex> = OnceLock::new();
+
+// `vim: set ft=rust:`, `vi: filetype=sh`, `-*- mode: python; coding: utf-8 -*-`
+// or `-*- c++ -*-` to the language tag
+fn modeline_language(lines: &[String]) -> Option<String> {
+    let vim = VIM_MODELINE.get_or_init(|| Regex::new(r"\b(?:vi|vim|ex):.*\b(?:ft|filetype|syntax)=([\w+-]+)").unwrap());
+    let emacs = EMACS_MODELINE.get_or_init(|| Regex::new(r"-\*-(.*?)-\*-").unwrap());
+
+    let emacs_mode = |variables: &str| -> Option<String> {
+        if !variables.contains(':') {
+            return Some(variables.trim().to_string());
+        }
+        variables.split(';')
+            .filter_map(|variable| variable.split_once(':'))
+            .find(|(name, _)| name.trim() == "mode")
+            .map(|(_, mode)| mode.trim().to_string())
+    };
+
+    let tail_start = lines.len().saturating_sub(MODELINE_LINES).max(MODELINE_LINES);
+    lines.iter()
+        .take(MODELINE_LINES)
+        .chain(lines.iter().skip(tail_start))
+        .find_map(|line| {
+            if let Some(captures) = vim.captures(line) {
+                return Some(captures[1].to_string());
+            }
+            emacs.captures(line).and_then(|captures| emacs_mode(&captures[1]))
+        })
+        .map(|tag| {
+            let alias = MODELINE_ALIASES.iter().find(|(alias, _)| tag.eq_ignore_ascii_case(alias));
+            alias.map_or(tag, |(_, language)| language.to_string())
+        })
+}
+
+pub struct LanguageRegistry {
+    languages: Vec<Arc<dyn Language>>,
+}
+
+impl LanguageRegistry {
+    pub fn new() -> LanguageRegistry {
+        LanguageRegistry { languages: Vec::new() }
+    }
+
+    pub fn with_builtin_languages() -> LanguageRegistry {
+        let mut registry = LanguageRegistry::new();
+        registry.register(Arc::new(PythonLanguage));
+        registry.register(Arc::new(JavaScriptLanguage));
+        registry.register(Arc::new(TypeScriptLanguage));
+        registry.register(Arc::new(TsxLanguage));
+        registry.register(Arc::new(RustLanguage));
+        registry.register(Arc::new(GoLanguage));
+        registry.register(Arc::new(CLanguage));
+        registry.register(Arc::new(CppLanguage));
+        registry.register(Arc::new(JsonLanguage));
+        registry.register(Arc::new(TomlLanguage));
+        registry.register(Arc::new(YamlLanguage));
+        registry.register(Arc::new(MarkdownLanguage));
+        registry.register(Arc::new(CssLanguage));
+        registry.register(Arc::new(HtmlLanguage));
+        registry.register(Arc::new(VueLanguage));
+        registry.register(Arc::new(ShellLanguage));
+        registry.register(Arc::new(DockerfileLanguage));
+        registry.register(Arc::new(MakefileLanguage));
+        registry.register(Arc::new(PlainTextLanguage));
+        registry
+    }
+
+    // a language registered with an existing name replaces the old one
+    pub fn register(&mut self, language: Arc<dyn Language>) {
+        match self.languages.iter().position(|lang| lang.name() == language.name()) {
+            Some(index) => self.languages[index] = language,
+            None => self.languages.push(language),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Language>> {
+        self.languages.iter().find(|lang| lang.name() == name).cloned()
+    }
+
+    // Match the file name, then the `#!` line, then a vim or emacs modeline
+    // in the first or last lines, then the extension.
+    pub fn detect(&self, file_path: &str, lines: &[String]) -> Option<Arc<dyn Language>> {
+        let path = Path::new(file_path);
+
+        if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
+            let matches_file_name = |lang: &&Arc<dyn Language>| {
+                lang.filenames().iter().any(|pattern| glob_matches(pattern, file_name))
+            };
+            if let Some(lang) = self.languages.iter().find(matches_file_name) {
+                return Some(Arc::clone(lang));
+            }
+        }
+        if let Some(interpreter) = lines.first().and_then(|line| shebang_interpreter(line)) {
+            if let Some(lang) = self.languages.iter().find(|lang| lang.interpreters().contains(&interpreter.as_str())) {
+                return Some(Arc::clone(lang));
+            }
+        }
+        if let Some(lang) = modeline_language(lines).and_then(|tag| self.find(&tag)) {
+            return Some(lang);
+        }
+        let ext = path.extension().and_then(|ext| ext.to_str())?;
+        self.languages.iter().find(|lang| lang.extensions().contains(&ext)).cloned()
+    }
+
+    // Language of the user's `fileAssociations` entry matching the file, the longest
+    // pattern wins when several do. Entries naming unknown languages are skipped.
+    pub fn associated(&self, file_path: &str, file_associations: &HashMap<String, String>) -> Option<Arc<dyn Language>> {
+        file_associations.iter()
+            .filter(|(pattern, _)| association_matches(pattern, file_path))
+            .filter_map(|(pattern, name)| Some((pattern, self.get(name)?)))
+            .max_by(|(pattern_a, _), (pattern_b, _)| pattern_a.len().cmp(&pattern_b.len()).then(pattern_b.cmp(pattern_a)))
+            .map(|(_, lang)| lang)
+    }
+
+    // language named `tag` or using it as extension, e.g. the info string
+    // of a fenced code block: ```rust or ```py
+    pub fn find(&self, tag: &str) -> Option<Arc<dyn Language>> {
+        let tag = tag.to_lowercase();
+        self.get(&tag)
+            .or_else(|| self.languages.iter().find(|lang| lang.extensions().contains(&tag.as_str())).cloned())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.languages.iter().map(|lang| lang.name().to_string()).collect()
+    }
+}
+
+impl Default for LanguageRegistry {
+    fn default() -> Self {
+        LanguageRegistry::new()
+    }
+}
+
+static LANGUAGE_REGISTRY: OnceLock<RwLock<LanguageRegistry>> = OnceLock::new();
+
+pub fn language_registry() -> &'static RwLock<LanguageRegistry> {
+    LANGUAGE_REGISTRY.get_or_init(|| RwLock::new(LanguageRegistry::with_builtin_languages()))
+}
+
+// registered language by name, falls back to DEFAULT_LANGUAGE
+pub fn get_language(name: &str) -> Arc<dyn Language> {
+    let registry = language_registry().read().unwrap();
+    registry.get(name)
+        .or_else(|| registry.get(DEFAULT_LANGUAGE))
+        .expect("default language is always registered")
+}
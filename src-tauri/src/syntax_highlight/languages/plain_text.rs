@@ -0,0 +1,70 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::languages::Language;
+
+// Files no language matches. The line is one Unknown token without a color of its own,
+// the theme gives plain text the editor's text color rather than a highlight color from settings.json.
+pub fn tokenize_plain_text(theme: &Theme, input: &str) -> (Vec<Token>, LineState) {
+    if input.is_empty() {
+        return (Vec::new(), LineState::Normal);
+    }
+    (vec![create_token(theme, "plaintext", TokenType::Unknown, input)], LineState::Normal)
+}
+
+pub struct PlainTextLanguage;
+
+impl Language for PlainTextLanguage {
+    fn name(&self) -> &str {
+        "plaintext"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["txt", "text", "log"]
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        vec![".gitignore", ".dockerignore", ".npmignore", "LICENSE", "CODEOWNERS"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        None
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        _state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_plain_text(theme, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_plain_text() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("plaintext", &theme, "def not_python(): pass", &LineState::Normal);
+
+        assert_eq!(line_content.tokens.len(), 1);
+        assert_eq!(line_content.tokens[0].token_type, TokenType::Unknown);
+        assert_eq!(&*line_content.tokens[0].color, "inherit");
+        // every line shares the theme's color
+        let next_line = tokenize("plaintext", &theme, "more text", &LineState::Normal);
+        assert!(Arc::ptr_eq(&line_content.tokens[0].color, &next_line.tokens[0].color));
+        // unknown languages get plain text too
+        let fallback = tokenize("no-such-language", &theme, "x = 1", &LineState::Normal);
+        assert_eq!(&*fallback.tokens[0].color, "inherit");
+    }
+}
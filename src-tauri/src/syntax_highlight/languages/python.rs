@@ -1,5 +1,7 @@
-use crate::settings::Settings;
-use crate::syntax_highlight::{Token, TokenType, create_token};
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
 
 const PYTHON_KEYWORDS: [&str; 38] = [
     "and", "as", "assert", "break", "class", "continue", 
@@ -11,116 +13,329 @@ const PYTHON_KEYWORDS: [&str; 38] = [
     "match", "case"
 ];
 
-fn handle_whitespace(
+const PYTHON_BUILTINS: [&str; 70] = [
+    "abs", "aiter", "all", "anext", "any", "ascii", "bin", "bool", "breakpoint", "bytearray",
+    "bytes", "callable", "chr", "classmethod", "compile", "complex", "delattr", "dict", "dir",
+    "divmod", "enumerate", "eval", "exec", "filter", "float", "format", "frozenset", "getattr",
+    "globals", "hasattr", "hash", "help", "hex", "id", "input", "int", "isinstance", "issubclass",
+    "iter", "len", "list", "locals", "map", "max", "memoryview", "min", "next", "object", "oct",
+    "open", "ord", "pow", "print", "property", "range", "repr", "reversed", "round", "set",
+    "setattr", "slice", "sorted", "staticmethod", "str", "sum", "super", "tuple", "type", "vars", "zip"
+];
+
+// Length of the `r`, `b`, `u`, `f`, `t` prefix (in any case, e.g. `rb` or `Rf`)
+// when a string literal starts at `pos`.
+fn string_prefix_length(input_chars: &[char], pos: usize) -> Option<usize> {
+    let prefix: String = input_chars[pos..].iter()
+        .take(2)
+        .take_while(|ch| "rRbBuUfFtT".contains(**ch))
+        .collect::<String>()
+        .to_lowercase();
+
+    for prefix_length in (1..=prefix.len()).rev() {
+        let is_prefix = matches!(
+            &prefix[..prefix_length],
+            "r" | "b" | "u" | "f" | "t" | "rb" | "br" | "fr" | "rf" | "tr" | "rt"
+        );
+        if is_prefix && matches!(input_chars.get(pos + prefix_length), Some('"') | Some('\'')) {
+            return Some(prefix_length);
+        }
+    }
+    None
+}
+
+// Position of the character ending the expression of a replacement field starting at
+// `pos`: the closing `}`, a `!r` conversion or the `:` of a format spec, or the end of
+// the line. Brackets and strings inside the expression are skipped.
+fn replacement_field_expression_end(input_chars: &[char], mut pos: usize) -> usize {
+    let mut depth = 0;
+    while pos < input_chars.len() {
+        match input_chars[pos] {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' => depth -= 1,
+            '}' if depth == 0 => return pos,
+            '}' => depth -= 1,
+            '!' if depth == 0 && input_chars.get(pos + 1) != Some(&'=') => return pos,
+            ':' if depth == 0 => return pos,
+            quote @ ('"' | '\'') => {
+                pos += 1;
+                scan_string_body(input_chars, &mut pos, &quote.to_string(), false);
+                continue;
+            },
+            _ => (),
+        }
+        pos += 1;
+    }
+    pos
+}
+
+// `{expression!r:format spec}` of an f-string, the expression is tokenized as code
+// and the format spec as string, it may hold replacement fields of its own: `{x:{width}}`.
+// Returns how many fields are still open when the line ends, this one and those in its spec
+fn handle_replacement_field(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
-) {
-    let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
-        *pos += 1;
+) -> u32 {
+    handle_punctuation(tokens, theme, "python", '{', pos);
+    finish_replacement_field(tokens, theme, input_chars, pos)
+}
+
+// the rest of a replacement field from its expression on
+fn finish_replacement_field(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> u32 {
+    let expression_end = replacement_field_expression_end(input_chars, *pos);
+    let expression: String = input_chars[*pos..expression_end].iter().collect();
+    let (expression_tokens, _) = tokenize_python_code(theme, &expression, &LineState::Normal);
+    tokens.extend(expression_tokens);
+    *pos = expression_end;
+
+    // `!r`, `!s` or `!a` conversion
+    if input_chars.get(*pos) == Some(&'!') {
+        let conversion_end = (*pos + 2).min(input_chars.len());
+        let value: String = input_chars[*pos..conversion_end].iter().collect();
+        tokens.push(create_token(theme, "python", TokenType::Operator, &value));
+        *pos = conversion_end;
     }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "python", TokenType::Whitespace, &value));
+    if input_chars.get(*pos) == Some(&':') {
+        handle_punctuation(tokens, theme, "python", ':', pos);
+        return finish_format_spec(tokens, theme, input_chars, pos);
+    }
+    if input_chars.get(*pos) == Some(&'}') {
+        handle_punctuation(tokens, theme, "python", '}', pos);
+        return 0;
+    }
+    u32::from(*pos >= input_chars.len())
 }
 
-fn handle_comment(
+// the rest of a format spec up to and including the `}` closing its field
+fn finish_format_spec(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
+) -> u32 {
+    let mut spec_start = *pos;
+    while *pos < input_chars.len() && input_chars[*pos] != '}' {
+        if input_chars[*pos] == '{' {
+            push_string_segment(tokens, theme, input_chars, spec_start, *pos);
+            let open_fields = handle_replacement_field(tokens, theme, input_chars, pos);
+            if open_fields > 0 {
+                return open_fields + 1;
+            }
+            spec_start = *pos;
+        } else {
+            *pos += 1;
+        }
+    }
+    push_string_segment(tokens, theme, input_chars, spec_start, *pos);
+    if input_chars.get(*pos) == Some(&'}') {
+        handle_punctuation(tokens, theme, "python", '}', pos);
+        return 0;
+    }
+    1
+}
+
+// Replacement fields left open on the previous line, `fields` of them. The innermost
+// goes on with its expression, the ones around it with their format spec.
+// Returns how many are still open when this line ends
+fn resume_replacement_fields(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    fields: u32
+) -> u32 {
+    let mut open_fields = finish_replacement_field(tokens, theme, input_chars, pos);
+    let mut outer_fields = fields - 1;
+    while open_fields == 0 && outer_fields > 0 {
+        open_fields = finish_format_spec(tokens, theme, input_chars, pos);
+        outer_fields -= 1;
+    }
+    open_fields + outer_fields
+}
+
+fn push_string_segment(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    start_pos: usize, 
+    end: usize
 ) {
-    let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos] != '\n' {
-        *pos += 1;
+    if end > start_pos {
+        let value: String = input_chars[start_pos..end].iter().collect();
+        tokens.push(create_token(theme, "python", TokenType::StringLiteral, &value));
     }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "python", TokenType::Comment, &value));
 }
 
+// Body of an f-string (or t-string) from `pos` up to and including `delimiter`.
+// Text between replacement fields is string, `{{` and `}}` are escaped braces.
+// `segment_start` is where the string token being built began, e.g. at its prefix.
+// Returns false when the line ends first, `open_fields` is then the number of
+// replacement fields the line ends in.
+fn handle_interpolated_string_body(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    mut segment_start: usize, 
+    delimiter: &str, 
+    open_fields: &mut u32
+) -> bool {
+    let delimiter_chars: Vec<char> = delimiter.chars().collect();
+
+    while *pos < input_chars.len() {
+        if input_chars[*pos] == '\\' {
+            *pos = (*pos + 2).min(input_chars.len());
+        } else if input_chars[*pos..].starts_with(&delimiter_chars) {
+            *pos += delimiter_chars.len();
+            push_string_segment(tokens, theme, input_chars, segment_start, *pos);
+            return true;
+        } else if input_chars[*pos..].starts_with(&['{', '{']) || input_chars[*pos..].starts_with(&['}', '}']) {
+            *pos += 2;
+        } else if input_chars[*pos] == '{' {
+            push_string_segment(tokens, theme, input_chars, segment_start, *pos);
+            *open_fields = handle_replacement_field(tokens, theme, input_chars, pos);
+            if *open_fields > 0 {
+                return false;
+            }
+            segment_start = *pos;
+        } else {
+            *pos += 1;
+        }
+    }
+    push_string_segment(tokens, theme, input_chars, segment_start, *pos);
+    false
+}
+
+// A string literal with its prefix of `prefix_length` characters.
+// Raw strings still can't contain their unescaped quote, so all strings are scanned alike.
 fn handle_string_literal(
     tokens: &mut Vec<Token>, 
-    settings: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
-    pos: &mut usize
+    pos: &mut usize, 
+    prefix_length: usize, 
+    state: &mut LineState
 ) {
-    let start_char = input_chars[*pos];
     let start_pos = *pos;
-    *pos += 1; // Move past the opening quote
+    let prefix: String = input_chars[*pos..*pos + prefix_length].iter().collect::<String>().to_lowercase();
+    *pos += prefix_length;
+    let quote = input_chars[*pos];
 
-    // Check if this is a triple-quoted string
-    let is_triple_quote = *pos + 1 < input_chars.len() && input_chars[*pos] == start_char && input_chars[*pos + 1] == start_char;
+    // triple-quoted strings, docstrings usually span multiple lines
+    let is_triple_quote = input_chars[*pos..].starts_with(&[quote, quote, quote]);
+    let delimiter = if is_triple_quote { quote.to_string().repeat(3) } else { quote.to_string() };
+    *pos += delimiter.len(); // Move past the opening quotes
 
-    if is_triple_quote {
-        *pos += 2; // Move past the opening triple quotes
+    if prefix.contains('f') || prefix.contains('t') {
+        let mut fields = 0;
+        let closed = handle_interpolated_string_body(tokens, theme, input_chars, pos, start_pos, &delimiter, &mut fields);
+        if !closed && is_triple_quote {
+            *state = LineState::InInterpolatedString { delimiter, fields };
+        }
+        return;
+    }
 
-        // Process the content inside the triple quotes
-        while *pos + 2 < input_chars.len() {
-            // Check if we've reached the closing triple quotes
-            if input_chars[*pos] == start_char && input_chars[*pos + 1] == start_char && input_chars[*pos + 2] == start_char {
-                *pos += 3; // Move past the closing triple quotes
-                break;
-            }
+    let closed = scan_string_body(input_chars, pos, &delimiter, false);
+    if !closed && is_triple_quote {
+        *state = LineState::InString { delimiter, raw: false };
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "python", TokenType::StringLiteral, &value));
+}
+
+// `0xFF`, `0o777`, `0b1010`, `1_000_000`, `3.14`, `.5`, `1e-10`, `2j`, `1.5e3J`
+fn handle_python_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    let scan_digits = |pos: &mut usize, radix: u32| {
+        while *pos < input_chars.len() && (input_chars[*pos].is_digit(radix) || input_chars[*pos] == '_') {
             *pos += 1;
         }
+    };
+
+    let radix = match (input_chars[*pos], input_chars.get(*pos + 1)) {
+        ('0', Some('x' | 'X')) => 16,
+        ('0', Some('o' | 'O')) => 8,
+        ('0', Some('b' | 'B')) => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        *pos += 2; // Move past the `0x`
+        scan_digits(pos, radix);
     } else {
-        // Handle single-line strings (delimited by ' or ")
-        while *pos < input_chars.len() && input_chars[*pos] != start_char {
-            // Allow for escaped quotes within single-line strings
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
+        scan_digits(pos, 10);
+        if input_chars.get(*pos) == Some(&'.') {
+            *pos += 1;
+            scan_digits(pos, 10);
+        }
+        if matches!(input_chars.get(*pos), Some('e' | 'E')) {
+            let sign_length = usize::from(matches!(input_chars.get(*pos + 1), Some('+' | '-')));
+            if input_chars.get(*pos + 1 + sign_length).is_some_and(|ch| ch.is_ascii_digit()) {
+                *pos += 1 + sign_length;
+                scan_digits(pos, 10);
             }
+        }
+        // imaginary number
+        if matches!(input_chars.get(*pos), Some('j' | 'J')) {
             *pos += 1;
         }
-        *pos += 1; // Move past the closing quote
     }
 
-    // Collect the entire string value including quotes
-    let value: String = input_chars[start_pos..*pos.min(&mut input_chars.len())].iter().collect();
-    tokens.push(create_token(settings, "python", TokenType::StringLiteral, &value));
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "python", TokenType::Number, &value));
 }
 
-
-fn handle_number(
+// `@property` or `@app.route` at the start of a line, anywhere else `@` is matrix multiplication
+fn handle_decorator(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_digit(10) {
+    *pos += 1; // Move past the `@`
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_' || input_chars[*pos] == '.') {
         *pos += 1;
     }
     let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "python", TokenType::Number, &value));
+    tokens.push(create_token(theme, "python", TokenType::FunctionName, &value));
 }
 
 fn handle_return_operator(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     pos: &mut usize
 ) {
     let value = "->".to_string();
-    tokens.push(create_token(settings_config, "python", TokenType::Operator, &value));
+    tokens.push(create_token(theme, "python", TokenType::Operator, &value));
     *pos += 2;
 }
 
 fn handle_operator(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     current_char: char, 
     pos: &mut usize
 ) {
     let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "python", TokenType::Operator, &value));
+    tokens.push(create_token(theme, "python", TokenType::Operator, &value));
     *pos += 1;
 }
 
 fn handle_identifier(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
@@ -131,8 +346,13 @@ fn handle_identifier(
     let value: String = input_chars[start_pos..*pos].iter().collect();
     let next_char = input_chars.get(*pos).unwrap_or(&' ');
 
-    if *next_char == '(' || input_chars.get(start_pos.wrapping_sub(1)) == Some(&'.') {
-        tokens.push(create_token(settings_config, "python", TokenType::FunctionName, &value));
+    let is_attribute = input_chars.get(start_pos.wrapping_sub(1)) == Some(&'.');
+
+    // builtins can be shadowed by attributes, `self.print()` is a method
+    if PYTHON_BUILTINS.contains(&value.as_str()) && !is_attribute {
+        tokens.push(create_token(theme, "python", TokenType::Builtin, &value));
+    } else if *next_char == '(' || is_attribute {
+        tokens.push(create_token(theme, "python", TokenType::FunctionName, &value));
     } else {
         let token_type;
         if PYTHON_KEYWORDS.contains(&value.as_str()) {
@@ -142,68 +362,236 @@ fn handle_identifier(
         } else {
             token_type = TokenType::Identifier;
         }
-        tokens.push(create_token(settings_config, "python", token_type, &value));
+        tokens.push(create_token(theme, "python", token_type, &value));
     }
 }
 
-fn handle_punctuation(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
-) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "python", TokenType::Punctuation, &value));
-    *pos += 1;
-}
-
-fn handle_unknown(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
-) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "python", TokenType::Unknown, &value));
-    *pos += 1;
-}
-
-pub fn tokenize_python_code(settings_config: &Settings, input: &str) -> Vec<Token> {
+pub fn tokenize_python_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
     let mut tokens = Vec::new();
     let mut pos = 0;
+    let mut state = state.clone();
     let input_chars: Vec<char> = input.chars().collect();
 
+    // python has no block comments, only strings can span lines
+    handle_line_state(
+        &mut tokens, theme, "python", None, 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+    if let LineState::InInterpolatedString { delimiter, fields } = state.clone() {
+        let mut fields = match fields {
+            0 => 0,
+            _ => resume_replacement_fields(&mut tokens, theme, input_chars.as_slice(), &mut pos, fields),
+        };
+        let string_pos = pos;
+        if fields > 0 {
+            state = LineState::InInterpolatedString { delimiter, fields };
+        }
+        else if handle_interpolated_string_body(&mut tokens, theme, input_chars.as_slice(), &mut pos, string_pos, &delimiter, &mut fields) {
+            state = LineState::Normal;
+        }
+        else {
+            state = LineState::InInterpolatedString { delimiter, fields };
+        }
+    }
+
     while pos < input_chars.len() {
         let current_char = input_chars[pos];
+        let previous_char = input_chars.get(pos.wrapping_sub(1)).copied().unwrap_or(' ');
 
         if current_char.is_whitespace() {
-            handle_whitespace(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_whitespace(&mut tokens, theme, "python", input_chars.as_slice(), &mut pos);
         } 
         else if current_char == '#' {
-            handle_comment(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_line_comment(&mut tokens, theme, "python", input_chars.as_slice(), &mut pos);
         } 
         else if current_char == '"' || current_char == '\'' {
-            handle_string_literal(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, 0, &mut state);
+        } 
+        else if let Some(prefix_length) = string_prefix_length(input_chars.as_slice(), pos) {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, prefix_length, &mut state);
+        } 
+        else if current_char.is_ascii_digit() 
+            || (current_char == '.' && input_chars.get(pos + 1).is_some_and(|ch| ch.is_ascii_digit()) && !previous_char.is_alphanumeric() && !")]_".contains(previous_char)) {
+            handle_python_number(&mut tokens, theme, input_chars.as_slice(), &mut pos);
         } 
-        else if current_char.is_digit(10) {
-            handle_number(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+        else if current_char == '@' && input_chars[..pos].iter().all(|ch| ch.is_whitespace()) {
+            handle_decorator(&mut tokens, theme, input_chars.as_slice(), &mut pos);
         } 
         else if current_char == '-' && input_chars.get(pos + 1) == Some(&'>') {
-            handle_return_operator(&mut tokens, settings_config, &mut pos);
+            handle_return_operator(&mut tokens, theme, &mut pos);
         } 
-        else if "+-*/=%".contains(current_char) {
-            handle_operator(&mut tokens, settings_config, current_char, &mut pos);
+        else if "+-*/=%<>!&|^~@".contains(current_char) {
+            handle_operator(&mut tokens, theme, current_char, &mut pos);
         } 
         else if current_char.is_alphabetic() || current_char == '_' {
-            handle_identifier(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_identifier(&mut tokens, theme, input_chars.as_slice(), &mut pos);
         } 
         else if ",.:;()[]{}'\"".contains(current_char) {
-            handle_punctuation(&mut tokens, settings_config, current_char, &mut pos);
+            handle_punctuation(&mut tokens, theme, "python", current_char, &mut pos);
         } 
         else {
-            handle_unknown(&mut tokens, settings_config, current_char, &mut pos);
+            handle_unknown(&mut tokens, theme, "python", current_char, &mut pos);
         }
     }
 
-    tokens
-}
\ No newline at end of file
+    (tokens, state)
+}
+
+pub struct PythonLanguage;
+
+impl Language for PythonLanguage {
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["py"]
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        vec!["python"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_python_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_python_docstring_state() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let first = tokenize("python", &theme, "    \"\"\"Docstring", &LineState::Normal);
+        assert_eq!(
+            first.state, 
+            LineState::InString { delimiter: "\"\"\"".to_string(), raw: false }
+        );
+        let second = tokenize("python", &theme, "", &first.state);
+        assert!(second.tokens.is_empty());
+        assert_eq!(second.state, first.state);
+
+        let third = tokenize("python", &theme, "end\"\"\" + x", &second.state);
+        assert_eq!(third.tokens[0].token_type, TokenType::StringLiteral);
+        assert_eq!(third.state, LineState::Normal);
+    }
+
+    #[test]
+    fn test_tokenize_python_fstrings() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("python", &theme, "msg = f\"{x + 1} {{literal}} {name!r:>{width}}\"", &LineState::Normal);
+        let values: Vec<(&str, TokenType)> = line_content.tokens.iter().map(|token| (token.value.as_str(), token.token_type)).collect();
+
+        assert_eq!(&values[4..], [
+            ("f\"", TokenType::StringLiteral),
+            ("{", TokenType::Punctuation),
+            ("x", TokenType::Identifier),
+            (" ", TokenType::Whitespace),
+            ("+", TokenType::Operator),
+            (" ", TokenType::Whitespace),
+            ("1", TokenType::Number),
+            ("}", TokenType::Punctuation),
+            (" {{literal}} ", TokenType::StringLiteral),
+            ("{", TokenType::Punctuation),
+            ("name", TokenType::Identifier),
+            ("!r", TokenType::Operator),
+            (":", TokenType::Punctuation),
+            (">", TokenType::StringLiteral),
+            ("{", TokenType::Punctuation),
+            ("width", TokenType::Identifier),
+            ("}", TokenType::Punctuation),
+            ("}", TokenType::Punctuation),
+            ("\"", TokenType::StringLiteral),
+        ]);
+
+        // triple-quoted f-strings keep their fields on the following lines
+        let first = tokenize("python", &theme, "text = rf'''{path}", &LineState::Normal);
+        assert_eq!(first.state, LineState::InInterpolatedString { delimiter: "'''".to_string(), fields: 0 });
+        let second = tokenize("python", &theme, "{len(items)} done''' + x", &first.state);
+        assert_eq!(second.tokens[1].value, "len");
+        assert_eq!(second.tokens[1].token_type, TokenType::Builtin);
+        assert_eq!(second.state, LineState::Normal);
+
+        // a field open at the end of the line goes on as code
+        let first = tokenize("python", &theme, "f'''multi {", &LineState::Normal);
+        assert_eq!(first.state, LineState::InInterpolatedString { delimiter: "'''".to_string(), fields: 1 });
+        let second = tokenize("python", &theme, "x} end'''", &first.state);
+        let values: Vec<(&str, TokenType)> = second.tokens.iter().map(|token| (token.value.as_str(), token.token_type)).collect();
+        assert_eq!(values, [
+            ("x", TokenType::Identifier),
+            ("}", TokenType::Punctuation),
+            (" end'''", TokenType::StringLiteral),
+        ]);
+        assert_eq!(second.state, LineState::Normal);
+
+        // a field nested in a format spec returns to the spec once it closes
+        let first = tokenize("python", &theme, "f'''{value:{", &LineState::Normal);
+        assert_eq!(first.state, LineState::InInterpolatedString { delimiter: "'''".to_string(), fields: 2 });
+        let second = tokenize("python", &theme, "width}x} done", &first.state);
+        let values: Vec<(&str, TokenType)> = second.tokens.iter().map(|token| (token.value.as_str(), token.token_type)).collect();
+        assert_eq!(values, [
+            ("width", TokenType::Identifier),
+            ("}", TokenType::Punctuation),
+            ("x", TokenType::StringLiteral),
+            ("}", TokenType::Punctuation),
+            (" done", TokenType::StringLiteral),
+        ]);
+        assert_eq!(second.state, LineState::InInterpolatedString { delimiter: "'''".to_string(), fields: 0 });
+    }
+
+    #[test]
+    fn test_tokenize_python_prefixes_decorators_and_builtins() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "@app.route(\"/\")",
+            "def view(data: bytes = rb'\\x00', name = u'x') -> None:",
+            "    print(len(data), isinstance(name, str), self.print(), a @ b)",
+        ].iter().map(|line| line.to_string()).collect();
+        let line_contents = tokenize_lines("python", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of(0, "@app.route"), TokenType::FunctionName);
+        assert_eq!(token_of(1, "rb'\\x00'"), TokenType::StringLiteral);
+        assert_eq!(token_of(1, "u'x'"), TokenType::StringLiteral);
+        assert_eq!(token_of(2, "print"), TokenType::Builtin);
+        assert_eq!(token_of(2, "len"), TokenType::Builtin);
+        assert_eq!(token_of(2, "isinstance"), TokenType::Builtin);
+        assert_eq!(token_of(2, "str"), TokenType::Builtin);
+        assert_eq!(token_of(2, "@"), TokenType::Operator);
+        // a method with a builtin's name is still a method
+        assert_eq!(line_contents[2].tokens.iter().rfind(|token| token.value == "print").unwrap().token_type, TokenType::FunctionName);
+    }
+
+    #[test]
+    fn test_tokenize_python_numbers() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("python", &theme, "[0xFF, 0o17, 0B1010, 1_000_000, 3.14, .5, 1e-10, 2j, 1.5E3J, 10.]", &LineState::Normal);
+        let numbers: Vec<&str> = line_content.tokens.iter()
+            .filter(|token| token.token_type == TokenType::Number)
+            .map(|token| token.value.as_str())
+            .collect();
+        assert_eq!(numbers, ["0xFF", "0o17", "0B1010", "1_000_000", "3.14", ".5", "1e-10", "2j", "1.5E3J", "10."]);
+    }
+}
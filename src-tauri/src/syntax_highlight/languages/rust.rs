@@ -1,5 +1,7 @@
-use crate::settings::Settings;
-use crate::syntax_highlight::{Token, TokenType, create_token};
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
 
 const RUST_KEYWORDS: [&str; 41] = [
     "as", "break", "const", "continue", "crate", "else", "enum", "extern",
@@ -9,9 +11,11 @@ const RUST_KEYWORDS: [&str; 41] = [
     "while", "async", "await", "dyn", "abstract", "final", "override"
 ];
 
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: true };
+
 fn handle_macro(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
@@ -22,274 +26,378 @@ fn handle_macro(
     if *pos < input_chars.len() && input_chars[*pos] == '!' {
         *pos += 1; // Include the '!' in the macro name
         let value: String = input_chars[start_pos..*pos].iter().collect();
-        tokens.push(create_token(settings_config, "rust", TokenType::FunctionName, &value));
+        tokens.push(create_token(theme, "rust", TokenType::FunctionName, &value));
     }
 }
 
+// `'a`, `'static`, `'_` and loop labels like `'outer`, or a raw lifetime `'r#ident`
+fn is_lifetime(input_chars: &[char], pos: usize) -> bool {
+    let is_ident_start = |ch: Option<&char>| ch.is_some_and(|ch| ch.is_alphabetic() || *ch == '_');
+    // `'a'` is a char literal, only a lifetime when no quote follows the first character
+    is_ident_start(input_chars.get(pos + 1)) && input_chars.get(pos + 2) != Some(&'\'')
+}
+
 fn handle_lifetime(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
     *pos += 1; // Move past the apostrophe
-    while *pos < input_chars.len() && input_chars[*pos].is_alphanumeric() {
+    if input_chars[*pos..].starts_with(&['r', '#']) {
+        *pos += 2;
+    }
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
         *pos += 1;
     }
     let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "rust", TokenType::Identifier, &value));
+    tokens.push(create_token(theme, "rust", TokenType::Lifetime, &value));
 }
 
-fn handle_string_literal(
-    tokens: &mut Vec<Token>, 
-    settings: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_char = input_chars[*pos];
-    let start_pos = *pos;
-    *pos += 1; // Move past the opening quote
-
-    if start_char == 'r' {
-        // Handle raw strings in Rust
-        let mut hash_count = 0;
-        while *pos < input_chars.len() && input_chars[*pos] == '#' {
-            hash_count += 1;
-            *pos += 1;
-        }
+// Length of the `b`, `r`, `br`, `c` or `cr` prefix (with the `#`s of a raw string)
+// when a string or byte literal starts at `pos`, e.g. 3 for `br#"`.
+fn string_prefix_length(input_chars: &[char], pos: usize) -> Option<usize> {
+    let letters = input_chars[pos..].iter().take(2).take_while(|ch| matches!(ch, 'b' | 'r' | 'c')).count();
 
-        // Check if it's a raw string with double quotes after the hashes
-        if *pos < input_chars.len() && input_chars[*pos] == '"' {
-            *pos += 1; // Move past the opening quote
-            let closing_sequence: Vec<char> = std::iter::repeat('#').take(hash_count).collect();
-
-            // Process until we find the closing raw string sequence
-            while *pos + hash_count < input_chars.len() {
-                if input_chars[*pos] == '"' && &input_chars[*pos + 1..*pos + 1 + hash_count] == closing_sequence.as_slice() {
-                    *pos += 1 + hash_count; // Move past the closing quote and hashes
-                    break;
-                }
-                *pos += 1;
-            }
+    for prefix_length in (1..=letters).rev() {
+        let prefix: String = input_chars[pos..pos + prefix_length].iter().collect();
+        if !matches!(prefix.as_str(), "b" | "r" | "br" | "c" | "cr") {
+            continue;
         }
-    } else if start_char == '\'' {
-        // Handle single-quoted characters
-        while *pos < input_chars.len() && input_chars[*pos] != '\'' {
-            // Allow escaped single-quote within single character
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
-            }
-            *pos += 1;
-        }
-        *pos += 1; // Move past the closing single quote
-    } else {
-        // Handle double-quoted strings
-        while *pos < input_chars.len() && input_chars[*pos] != '"' {
-            // Allow for escaped quotes within double-quoted strings
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
-            }
-            *pos += 1;
+        let hashes = if prefix.ends_with('r') {
+            input_chars[pos + prefix_length..].iter().take_while(|ch| **ch == '#').count()
+        } else {
+            0
+        };
+        match input_chars.get(pos + prefix_length + hashes) {
+            Some('"') => return Some(prefix_length + hashes),
+            Some('\'') if prefix == "b" => return Some(prefix_length),
+            _ => (),
         }
-        *pos += 1; // Move past the closing double quote
     }
-
-    // Collect the entire string value including quotes
-    let value: String = input_chars[start_pos..*pos.min(&mut input_chars.len())].iter().collect();
-    tokens.push(create_token(settings, "rust", TokenType::StringLiteral, &value));
+    None
 }
 
-
-fn handle_attribute(
+// `"..."`, `'c'` and their prefixed forms `b"..."`, `b'c'`, `r#"..."#`, `br"..."`, `c"..."`.
+// Raw strings have no escapes and end at a quote followed by as many `#`s as they
+// started with, strings and raw strings may continue on the next line.
+fn handle_string_literal(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
-    pos: &mut usize
+    pos: &mut usize, 
+    prefix_length: usize, 
+    state: &mut LineState
 ) {
     let start_pos = *pos;
-    *pos += 1; // Move past the opening `#`
+    let prefix: String = input_chars[*pos..*pos + prefix_length].iter().collect();
+    *pos += prefix_length;
+    let quote = input_chars[*pos];
+    *pos += 1; // Move past the opening quote
 
-    if *pos < input_chars.len() && input_chars[*pos] == '[' {
-        *pos += 1;
-        while *pos < input_chars.len() && input_chars[*pos] != ']' {
-            *pos += 1;
+    if quote == '\'' {
+        // char literals never span lines
+        scan_string_body(input_chars, pos, "'", false);
+    } else {
+        let raw = prefix.contains('r');
+        let delimiter = format!("\"{}", "#".repeat(prefix.matches('#').count()));
+        if !scan_string_body(input_chars, pos, &delimiter, raw) {
+            *state = LineState::InString { delimiter, raw };
         }
-        *pos += 1; // Move past the closing `]`
     }
 
     let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "rust", TokenType::FunctionName, &value));
+    tokens.push(create_token(theme, "rust", TokenType::StringLiteral, &value));
 }
 
-fn handle_whitespace(
+// `r#type`, an identifier that may be spelled like a keyword
+fn handle_raw_identifier(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
-        *pos += 1;
-    }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "rust", TokenType::Whitespace, &value));
-}
-
-fn handle_comment(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos] != '\n' {
+    *pos += 2; // Move past `r#`
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
         *pos += 1;
     }
     let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "rust", TokenType::Comment, &value));
+    let token_type = if input_chars.get(*pos) == Some(&'(') {
+        TokenType::FunctionName
+    } else if value[2..].starts_with(char::is_uppercase) {
+        TokenType::ClassName
+    } else {
+        TokenType::Identifier
+    };
+    tokens.push(create_token(theme, "rust", token_type, &value));
 }
 
-fn handle_number(
+// `0xFF`, `0o777`, `0b1010_1010`, `1_000`, `3.14f32`, `1e-3`, `10u8`.
+// A `.` only belongs to the number when a digit or nothing number-like follows,
+// so `1..2`, `1.max(2)` and the tuple index in `pair.0.1` stay separate tokens.
+fn handle_rust_number(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_digit(10) {
-        *pos += 1;
-    }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "rust", TokenType::Number, &value));
-}
-
-fn handle_operator(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let mut value = current_char.to_string();
-    *pos += 1;
-
-    // Handle multi-character operators like ==, ===, =>, etc.
-    if let Some(next_char) = input_chars.get(*pos) {
-        if current_char == '=' && (*next_char == '=' || *next_char == '>') {
-            value.push(*next_char);
+    let is_digit_or_underscore = |ch: &char, radix: u32| ch.is_digit(radix) || *ch == '_';
+    let scan_digits = |pos: &mut usize, radix: u32| {
+        while *pos < input_chars.len() && is_digit_or_underscore(&input_chars[*pos], radix) {
             *pos += 1;
+        }
+    };
 
-            // Handle === case
-            if *next_char == '=' && *pos < input_chars.len() && input_chars[*pos] == '=' {
-                value.push('=');
-                *pos += 1;
+    let radix = match (input_chars[*pos], input_chars.get(*pos + 1)) {
+        ('0', Some('x')) => 16,
+        ('0', Some('o')) => 8,
+        ('0', Some('b')) => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        *pos += 2; // Move past the `0x`
+        scan_digits(pos, radix);
+    } else {
+        scan_digits(pos, 10);
+        let is_tuple_index = start_pos > 0 && input_chars[start_pos - 1] == '.'
+            && (start_pos < 2 || input_chars[start_pos - 2] != '.');
+        let next = input_chars.get(*pos + 1);
+        let has_fraction = input_chars.get(*pos) == Some(&'.')
+            && !is_tuple_index
+            && !next.is_some_and(|ch| *ch == '.' || ch.is_alphabetic() || *ch == '_');
+        if has_fraction {
+            *pos += 1; // Move past the `.`
+            scan_digits(pos, 10);
+        }
+        // exponent, `1e10`, `2.5E-3`
+        if matches!(input_chars.get(*pos), Some('e') | Some('E')) {
+            let sign_length = usize::from(matches!(input_chars.get(*pos + 1), Some('+') | Some('-')));
+            if input_chars.get(*pos + 1 + sign_length).is_some_and(|ch| ch.is_ascii_digit()) {
+                *pos += 1 + sign_length;
+                scan_digits(pos, 10);
             }
-        } else if current_char == '!' && *next_char == '=' {
-            value.push(*next_char);
-            *pos += 1;
         }
     }
+    // type suffix, `u8`, `i64`, `f32`, `usize`
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
 
-    tokens.push(create_token(settings_config, "rust", TokenType::Operator, &value));
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "rust", TokenType::Number, &value));
 }
 
-fn handle_identifier(
+fn handle_attribute(
     tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
+    theme: &Theme, 
     input_chars: &[char], 
     pos: &mut usize
 ) {
     let start_pos = *pos;
-    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+    *pos += 1; // Move past the opening `#`
+    // inner attribute, `#![allow(dead_code)]`
+    if input_chars[*pos..].starts_with(&['!', '[']) {
         *pos += 1;
     }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    let next_char = input_chars.get(*pos).unwrap_or(&' ');
 
-    // Identify function calls and class names in JavaScript
-    if *next_char == '(' {
-        tokens.push(create_token(settings_config, "rust", TokenType::FunctionName, &value));
-    } else {
-        let token_type;
-        if RUST_KEYWORDS.contains(&value.as_str()) {
-            token_type = TokenType::Keyword;
-        } else if value.chars().next().unwrap().is_uppercase() {
-            token_type = TokenType::ClassName;
-        } else {
-            token_type = TokenType::Identifier;
+    if *pos < input_chars.len() && input_chars[*pos] == '[' {
+        *pos += 1;
+        while *pos < input_chars.len() && input_chars[*pos] != ']' {
+            *pos += 1;
+        }
+        // Move past the closing `]`, a multi-line attribute has none on its first line
+        if *pos < input_chars.len() {
+            *pos += 1;
         }
-        tokens.push(create_token(settings_config, "rust", token_type, &value));
     }
-}
-
-fn handle_punctuation(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
-) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "rust", TokenType::Punctuation, &value));
-    *pos += 1;
-}
 
-fn handle_unknown(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
-) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "rust", TokenType::Unknown, &value));
-    *pos += 1;
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "rust", TokenType::FunctionName, &value));
 }
 
-pub fn tokenize_rust_code(settings_config: &Settings, input: &str) -> Vec<Token> {
+pub fn tokenize_rust_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
     let mut tokens = Vec::new();
     let mut pos = 0;
+    let mut state = state.clone();
     let input_chars: Vec<char> = input.chars().collect();
 
+    handle_line_state(
+        &mut tokens, theme, "rust", Some(&BLOCK_COMMENT), 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
     while pos < input_chars.len() {
         let current_char = input_chars[pos];
 
         if current_char.is_whitespace() {
-            handle_whitespace(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_whitespace(&mut tokens, theme, "rust", input_chars.as_slice(), &mut pos);
         } 
         else if current_char == '#' {
-            handle_attribute(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_attribute(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        } 
+        else if current_char == '/' && input_chars.get(pos + 1) == Some(&'/') {
+            handle_line_comment(&mut tokens, theme, "rust", input_chars.as_slice(), &mut pos);
         } 
-        else if current_char == '/' && (input_chars.get(pos + 1) == Some(&'/') || input_chars.get(pos + 1) == Some(&'*')) {
-            handle_comment(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+        else if current_char == '/' && input_chars.get(pos + 1) == Some(&'*') {
+            handle_block_comment(&mut tokens, theme, "rust", &BLOCK_COMMENT, input_chars.as_slice(), &mut pos, &mut state);
+        } 
+        else if current_char == '\'' && is_lifetime(input_chars.as_slice(), pos) {
+            handle_lifetime(&mut tokens, theme, input_chars.as_slice(), &mut pos);
         } 
         else if current_char == '"' || current_char == '\'' {
-            handle_string_literal(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, 0, &mut state);
+        } 
+        else if let Some(prefix_length) = string_prefix_length(input_chars.as_slice(), pos) {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, prefix_length, &mut state);
         } 
-        else if current_char == '\'' && input_chars.get(pos + 1).map(|c| c.is_alphanumeric()).unwrap_or(false) {
-            handle_lifetime(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+        else if current_char == 'r' && input_chars.get(pos + 1) == Some(&'#') {
+            handle_raw_identifier(&mut tokens, theme, input_chars.as_slice(), &mut pos);
         } 
-        else if current_char.is_digit(10) {
-            handle_number(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+        else if current_char.is_ascii_digit() {
+            handle_rust_number(&mut tokens, theme, input_chars.as_slice(), &mut pos);
         } 
-        else if "+-*/=%><!".contains(current_char) {
-            handle_operator(&mut tokens, settings_config, current_char, input_chars.as_slice(), &mut pos);
+        else if "+-*/=%><!&|^?".contains(current_char) {
+            handle_operator(&mut tokens, theme, "rust", current_char, input_chars.as_slice(), &mut pos);
         } 
         else if current_char.is_alphabetic() || current_char == '_' {
             if input_chars.get(pos + 1) == Some(&'!') {
-                handle_macro(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+                handle_macro(&mut tokens, theme, input_chars.as_slice(), &mut pos);
             } else {
-                handle_identifier(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
+                handle_identifier(&mut tokens, theme, "rust", &RUST_KEYWORDS, input_chars.as_slice(), &mut pos);
             }
         } 
         else if ",.:;()[]{}<>".contains(current_char) {
-            handle_punctuation(&mut tokens, settings_config, current_char, &mut pos);
+            handle_punctuation(&mut tokens, theme, "rust", current_char, &mut pos);
         } 
         else {
-            handle_unknown(&mut tokens, settings_config, current_char, &mut pos);
+            handle_unknown(&mut tokens, theme, "rust", current_char, &mut pos);
         }
     }
 
-    tokens
+    (tokens, state)
+}
+
+pub struct RustLanguage;
+
+impl Language for RustLanguage {
+    fn name(&self) -> &str {
+        "rust"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["rs"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_rust_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_rust_nested_block_comment() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let first = tokenize("rust", &theme, "/* outer /* inner */", &LineState::Normal);
+        assert_eq!(first.state, LineState::InBlockComment { depth: 1 });
+
+        let second = tokenize("rust", &theme, "still outer */ fn", &first.state);
+        assert_eq!(second.tokens[0].value, "still outer */");
+        assert_eq!(second.tokens.last().unwrap().token_type, TokenType::Keyword);
+    }
+
+    #[test]
+    fn test_tokenize_rust_lifetimes_and_chars() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("rust", &theme, "fn f<'a>(x: &'a str, c: char) -> &'static str { 'outer: loop { if c == 'a' || c == '\\'' { break 'outer; } } }", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of("'a"), TokenType::Lifetime);
+        assert_eq!(token_of("'static"), TokenType::Lifetime);
+        assert_eq!(token_of("'outer"), TokenType::Lifetime);
+        assert_eq!(token_of("'a'"), TokenType::StringLiteral);
+        assert_eq!(token_of("\\'"), TokenType::EscapeSequence);
+        assert_eq!(token_of("str"), TokenType::Identifier);
+        assert_eq!(token_of("break"), TokenType::Keyword);
+    }
+
+    #[test]
+    fn test_tokenize_rust_prefixed_strings_and_raw_identifiers() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("rust", &theme, "let r#type = (b\"bytes\", b'x', br#\"a \"quoted\" \\ path\"#, c\"nul\", r\"C:\\dir\");", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of("r#type"), TokenType::Identifier);
+        assert_eq!(token_of("b\"bytes\""), TokenType::StringLiteral);
+        assert_eq!(token_of("b'x'"), TokenType::StringLiteral);
+        assert_eq!(token_of("br#\"a \"quoted\" \\ path\"#"), TokenType::StringLiteral);
+        assert_eq!(token_of("c\"nul\""), TokenType::StringLiteral);
+        assert_eq!(token_of("r\"C:\\dir\""), TokenType::StringLiteral);
+        assert_eq!(line_content.state, LineState::Normal);
+
+        // raw strings continue on the next line up to a quote followed by their `#`s
+        let first = tokenize("rust", &theme, "let s = r##\"first \"# line", &LineState::Normal);
+        assert_eq!(first.state, LineState::InString { delimiter: "\"##".to_string(), raw: true });
+        let second = tokenize("rust", &theme, "second \\\"## + 1", &first.state);
+        assert_eq!(second.tokens[0].value, "second \\\"##");
+        assert_eq!(second.state, LineState::Normal);
+    }
+
+    #[test]
+    fn test_tokenize_rust_numbers() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let line_content = tokenize("rust", &theme, "[0xFF, 0o777, 0b1010_1010, 1_000, 3.14f32, 10u8, 1e-3, 2.5E10, 7.]", &LineState::Normal);
+        let numbers: Vec<&str> = line_content.tokens.iter()
+            .filter(|token| token.token_type == TokenType::Number)
+            .map(|token| token.value.as_str())
+            .collect();
+        assert_eq!(numbers, ["0xFF", "0o777", "0b1010_1010", "1_000", "3.14f32", "10u8", "1e-3", "2.5E10", "7."]);
+
+        // ranges, method calls on literals and tuple indices keep their `.`
+        let line_content = tokenize("rust", &theme, "0..10; 1.max(2); pair.0.1", &LineState::Normal);
+        let values: Vec<&str> = line_content.tokens.iter().map(|token| token.value.as_str()).collect();
+        assert_eq!(&values[..4], ["0", ".", ".", "10"]);
+        assert_eq!(&values[6..10], ["1", ".", "max", "("]);
+        assert_eq!(&values[values.len() - 3..], ["0", ".", "1"]);
+    }
+
+    #[test]
+    fn test_tokenize_rust_multi_line_attribute() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = ["#[derive(", "    Debug,", ")]", "struct Point;", "#"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("rust", &theme, &lines);
+        assert_eq!(line_contents[0].tokens[0].value, "#[derive(");
+        assert_eq!(line_contents[0].tokens[0].token_type, TokenType::FunctionName);
+        assert_eq!(line_contents[3].tokens[0].token_type, TokenType::Keyword);
+        assert_eq!(line_contents[4].tokens[0].value, "#");
+    }
 }
@@ -0,0 +1,510 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+// reserved words, only recognized where a command starts
+const SHELL_KEYWORDS: [&str; 19] = [
+    "if", "then", "else", "elif", "fi", "case", "esac", "for", "select",
+    "while", "until", "do", "done", "in", "function", "time", "coproc", "!", "return"
+];
+
+// reserved words followed by another command
+const COMMAND_KEYWORDS: [&str; 9] = ["if", "then", "else", "elif", "while", "until", "do", "time", "!"];
+
+// longest first so `;;&` wins over `;;` and `;`, and `2>&1` is one redirection
+const SHELL_OPERATORS: [&str; 21] = [
+    ";;&", "<<<", "&>>", ">>", "<&", ">&", "<>", ">|", "&>", "&&", "||", ";;", ";&", "|&",
+    "<", ">", "|", "&", ";", "[[", "]]"
+];
+
+// characters ending an unquoted word
+fn is_metachar(ch: char) -> bool {
+    ch.is_whitespace() || "|&;<>()\"'`$".contains(ch)
+}
+
+fn is_name_start(ch: char) -> bool {
+    ch.is_ascii_alphabetic() || ch == '_'
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
+// `2>file` or `2>&1`, the digits are a file descriptor rather than a word
+fn is_redirection(input_chars: &[char], pos: usize) -> bool {
+    let digits = input_chars[pos..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+    digits > 0 && matches!(input_chars.get(pos + digits), Some('<') | Some('>'))
+}
+
+// end of the `$name`, `${...}`, `$1` or `$?` reference at `pos`, `pos` itself when there is none
+fn variable_end(input_chars: &[char], pos: usize) -> usize {
+    match input_chars.get(pos + 1) {
+        Some('{') => {
+            let mut end = pos + 2;
+            while end < input_chars.len() && input_chars[end] != '}' {
+                end += 1;
+            }
+            (end + 1).min(input_chars.len())
+        },
+        Some(ch) if is_name_start(*ch) => {
+            pos + 1 + input_chars[pos + 1..].iter().take_while(|ch| is_name_char(**ch)).count()
+        },
+        Some(ch) if ch.is_ascii_digit() || "@#?$!*-".contains(*ch) => pos + 2,
+        _ => pos,
+    }
+}
+
+// position of the `)` closing the `(` right before `pos`
+fn closing_paren(input_chars: &[char], pos: usize) -> Option<usize> {
+    let mut depth = 1;
+    for (index, ch) in input_chars.iter().enumerate().skip(pos) {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 {
+            return Some(index);
+        }
+    }
+    None
+}
+
+fn handle_variable(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos = variable_end(input_chars, *pos);
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "shell", TokenType::ClassName, &value));
+}
+
+// Double quoted strings expand `$name` and `$(...)`, those are tokenized within the string.
+// The string may go on on the next line.
+fn handle_double_quoted(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let mut start_pos = *pos;
+    *pos += 1; // Move past the opening quote
+
+    let push_literal = |tokens: &mut Vec<Token>, start_pos: usize, end: usize| {
+        if end > start_pos {
+            let value: String = input_chars[start_pos..end].iter().collect();
+            tokens.push(create_token(theme, "shell", TokenType::StringLiteral, &value));
+        }
+    };
+
+    while *pos < input_chars.len() {
+        let current_char = input_chars[*pos];
+
+        if current_char == '\\' {
+            *pos = (*pos + 2).min(input_chars.len());
+        }
+        else if current_char == '"' {
+            *pos += 1;
+            push_literal(tokens, start_pos, *pos);
+            return;
+        }
+        else if current_char == '$' && input_chars.get(*pos + 1) == Some(&'(') {
+            push_literal(tokens, start_pos, *pos);
+            let Some(end) = closing_paren(input_chars, *pos + 2) else {
+                start_pos = *pos;
+                *pos = input_chars.len();
+                break;
+            };
+            tokens.push(create_token(theme, "shell", TokenType::Punctuation, "$("));
+            let command: String = input_chars[*pos + 2..end].iter().collect();
+            let (command_tokens, _) = tokenize_shell_code(theme, &command, &LineState::Normal);
+            tokens.extend(command_tokens);
+            tokens.push(create_token(theme, "shell", TokenType::Punctuation, ")"));
+            *pos = end + 1;
+            start_pos = *pos;
+        }
+        else if current_char == '$' && variable_end(input_chars, *pos) > *pos {
+            push_literal(tokens, start_pos, *pos);
+            handle_variable(tokens, theme, input_chars, pos);
+            start_pos = *pos;
+        }
+        else {
+            *pos += 1;
+        }
+    }
+
+    push_literal(tokens, start_pos, *pos);
+    *state = LineState::InString { delimiter: "\"".to_string(), raw: false };
+}
+
+// single quoted strings have no escapes, `$'...'` strings do
+fn handle_quoted(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_pos = *pos;
+    let raw = input_chars[*pos] != '$';
+    *pos += if raw { 1 } else { 2 }; // Move past the opening quote
+
+    if !scan_string_body(input_chars, pos, "'", raw) {
+        *state = LineState::InString { delimiter: "'".to_string(), raw };
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "shell", TokenType::StringLiteral, &value));
+}
+
+// `<<EOF`, `<<-EOF`, `<<'EOF'` or `<<"EOF"`, the here-document starts on the next line.
+// Returns the delimiter and whether the terminator may be indented with tabs.
+fn handle_heredoc(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> (String, bool) {
+    let start_pos = *pos;
+    *pos += 2; // Move past the `<<`
+    let strip_tabs = input_chars.get(*pos) == Some(&'-');
+    if strip_tabs {
+        *pos += 1;
+    }
+    while *pos < input_chars.len() && input_chars[*pos] == ' ' {
+        *pos += 1;
+    }
+
+    let word_start = *pos;
+    while *pos < input_chars.len() && !input_chars[*pos].is_whitespace() && !"|&;<>()".contains(input_chars[*pos]) {
+        *pos += 1;
+    }
+    // quoting the word only turns off expansions in the body
+    let delimiter: String = input_chars[word_start..*pos].iter()
+        .filter(|ch| !"'\"\\".contains(**ch))
+        .collect();
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "shell", TokenType::Operator, &value));
+    (delimiter, strip_tabs)
+}
+
+fn handle_shell_operator(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    // file descriptor of a redirection, `2>` or `2>&1`
+    while *pos < input_chars.len() && input_chars[*pos].is_ascii_digit() {
+        *pos += 1;
+    }
+    let operator = SHELL_OPERATORS.iter()
+        .find(|operator| input_chars[*pos..].starts_with(&operator.chars().collect::<Vec<char>>()))
+        .expect("called on an operator character");
+    *pos += operator.len();
+    // `>&2` and `<&-` duplicate or close a file descriptor
+    if *operator == ">&" || *operator == "<&" {
+        while *pos < input_chars.len() && (input_chars[*pos].is_ascii_digit() || input_chars[*pos] == '-') {
+            *pos += 1;
+        }
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if value == "[[" || value == "]]" { TokenType::Punctuation } else { TokenType::Operator };
+    tokens.push(create_token(theme, "shell", token_type, &value));
+}
+
+// length of the `NAME=` or `NAME+=` starting an assignment at `pos`, 0 when there is none
+fn assignment_length(input_chars: &[char], pos: usize) -> usize {
+    let name_length = input_chars[pos..].iter().take_while(|ch| is_name_char(**ch)).count();
+    if name_length == 0 || !is_name_start(input_chars[pos]) {
+        return 0;
+    }
+    match input_chars.get(pos + name_length..pos + name_length + 2) {
+        Some(['+', '=']) => name_length + 2,
+        _ if input_chars.get(pos + name_length) == Some(&'=') => name_length + 1,
+        _ => 0,
+    }
+}
+
+fn handle_assignment(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let end = *pos + assignment_length(input_chars, *pos);
+    let operator_length = if input_chars[end - 2] == '+' { 2 } else { 1 };
+
+    let name: String = input_chars[*pos..end - operator_length].iter().collect();
+    let operator: String = input_chars[end - operator_length..end].iter().collect();
+    tokens.push(create_token(theme, "shell", TokenType::ClassName, &name));
+    tokens.push(create_token(theme, "shell", TokenType::Operator, &operator));
+    *pos = end;
+}
+
+// An unquoted word, where a command starts it is a reserved word or the command's name,
+// anything else is an argument.
+// Returns whether the next word starts a command.
+fn handle_word(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    command_start: bool
+) -> bool {
+    let start_pos = *pos;
+    // the first character belongs to the word even when it is a metacharacter,
+    // so the word is never empty
+    while *pos < input_chars.len() && (*pos == start_pos || !is_metachar(input_chars[*pos])) {
+        // an escaped character never ends the word
+        *pos = if input_chars[*pos] == '\\' { (*pos + 2).min(input_chars.len()) } else { *pos + 1 };
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+
+    // `in` of `for name in` and `case word in`
+    let previous_words: Vec<&str> = tokens.iter()
+        .rev()
+        .filter(|token| token.token_type != TokenType::Whitespace)
+        .take(2)
+        .map(|token| token.value.as_str())
+        .collect();
+    let is_loop_in = value == "in" && previous_words.len() == 2 && ["for", "case", "select"].contains(&previous_words[1]);
+
+    let (token_type, next_command_start) = if command_start && SHELL_KEYWORDS.contains(&value.as_str()) {
+        (TokenType::Keyword, COMMAND_KEYWORDS.contains(&value.as_str()) || value == "function")
+    } else if is_loop_in {
+        (TokenType::Keyword, false)
+    } else if command_start && value != "\\" {
+        (TokenType::FunctionName, false)
+    } else if value.chars().all(|ch| ch.is_ascii_digit()) {
+        (TokenType::Number, false)
+    } else if value == "\\" {
+        // line continuation
+        (TokenType::Punctuation, command_start)
+    } else {
+        (TokenType::Identifier, false)
+    };
+    tokens.push(create_token(theme, "shell", token_type, &value));
+    next_command_start
+}
+
+pub fn tokenize_shell_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+    // whether the next word is a command name or reserved word
+    let mut command_start = true;
+    // inside an assignment's value, `NAME=value command` runs a command after it
+    // when the assignment itself was where a command starts
+    let mut assignment: Option<bool> = None;
+    // the here-document opened on this line, it starts on the next one
+    let mut heredoc: Option<(String, bool)> = None;
+
+    handle_line_state(
+        &mut tokens, theme, "shell", None, 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "shell", input_chars.as_slice(), &mut pos);
+            if let Some(assignment_command_start) = assignment.take() {
+                command_start = assignment_command_start;
+            }
+        }
+        else if current_char == '#' && (pos == 0 || input_chars[pos - 1].is_whitespace()) {
+            handle_line_comment(&mut tokens, theme, "shell", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '"' {
+            handle_double_quoted(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+            command_start = false;
+        }
+        else if current_char == '\'' || (current_char == '$' && next_char == '\'') {
+            handle_quoted(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+            command_start = false;
+        }
+        else if current_char == '$' && next_char == '(' {
+            // `$((` starts an arithmetic expansion rather than a command
+            let value = if input_chars.get(pos + 2) == Some(&'(') { "$((" } else { "$(" };
+            tokens.push(create_token(theme, "shell", TokenType::Punctuation, value));
+            pos += value.len();
+            command_start = value == "$(";
+        }
+        else if current_char == '$' && variable_end(input_chars.as_slice(), pos) > pos {
+            handle_variable(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+            command_start = false;
+        }
+        else if current_char == '$' {
+            // `echo $` or `x=$`, a `$` no name or expansion follows
+            handle_unknown(&mut tokens, theme, "shell", current_char, &mut pos);
+            command_start = false;
+        }
+        else if current_char == '`' {
+            // a command starts after the opening backtick, counting them tells which one this is
+            let opening = tokens.iter().filter(|token| token.value == "`").count() % 2 == 0;
+            handle_punctuation(&mut tokens, theme, "shell", current_char, &mut pos);
+            command_start = opening;
+        }
+        else if current_char == '<' && next_char == '<' && input_chars.get(pos + 2) != Some(&'<') {
+            heredoc = Some(handle_heredoc(&mut tokens, theme, input_chars.as_slice(), &mut pos));
+        }
+        else if "(){}".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, "shell", current_char, &mut pos);
+            command_start = current_char == '(' || current_char == '{';
+        }
+        else if "|&;<>".contains(current_char)
+            || is_redirection(input_chars.as_slice(), pos)
+            || input_chars[pos..].starts_with(&['[', '['])
+            || input_chars[pos..].starts_with(&[']', ']'])
+        {
+            handle_shell_operator(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+            let operator = tokens.last().unwrap().value.as_str();
+            command_start = ["|", "||", "&&", "&", ";", ";;", ";&", ";;&", "|&"].contains(&operator);
+        }
+        else if assignment.is_none() && assignment_length(input_chars.as_slice(), pos) > 0 {
+            handle_assignment(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+            assignment = Some(command_start);
+            command_start = false;
+        }
+        else {
+            command_start = handle_word(&mut tokens, theme, input_chars.as_slice(), &mut pos, command_start);
+        }
+    }
+
+    // the body starts on the next line, unless a string is still open
+    if let (Some((delimiter, strip_tabs)), LineState::Normal) = (heredoc, &state) {
+        state = LineState::InHeredoc { delimiter, strip_tabs };
+    }
+
+    (tokens, state)
+}
+
+pub struct ShellLanguage;
+
+impl Language for ShellLanguage {
+    fn name(&self) -> &str {
+        "shell"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["sh", "bash", "zsh", "ksh"]
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        vec![
+            ".bashrc", ".bash_profile", ".bash_login", ".bash_logout", ".bash_aliases",
+            ".profile", ".zshrc", ".zshenv", ".zprofile", ".zlogin", ".kshrc", "PKGBUILD"
+        ]
+    }
+
+    fn interpreters(&self) -> Vec<&str> {
+        vec!["sh", "bash", "zsh", "ksh", "dash", "ash"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_shell_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_shell() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "if [ -n \"$HOME\" ]; then NAME=world echo \"hi $(whoami)\" 2>&1 | grep -v x # done",
+            "for file in *.txt; do",
+            "cat <<-EOF > out.log",
+            "\tbody $NAME",
+            "\tEOF",
+            "echo 'still",
+            "quoted' ${PATH}",
+        ]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("shell", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of(0, "if"), TokenType::Keyword);
+        assert_eq!(token_of(0, "$HOME"), TokenType::ClassName);
+        assert_eq!(token_of(0, "then"), TokenType::Keyword);
+        assert_eq!(token_of(0, "NAME"), TokenType::ClassName);
+        // the assignment doesn't stop `echo` from being the command
+        assert_eq!(token_of(0, "echo"), TokenType::FunctionName);
+        assert_eq!(token_of(0, "\"hi "), TokenType::StringLiteral);
+        assert_eq!(token_of(0, "whoami"), TokenType::FunctionName);
+        assert_eq!(token_of(0, "2>&1"), TokenType::Operator);
+        assert_eq!(token_of(0, "grep"), TokenType::FunctionName);
+        assert_eq!(token_of(0, "-v"), TokenType::Identifier);
+        assert_eq!(token_of(0, "# done"), TokenType::Comment);
+        assert_eq!(token_of(1, "in"), TokenType::Keyword);
+        assert_eq!(token_of(1, "do"), TokenType::Keyword);
+        // here-document
+        assert_eq!(token_of(2, "<<-EOF"), TokenType::Operator);
+        assert_eq!(line_contents[2].state, LineState::InHeredoc { delimiter: "EOF".to_string(), strip_tabs: true });
+        assert_eq!(line_contents[3].tokens[0].token_type, TokenType::StringLiteral);
+        assert_eq!(line_contents[4].state, LineState::Normal);
+        // quotes carry over lines
+        assert_eq!(line_contents[5].state, LineState::InString { delimiter: "'".to_string(), raw: true });
+        assert_eq!(line_contents[6].tokens[0].token_type, TokenType::StringLiteral);
+        assert_eq!(token_of(6, "${PATH}"), TokenType::ClassName);
+    }
+
+    #[test]
+    fn test_tokenize_shell_lone_dollar() {
+        let theme = Theme::new(&Settings::init_default_values());
+        // a `$` without a name or expansion after it used to loop forever
+        let sources = [
+            ("shell", "x=$"),
+            ("shell", "echo $ "),
+            ("shell", "echo $"),
+            ("dockerfile", "RUN echo $"),
+            ("markdown", "```sh\nx=$\n```"),
+        ];
+        for (language, source) in sources {
+            let lines: Vec<String> = source.lines().map(|line| line.to_string()).collect();
+            let line_contents = tokenize_lines(language, &theme, &lines);
+            for (line, line_content) in lines.iter().zip(&line_contents) {
+                let joined: String = line_content.tokens.iter().map(|token| token.value.as_str()).collect();
+                assert_eq!(&joined, line);
+                assert!(line_content.tokens.iter().all(|token| !token.value.is_empty()));
+            }
+        }
+        let line_content = tokenize("shell", &theme, "echo $ ", &LineState::Normal);
+        assert_eq!(line_content.tokens[2].value, "$");
+        assert_eq!(line_content.tokens[2].token_type, TokenType::Unknown);
+    }
+}
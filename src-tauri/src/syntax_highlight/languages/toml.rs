@@ -0,0 +1,251 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+fn is_bare_key_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'
+}
+
+// a key is followed by `=`, or by `.` when it is part of a dotted key
+fn is_key(input_chars: &[char], pos: usize) -> bool {
+    matches!(peek_non_whitespace(input_chars, pos), Some('=') | Some('.'))
+}
+
+// bare keys may be all digits, e.g. `1234 = "value"`, a number can't be followed by `=`
+fn is_number_key(input_chars: &[char], pos: usize) -> bool {
+    let end = pos + input_chars[pos..].iter().take_while(|ch| is_bare_key_char(**ch)).count();
+    peek_non_whitespace(input_chars, end) == Some('=')
+}
+
+// `[table]` or `[[array.of.tables]]` as the only thing on the line besides a comment.
+// Returns false when the brackets are an array value instead.
+fn handle_table_header(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) -> bool {
+    let start_pos = *pos;
+    let depth = if input_chars.get(start_pos + 1) == Some(&'[') { 2 } else { 1 };
+    let mut end = start_pos + depth;
+
+    while end < input_chars.len() && input_chars[end] != ']' {
+        if input_chars[end] == '"' || input_chars[end] == '\'' {
+            let delimiter = input_chars[end].to_string();
+            end += 1;
+            scan_string_body(input_chars, &mut end, &delimiter, delimiter == "'");
+        } else if input_chars[end] == ',' || input_chars[end] == '=' {
+            return false;
+        } else {
+            end += 1;
+        }
+    }
+    let closing = vec![']'; depth];
+    if !input_chars[end.min(input_chars.len())..].starts_with(&closing) {
+        return false;
+    }
+    end += depth;
+    if !matches!(peek_non_whitespace(input_chars, end), None | Some('#')) {
+        return false;
+    }
+
+    *pos = end;
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "toml", TokenType::ClassName, &value));
+    true
+}
+
+// basic ("...") and literal ('...') strings, the triple quoted forms may span lines.
+// A single line string followed by `=` is a quoted key.
+fn handle_string_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_char = input_chars[*pos];
+    let start_pos = *pos;
+    let raw = start_char == '\'';
+    let multiline = input_chars[*pos..].starts_with(&[start_char; 3]);
+
+    let delimiter = if multiline { start_char.to_string().repeat(3) } else { start_char.to_string() };
+    *pos += delimiter.len(); // Move past the opening quotes
+
+    let closed = scan_string_body(input_chars, pos, &delimiter, raw);
+    if !closed && multiline {
+        *state = LineState::InString { delimiter, raw };
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if !multiline && is_key(input_chars, *pos) {
+        TokenType::Identifier
+    } else {
+        TokenType::StringLiteral
+    };
+    tokens.push(create_token(theme, "toml", token_type, &value));
+}
+
+// integers (with `_`, 0x, 0o, 0b), floats, inf, nan and dates/times like 1979-05-27T07:32:00Z
+fn handle_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1;
+    while *pos < input_chars.len() && (input_chars[*pos].is_ascii_alphanumeric() || "_.:+-".contains(input_chars[*pos])) {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "toml", TokenType::Number, &value));
+}
+
+// bare keys, and the unquoted values true, false, inf and nan
+fn handle_bare_word(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && is_bare_key_char(input_chars[*pos]) {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+
+    let token_type = if is_key(input_chars, *pos) {
+        TokenType::Identifier
+    } else if value == "true" || value == "false" {
+        TokenType::Keyword
+    } else if value == "inf" || value == "nan" {
+        TokenType::Number
+    } else {
+        TokenType::Unknown
+    };
+    tokens.push(create_token(theme, "toml", token_type, &value));
+}
+
+pub fn tokenize_toml_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+
+    handle_line_state(
+        &mut tokens, theme, "toml", None, 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+        let line_start = tokens.iter().all(|token| token.token_type == TokenType::Whitespace);
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "toml", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '#' {
+            handle_line_comment(&mut tokens, theme, "toml", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '[' && line_start {
+            if !handle_table_header(&mut tokens, theme, input_chars.as_slice(), &mut pos) {
+                handle_punctuation(&mut tokens, theme, "toml", current_char, &mut pos);
+            }
+        }
+        else if current_char == '"' || current_char == '\'' {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if (current_char.is_ascii_digit() && !is_number_key(input_chars.as_slice(), pos))
+            || ((current_char == '+' || current_char == '-') && (next_char.is_ascii_digit() || next_char == 'i' || next_char == 'n'))
+        {
+            handle_number(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if is_bare_key_char(current_char) {
+            handle_bare_word(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '=' {
+            handle_operator(&mut tokens, theme, "toml", current_char, input_chars.as_slice(), &mut pos);
+        }
+        else if "[]{},.".contains(current_char) {
+            handle_punctuation(&mut tokens, theme, "toml", current_char, &mut pos);
+        }
+        else {
+            handle_unknown(&mut tokens, theme, "toml", current_char, &mut pos);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct TomlLanguage;
+
+impl Language for TomlLanguage {
+    fn name(&self) -> &str {
+        "toml"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["toml"]
+    }
+
+    fn filenames(&self) -> Vec<&str> {
+        vec!["Cargo.lock", "Pipfile"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_toml_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_toml() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let header = tokenize("toml", &theme, "[[bin]] # binaries", &LineState::Normal);
+        assert_eq!(header.tokens[0].token_type, TokenType::ClassName);
+        assert_eq!(header.tokens[0].value, "[[bin]]");
+
+        let line_content = tokenize(
+            "toml", 
+            &theme, 
+            "serde.version = \"1.0\"; x = [1_000, true, 1979-05-27T07:32:00Z]", 
+            &LineState::Normal
+        );
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of("serde"), TokenType::Identifier);
+        assert_eq!(token_of("version"), TokenType::Identifier);
+        assert_eq!(token_of("\"1.0\""), TokenType::StringLiteral);
+        assert_eq!(token_of("1_000"), TokenType::Number);
+        assert_eq!(token_of("true"), TokenType::Keyword);
+        assert_eq!(token_of("1979-05-27T07:32:00Z"), TokenType::Number);
+
+        let first = tokenize("toml", &theme, "text = '''", &LineState::Normal);
+        assert_eq!(first.state, LineState::InString { delimiter: "'''".to_string(), raw: true });
+    }
+}
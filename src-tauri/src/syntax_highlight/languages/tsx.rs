@@ -0,0 +1,35 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, LineState, BlockCommentSyntax};
+use crate::syntax_highlight::languages::Language;
+use crate::syntax_highlight::languages::javascript::tokenize_script_code;
+use crate::syntax_highlight::languages::typescript::TSX_DIALECT;
+
+// TypeScript with JSX elements, the dialect is defined next to TypeScript's own
+pub struct TsxLanguage;
+
+impl Language for TsxLanguage {
+    fn name(&self) -> &str {
+        "tsx"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["tsx"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BlockCommentSyntax { open: "/*", close: "*/", nested: false })
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_script_code(theme, &TSX_DIALECT, input, state)
+    }
+}
@@ -1,254 +1,121 @@
-use crate::settings::Settings;
-use crate::syntax_highlight::{Token, TokenType, create_token};
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, LineState, BlockCommentSyntax};
+use crate::syntax_highlight::languages::Language;
+use crate::syntax_highlight::languages::javascript::{ScriptDialect, tokenize_script_code};
 
-const TYPESCRIPT_KEYWORDS: [&str; 46] = [
+const TYPESCRIPT_KEYWORDS: [&str; 57] = [
     "break", "case", "catch", "class", "const", "continue", "debugger", 
     "default", "delete", "do", "else", "export", "extends", "finally", 
     "for", "function", "if", "import", "in", "instanceof", "let", "new", 
     "return", "super", "switch", "this", "throw", "try", "typeof", 
     "var", "void", "while", "with", "yield", "await", "async", 
+    "of", "static", "null", "true", "false",
     "enum", "interface", "implements", "private", "public", "protected",
-    "readonly", "as", "never", "unknown"
+    "readonly", "as", "type", "namespace", "declare", "abstract", "keyof",
+    "infer", "is", "satisfies"
 ];
 
-fn handle_whitespace(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
-        *pos += 1;
-    }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "typescript", TokenType::Whitespace, &value));
-}
-
-fn handle_comment(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_pos = *pos;
-    *pos += 1; // Move past the initial '/' character
-
-    if *pos < input_chars.len() && input_chars[*pos] == '/' {
-        // Single-line comment (//)
-        while *pos < input_chars.len() && input_chars[*pos] != '\n' {
-            *pos += 1;
-        }
-    } else if *pos < input_chars.len() && input_chars[*pos] == '*' {
-        // Multi-line comment (/* ... */)
-        *pos += 1;
-        while *pos + 1 < input_chars.len() {
-            if input_chars[*pos] == '*' && input_chars[*pos + 1] == '/' {
-                *pos += 2; // Move past the closing */
-                break;
-            }
-            *pos += 1;
-        }
-    }
+// types built into the language, colored as builtins when they aren't a member name
+pub const TYPESCRIPT_PRIMITIVE_TYPES: [&str; 10] = [
+    "string", "number", "boolean", "bigint", "symbol", "object", "any", "unknown", "never", "undefined"
+];
 
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "typescript", TokenType::Comment, &value));
+const BLOCK_COMMENT: BlockCommentSyntax = BlockCommentSyntax { open: "/*", close: "*/", nested: false };
+
+// JSX would make `<T>value` type assertions ambiguous, so only .tsx files have it
+pub const TYPESCRIPT_DIALECT: ScriptDialect = ScriptDialect {
+    language: "typescript",
+    keywords: &TYPESCRIPT_KEYWORDS,
+    typescript: true,
+    jsx: false,
+};
+
+pub const TSX_DIALECT: ScriptDialect = ScriptDialect {
+    language: "tsx",
+    keywords: &TYPESCRIPT_KEYWORDS,
+    typescript: true,
+    jsx: true,
+};
+
+pub fn tokenize_typescript_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    tokenize_script_code(theme, &TYPESCRIPT_DIALECT, input, state)
 }
 
-fn handle_string_literal(
-    tokens: &mut Vec<Token>, 
-    settings: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_char = input_chars[*pos];
-    let start_pos = *pos;
-    *pos += 1; // Move past the opening quote
-
-    // Handle template literals for JavaScript
-    if start_char == '`' {
-        while *pos < input_chars.len() {
-            if input_chars[*pos] == '`' {
-                *pos += 1; // Move past the closing backtick
-                break;
-            }
-            // Handle escaped backticks within template literals
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
-            }
-            *pos += 1;
-        }
-    } else {
-        // Handle single and double-quoted strings
-        while *pos < input_chars.len() && input_chars[*pos] != start_char {
-            // Handle escaped quotes within strings
-            if input_chars[*pos] == '\\' && *pos + 1 < input_chars.len() {
-                *pos += 2;
-                continue;
-            }
-            *pos += 1;
-        }
-        *pos += 1; // Move past the closing quote
-    }
-
-    // Collect the entire string value including quotes
-    let value: String = input_chars[start_pos..*pos.min(&mut input_chars.len())].iter().collect();
-    tokens.push(create_token(settings, "typescript", TokenType::StringLiteral, &value));
-}
+pub struct TypeScriptLanguage;
 
-fn handle_number(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_pos = *pos;
-    while *pos < input_chars.len() && input_chars[*pos].is_digit(10) {
-        *pos += 1;
+impl Language for TypeScriptLanguage {
+    fn name(&self) -> &str {
+        "typescript"
     }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    tokens.push(create_token(settings_config, "typescript", TokenType::Number, &value));
-}
 
-fn handle_operator(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let mut value = current_char.to_string();
-    *pos += 1;
-
-    // Handle multi-character operators like ==, ===, =>, etc.
-    if let Some(next_char) = input_chars.get(*pos) {
-        if current_char == '=' && (*next_char == '=' || *next_char == '>') {
-            value.push(*next_char);
-            *pos += 1;
-
-            // Handle === case
-            if *next_char == '=' && *pos < input_chars.len() && input_chars[*pos] == '=' {
-                value.push('=');
-                *pos += 1;
-            }
-        } else if current_char == '!' && *next_char == '=' {
-            value.push(*next_char);
-            *pos += 1;
-        }
+    fn extensions(&self) -> Vec<&str> {
+        vec!["ts", "mts", "cts"]
     }
 
-    tokens.push(create_token(settings_config, "typescript", TokenType::Operator, &value));
-}
-
-fn handle_punctuation(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
-) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "typescript", TokenType::Punctuation, &value));
-    *pos += 1;
-}
-
-fn handle_unknown(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    current_char: char, 
-    pos: &mut usize
-) {
-    let value = current_char.to_string();
-    tokens.push(create_token(settings_config, "typescript", TokenType::Unknown, &value));
-    *pos += 1;
-}
+    fn interpreters(&self) -> Vec<&str> {
+        vec!["deno", "ts-node", "tsx"]
+    }
 
-fn handle_type_annotation(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    // Expecting a colon followed by a type name
-    if input_chars.get(*pos) == Some(&':') {
-        *pos += 1; // Move past the colon
-        let start_pos = *pos;
-
-        // Collect the type name (alphanumeric or generic types)
-        while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '<' || input_chars[*pos] == '>' || input_chars[*pos] == '_') {
-            *pos += 1;
-        }
-
-        let value: String = input_chars[start_pos..*pos].iter().collect();
-        tokens.push(create_token(settings_config, "typescript", TokenType::Identifier, &value));
+    fn line_comment(&self) -> Option<&str> {
+        Some("//")
     }
-}
 
-fn handle_identifier(
-    tokens: &mut Vec<Token>, 
-    settings_config: &Settings, 
-    input_chars: &[char], 
-    pos: &mut usize
-) {
-    let start_pos = *pos;
-    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
-        *pos += 1;
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BLOCK_COMMENT)
     }
-    let value: String = input_chars[start_pos..*pos].iter().collect();
-    let next_char = input_chars.get(*pos).unwrap_or(&' ');
-
-    // Identify function calls and class names in TypeScript
-    if *next_char == '(' {
-        tokens.push(create_token(settings_config, "typescript", TokenType::FunctionName, &value));
-    } else {
-        let token_type;
-        if TYPESCRIPT_KEYWORDS.contains(&value.as_str()) {
-            token_type = TokenType::Keyword;
-        } else if value.chars().next().unwrap().is_uppercase() {
-            token_type = TokenType::ClassName;
-        } else {
-            token_type = TokenType::Identifier;
-        }
-        tokens.push(create_token(settings_config, "typescript", token_type, &value));
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_typescript_code(theme, input, state)
     }
 }
 
-pub fn tokenize_typescript_code(settings_config: &Settings, input: &str) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut pos = 0;
-    let input_chars: Vec<char> = input.chars().collect();
-
-    while pos < input_chars.len() {
-        let current_char = input_chars[pos];
-
-        if current_char.is_whitespace() {
-            handle_whitespace(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char == '/' && (input_chars.get(pos + 1) == Some(&'/') || input_chars.get(pos + 1) == Some(&'*')) {
-            handle_comment(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char == '"' || current_char == '\'' || current_char == '`' {
-            handle_string_literal(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char.is_digit(10) {
-            handle_number(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char == ':' {
-            handle_type_annotation(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if "+-*/=%><!".contains(current_char) {
-            handle_operator(&mut tokens, settings_config, current_char, input_chars.as_slice(), &mut pos);
-        } 
-        else if current_char.is_alphabetic() || current_char == '_' {
-            handle_identifier(&mut tokens, settings_config, input_chars.as_slice(), &mut pos);
-        } 
-        else if ",.:;()[]{}".contains(current_char) {
-            handle_punctuation(&mut tokens, settings_config, current_char, &mut pos);
-        } 
-        else {
-            handle_unknown(&mut tokens, settings_config, current_char, &mut pos);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+    use crate::syntax_highlight::TokenType;
+
+    #[test]
+    fn test_tokenize_typescript_generics_and_decorators() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "@Component({ selector: \"app\" })",
+            "class Store<T extends object> {",
+            "  items = new Map<string, Array<T>>();",
+            "  ok = a < b && c > d;",
+            "}",
+            "const config = { port: 80 } satisfies Config;",
+            "const modes = [\"a\", \"b\"] as const;",
+        ].iter().map(|line| line.to_string()).collect();
+        let line_contents = tokenize_lines("typescript", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+
+        assert_eq!(token_of(0, "@Component"), TokenType::FunctionName);
+        assert_eq!(token_of(1, "<"), TokenType::Punctuation);
+        assert_eq!(token_of(1, "extends"), TokenType::Keyword);
+        assert_eq!(token_of(1, "object"), TokenType::Builtin);
+        assert_eq!(token_of(2, "string"), TokenType::Builtin);
+        assert_eq!(token_of(2, "Array"), TokenType::ClassName);
+        assert_eq!(line_contents[2].tokens.iter().filter(|token| token.value == ">").count(), 2);
+        assert_eq!(token_of(3, "<"), TokenType::Operator);
+        assert_eq!(token_of(3, ">"), TokenType::Operator);
+        assert_eq!(token_of(5, "satisfies"), TokenType::Keyword);
+        assert_eq!(token_of(6, "as"), TokenType::Keyword);
+        assert_eq!(line_contents[6].tokens.iter().filter(|token| token.value == "const").count(), 2);
+
+        // .tsx files have both generics and JSX
+        let line_content = tokenize("tsx", &theme, "const list = <List<Item> items={items} />;", &LineState::Normal);
+        assert_eq!(line_content.tokens.iter().find(|token| token.value == "List").unwrap().token_type, TokenType::ClassName);
+        assert_eq!(line_content.state, LineState::Normal);
     }
-
-    tokens
 }
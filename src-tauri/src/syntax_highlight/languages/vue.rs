@@ -0,0 +1,89 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, LineState, BlockCommentSyntax};
+use crate::syntax_highlight::languages::Language;
+use crate::syntax_highlight::languages::html::{MarkupDialect, tokenize_markup_code};
+
+// a single-file component is HTML at the top level: <template> is markup,
+// <script> and <style> are handed to their languages by the HTML tokenizer
+pub const VUE_DIALECT: MarkupDialect = MarkupDialect { language: "vue", template_syntax: true };
+
+pub struct VueLanguage;
+
+impl Language for VueLanguage {
+    fn name(&self) -> &str {
+        "vue"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["vue"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        None
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        Some(BlockCommentSyntax { open: "<!--", close: "-->", nested: false })
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_markup_code(theme, &VUE_DIALECT, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize_lines;
+    use crate::settings::Settings;
+    use crate::syntax_highlight::TokenType;
+
+    #[test]
+    fn test_tokenize_vue_single_file_component() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let lines: Vec<String> = [
+            "<template>",
+            "  <MyButton :label=\"title\" @click=\"save\">{{ count + 1 }}</MyButton>",
+            "</template>",
+            "<script setup",
+            "        lang=\"ts\">",
+            "interface Props { title: string }",
+            "</script>",
+            "<style scoped>",
+            ".button { color: red; }",
+            "</style>",
+        ]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        let line_contents = tokenize_lines("vue", &theme, &lines);
+        let token_of = |line: usize, value: &str| line_contents[line].tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of(0, "template"), TokenType::Keyword);
+        assert_eq!(token_of(1, "MyButton"), TokenType::ClassName);
+        assert_eq!(token_of(1, ":label"), TokenType::FunctionName);
+        assert_eq!(token_of(1, "@click"), TokenType::FunctionName);
+        assert_eq!(token_of(1, "{{"), TokenType::Punctuation);
+        assert_eq!(token_of(1, "1"), TokenType::Number);
+        // lang="ts" is on the tag's second line
+        assert_eq!(line_contents[3].state, LineState::InTag { name: "script".to_string(), lang: String::new() });
+        assert_eq!(
+            line_contents[4].state, 
+            LineState::InEmbedded { 
+                language: "typescript".to_string(), 
+                end: "</script".to_string(), 
+                state: Box::new(LineState::Normal) 
+            }
+        );
+        assert_eq!(token_of(5, "interface"), TokenType::Keyword);
+        assert_eq!(line_contents[6].state, LineState::Normal);
+        assert_eq!(token_of(8, ".button"), TokenType::ClassName);
+        assert_eq!(token_of(8, "color"), TokenType::Identifier);
+        assert_eq!(line_contents[9].state, LineState::Normal);
+    }
+}
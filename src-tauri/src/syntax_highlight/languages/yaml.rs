@@ -0,0 +1,271 @@
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::scanner::*;
+use crate::syntax_highlight::languages::Language;
+
+// plain scalars that aren't strings in the YAML 1.2 core schema
+const YAML_LITERALS: [&str; 10] = ["true", "True", "TRUE", "false", "False", "FALSE", "null", "Null", "NULL", "~"];
+
+fn is_flow_indicator(ch: char) -> bool {
+    ",[]{}".contains(ch)
+}
+
+// `:` ends a key when followed by whitespace or the end of the line
+fn is_mapping_colon(input_chars: &[char], pos: usize) -> bool {
+    input_chars.get(pos) == Some(&':') && input_chars.get(pos + 1).map_or(true, |ch| ch.is_whitespace())
+}
+
+fn is_number(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+
+    if let Some(hex) = unsigned.strip_prefix("0x") {
+        return !hex.is_empty() && hex.chars().all(|ch| ch.is_ascii_hexdigit());
+    }
+    if let Some(octal) = unsigned.strip_prefix("0o") {
+        return !octal.is_empty() && octal.chars().all(|ch| ch.is_digit(8));
+    }
+    if [".inf", ".Inf", ".INF", ".nan", ".NaN", ".NAN"].contains(&unsigned) {
+        return true;
+    }
+    // f64 parsing alone would also accept `inf` and `nan`
+    unsigned.starts_with(|ch: char| ch.is_ascii_digit() || ch == '.') && unsigned.parse::<f64>().is_ok()
+}
+
+// `---` and `...` document markers, `%YAML` / `%TAG` directives
+fn handle_document_marker(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos = if input_chars[*pos] == '%' { input_chars.len() } else { *pos + 3 };
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "yaml", TokenType::Preprocessor, &value));
+}
+
+// `&anchor`, `*alias` and `!tag`
+fn handle_node_property(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    *pos += 1;
+    while *pos < input_chars.len() && !input_chars[*pos].is_whitespace() && !is_flow_indicator(input_chars[*pos]) {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if input_chars[start_pos] == '!' { TokenType::ClassName } else { TokenType::FunctionName };
+    tokens.push(create_token(theme, "yaml", token_type, &value));
+}
+
+// `|` or `>` with optional chomping/indentation indicators, the following lines
+// indented deeper than this line are the scalar's content
+fn handle_block_scalar(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_pos = *pos;
+    *pos += 1;
+    while *pos < input_chars.len() && (input_chars[*pos].is_ascii_digit() || input_chars[*pos] == '+' || input_chars[*pos] == '-') {
+        *pos += 1;
+    }
+    let indent = input_chars.iter().take_while(|ch| ch.is_whitespace()).count();
+    *state = LineState::InIndentedBlock { indent: indent as u32 };
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, "yaml", TokenType::Operator, &value));
+}
+
+// single and double quoted scalars may continue on the next line
+fn handle_string_literal(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_char = input_chars[*pos];
+    let start_pos = *pos;
+    *pos += 1; // Move past the opening quote
+
+    // single quoted scalars escape a quote by doubling it, scanning on after `''` handles that
+    let delimiter = start_char.to_string();
+    let raw = start_char == '\'';
+    if !scan_string_body(input_chars, pos, &delimiter, raw) {
+        *state = LineState::InString { delimiter, raw };
+    }
+
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let token_type = if is_mapping_colon(input_chars, *pos) {
+        TokenType::Identifier
+    } else {
+        TokenType::StringLiteral
+    };
+    tokens.push(create_token(theme, "yaml", token_type, &value));
+}
+
+// unquoted key or value, it runs until `: `, ` #` or in flow collections a flow indicator
+fn handle_plain_scalar(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    in_flow: bool
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() {
+        let ch = input_chars[*pos];
+        if is_mapping_colon(input_chars, *pos)
+            || (ch == '#' && input_chars[*pos - 1].is_whitespace())
+            || (in_flow && is_flow_indicator(ch))
+        {
+            break;
+        }
+        *pos += 1;
+    }
+    // trailing whitespace isn't part of the scalar
+    while *pos > start_pos + 1 && input_chars[*pos - 1].is_whitespace() {
+        *pos -= 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+
+    let token_type = if is_mapping_colon(input_chars, *pos) {
+        TokenType::Identifier
+    } else if YAML_LITERALS.contains(&value.as_str()) {
+        TokenType::Keyword
+    } else if is_number(&value) {
+        TokenType::Number
+    } else {
+        TokenType::StringLiteral
+    };
+    tokens.push(create_token(theme, "yaml", token_type, &value));
+}
+
+pub fn tokenize_yaml_code(
+    theme: &Theme, 
+    input: &str, 
+    state: &LineState
+) -> (Vec<Token>, LineState) {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut state = state.clone();
+    let input_chars: Vec<char> = input.chars().collect();
+    let mut flow_depth = 0;
+
+    handle_line_state(
+        &mut tokens, theme, "yaml", None, 
+        input_chars.as_slice(), &mut pos, &mut state
+    );
+
+    while pos < input_chars.len() {
+        let current_char = input_chars[pos];
+        let next_char = input_chars.get(pos + 1).copied().unwrap_or(' ');
+        let followed_by_space = next_char.is_whitespace() || pos + 1 == input_chars.len();
+
+        if current_char.is_whitespace() {
+            handle_whitespace(&mut tokens, theme, "yaml", input_chars.as_slice(), &mut pos);
+        }
+        else if current_char == '#' {
+            handle_line_comment(&mut tokens, theme, "yaml", input_chars.as_slice(), &mut pos);
+        }
+        else if pos == 0 && (current_char == '%' || input_chars.starts_with(&['-', '-', '-']) || input_chars.starts_with(&['.', '.', '.'])) {
+            handle_document_marker(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if (current_char == '-' || current_char == '?' || current_char == ':') && followed_by_space {
+            handle_punctuation(&mut tokens, theme, "yaml", current_char, &mut pos);
+        }
+        else if current_char == '&' || current_char == '*' || current_char == '!' {
+            handle_node_property(&mut tokens, theme, input_chars.as_slice(), &mut pos);
+        }
+        else if (current_char == '|' || current_char == '>') && flow_depth == 0 {
+            handle_block_scalar(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if current_char == '"' || current_char == '\'' {
+            handle_string_literal(&mut tokens, theme, input_chars.as_slice(), &mut pos, &mut state);
+        }
+        else if is_flow_indicator(current_char) {
+            match current_char {
+                '[' | '{' => flow_depth += 1,
+                ']' | '}' => flow_depth -= 1,
+                _ => (),
+            }
+            handle_punctuation(&mut tokens, theme, "yaml", current_char, &mut pos);
+        }
+        else {
+            handle_plain_scalar(&mut tokens, theme, input_chars.as_slice(), &mut pos, flow_depth > 0);
+        }
+    }
+
+    (tokens, state)
+}
+
+pub struct YamlLanguage;
+
+impl Language for YamlLanguage {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["yaml", "yml"]
+    }
+
+    fn line_comment(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn block_comment(&self) -> Option<BlockCommentSyntax<'_>> {
+        None
+    }
+
+    fn tokenize_line(
+        &self, 
+        theme: &Theme, 
+        input: &str, 
+        state: &LineState
+    ) -> (Vec<Token>, LineState) {
+        tokenize_yaml_code(theme, input, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::{tokenize, tokenize_lines};
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_tokenize_yaml() {
+        let theme = Theme::new(&Settings::init_default_values());
+
+        let line_content = tokenize("yaml", &theme, "- name: &anchor build app # step", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of("-"), TokenType::Punctuation);
+        assert_eq!(token_of("name"), TokenType::Identifier);
+        assert_eq!(token_of("&anchor"), TokenType::FunctionName);
+        assert_eq!(token_of("build app"), TokenType::StringLiteral);
+        assert_eq!(token_of("# step"), TokenType::Comment);
+
+        let line_content = tokenize("yaml", &theme, "retries: [3, null, .inf]", &LineState::Normal);
+        let token_of = |value: &str| line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type;
+        assert_eq!(token_of("3"), TokenType::Number);
+        assert_eq!(token_of("null"), TokenType::Keyword);
+        assert_eq!(token_of(".inf"), TokenType::Number);
+
+        // block scalar content runs until a line that isn't indented deeper than its key
+        let lines: Vec<String> = ["  script: |", "    echo: done", "", "  next: 1"].iter().map(|line| line.to_string()).collect();
+        let line_contents = tokenize_lines("yaml", &theme, &lines);
+        assert_eq!(line_contents[0].state, LineState::InIndentedBlock { indent: 2 });
+        assert_eq!(line_contents[1].tokens.len(), 1);
+        assert_eq!(line_contents[1].tokens[0].token_type, TokenType::StringLiteral);
+        assert_eq!(line_contents[2].state, LineState::InIndentedBlock { indent: 2 });
+        assert_eq!(line_contents[3].tokens[1].token_type, TokenType::Identifier);
+        assert_eq!(line_contents[3].state, LineState::Normal);
+    }
+}
@@ -1,9 +1,19 @@
+pub mod brackets;
+pub mod folding;
 pub mod languages;
+pub mod outline;
+pub mod scanner;
+pub mod sub_tokens;
+pub mod syntax_tree;
+pub mod theme;
+pub mod theme_file;
 
+use std::collections::HashMap;
+use std::sync::Arc;
 use serde::{Serialize, Deserialize};
-use crate::settings::{LanguageSyntaxHighlight, Settings};
+use crate::syntax_highlight::theme::Theme;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum TokenType {
     Operator,
     Keyword,
@@ -15,7 +25,21 @@ pub enum TokenType {
     Unknown,
     Comment,
     ClassName,
-    FunctionName
+    FunctionName,
+    Preprocessor,
+    // Rust lifetimes and loop labels, `'a`
+    Lifetime,
+    // functions and types that are always in scope, Python's `len` or `print`
+    Builtin,
+    // split out of comments and strings by sub_tokens, after the language's tokenizer ran:
+    // `TODO`, `FIXME`, `HACK` and `XXX` in comments
+    Annotation,
+    // http(s), ftp, file and ws(s) URLs in comments and strings
+    Url,
+    // `\n`, `\x41`, `\u{1F600}` in strings
+    EscapeSequence,
+    // format placeholders in strings, `{}`, `{name:>8}`, `%s`, `%5.2f`
+    Placeholder,
 }
 
 // Define the PythonToken struct
@@ -24,51 +48,153 @@ pub enum TokenType {
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
-    pub color: String,
+    pub color: Arc<str>,
+    // CSS font style and background of the token type, see TokenStyle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<Arc<str>>,
+}
+
+// lexer state at the end of a line, carried into the start of the next line
+// so constructs spanning multiple lines (block comments, triple-quoted strings,
+// template literals) keep their highlighting after the first line
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LineState {
+    #[default]
+    Normal,
+    InBlockComment { depth: u32 },
+    InString { delimiter: String, raw: bool },
+    // lines indented deeper than `indent` belong to the block (YAML block scalars)
+    InIndentedBlock { indent: u32 },
+    // inside a region written in another language, e.g. a fenced code block in Markdown.
+    // `state` is the embedded language's own state, `end` the marker closing the region,
+    // empty when the host language ends it by its own rule (Dockerfile line continuations)
+    InEmbedded { language: String, end: String, state: Box<LineState> },
+    // inside an HTML start tag whose attributes continue on the next line.
+    // `lang` is its lang attribute so far, <script lang="ts"> picks the embedded language once the tag closes
+    InTag { name: String, lang: String },
+    // lines up to one that is exactly `delimiter` are a here-document (shell `<<EOF`).
+    // `strip_tabs` for `<<-EOF`, whose terminator may be indented with tabs
+    InHeredoc { delimiter: String, strip_tabs: bool },
+    // inside a string with `{...}` code regions (Python f-strings) that continues on the next line.
+    // `fields` counts the regions still open, a field nested in another's format spec is one more
+    InInterpolatedString { delimiter: String, fields: u32 },
+    // JavaScript constructs that nest code and non-code, template literals and JSX.
+    // The innermost context is last, code of an `${...}` or `{...}` region is an Expression
+    InScript { contexts: Vec<ScriptContext> },
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptContext {
+    // the text of a template literal
+    Template,
+    // the attributes of a JSX start tag
+    JsxTag,
+    // the children of a JSX element, text and nested elements
+    JsxChildren,
+    // code inside `${...}` or `{...}`, `braces` counts the blocks opened inside it
+    Expression { braces: u32 },
+}
+
+// opening and closing markers of a language's block comment
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BlockCommentSyntax<'a> {
+    pub open: &'a str,
+    pub close: &'a str,
+    pub nested: bool,
 }
 
 // a file content consists of multiple lines
 // each line consist of each word that represent by Token 
+// state is the lexer state at the end of the line
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct LineContent {
-    pub tokens: Vec<Token>
+    pub tokens: Vec<Token>,
+    pub state: LineState,
+}
+
+// color of each token type for one language, the frontend paints structured tokens
+// with it so switching themes doesn't need the file tokenized again
+pub type TokenPalette = HashMap<TokenType, String>;
+
+// A token of a line as sent to the frontend, (type, start, length).
+// start and length count UTF-16 code units so they index JavaScript strings directly
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub struct TokenSpan(pub TokenType, pub usize, pub usize);
+
+impl TokenType {
+    pub const ALL: [TokenType; 18] = [
+        TokenType::Operator,
+        TokenType::Keyword,
+        TokenType::Identifier,
+        TokenType::Number,
+        TokenType::Punctuation,
+        TokenType::StringLiteral,
+        TokenType::Whitespace,
+        TokenType::Unknown,
+        TokenType::Comment,
+        TokenType::ClassName,
+        TokenType::FunctionName,
+        TokenType::Preprocessor,
+        TokenType::Lifetime,
+        TokenType::Builtin,
+        TokenType::Annotation,
+        TokenType::Url,
+        TokenType::EscapeSequence,
+        TokenType::Placeholder,
+    ];
+}
+
+// Spans of a line's tokens. Whitespace is left out, the frontend renders
+// the gaps between spans as plain text.
+pub fn token_spans(tokens: &[Token]) -> Vec<TokenSpan> {
+    let mut start = 0;
+
+    tokens.iter()
+        .filter_map(|token| {
+            let length = token.value.encode_utf16().count();
+            let span = TokenSpan(token.token_type, start, length);
+            start += length;
+            (token.token_type != TokenType::Whitespace && length > 0).then_some(span)
+        })
+        .collect()
 }
 
 pub fn create_token(
-    settings_config: &Settings, 
+    theme: &Theme, 
     language: &str, 
     token_type: TokenType, 
     value: &str
 ) -> Token {
 
-    let languages:  Vec<LanguageSyntaxHighlight> = settings_config.syntax_highlight.languages.clone();
-    
-    let language_syntax = if let Some(lang_syntax) = languages.iter().find(|&lang| lang.language == language) {
-        lang_syntax
-    } else if let Some(lang_syntax) = languages.iter().find(|&lang| lang.language == "python") {
-        lang_syntax  // use python as default
-    } else {
-        panic!("Neither the provided language nor 'python' were found");
-    };
-
-    let color = match token_type {
-        TokenType::Keyword => &language_syntax.keyword_color,
-        TokenType::Identifier => &language_syntax.identifier_color,
-        TokenType::Number => &language_syntax.number_color,
-        TokenType::Operator => &language_syntax.operator_color,
-        TokenType::Punctuation => &language_syntax.punctuation_color,
-        TokenType::StringLiteral => &language_syntax.string_literal_color,
-        TokenType::Whitespace => &language_syntax.whitespace_color,
-        TokenType::Unknown => &language_syntax.unknown_color,
-        TokenType::Comment => &language_syntax.comment_color,
-        TokenType::ClassName => &language_syntax.class_name_color,
-        TokenType::FunctionName => &language_syntax.function_name_color,
-    }.to_string();
-
     Token {
         token_type,
         value: value.to_string(),
-        color,
+        color: Arc::clone(theme.color(language, token_type)),
+        style: theme.style(language, token_type).cloned(),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+
+    #[test]
+    fn test_token_spans() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let tokens = tokenize("python", &theme, "s = \"né😀\"  # x", &LineState::Normal).tokens;
+
+        // whitespace is left out, offsets count UTF-16 code units
+        assert_eq!(token_spans(&tokens), vec![
+            TokenSpan(TokenType::Identifier, 0, 1),
+            TokenSpan(TokenType::Operator, 2, 1),
+            TokenSpan(TokenType::StringLiteral, 4, 6),
+            TokenSpan(TokenType::Comment, 12, 3),
+        ]);
+        assert!(token_spans(&[]).is_empty());
+    }
+}
@@ -0,0 +1,457 @@
+// Document outline of a tokenized file: the functions, methods, types, impl blocks and
+// top-level constants it declares, nested the way they are in the source.
+// Declarations are found from the Keyword, FunctionName and ClassName tokens,
+// a symbol's body from the bracket pairs or, in Python, from its indentation.
+
+use serde::{Serialize, Deserialize};
+
+use crate::syntax_highlight::brackets::{BracketPairs, BracketPosition};
+use crate::syntax_highlight::folding::{fold_ranges, FoldKind};
+use crate::syntax_highlight::{LineContent, Token, TokenType};
+
+// languages whose functions are found by their name followed by `(`, having no keyword to go by.
+// Only declarations with a body count there, prototypes and macro calls end with `;` first
+const KEYWORDLESS_FUNCTION_LANGUAGES: [&str; 2] = ["c", "cpp"];
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SymbolKind {
+    Function,
+    // a function inside a class, impl, trait or interface
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    Impl,
+    Module,
+    TypeAlias,
+    Constant,
+}
+
+// line and column of the name, columns count UTF-16 code units like TokenSpan.
+// end_line is the last line of the body, `line` for declarations without one
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub children: Vec<Symbol>,
+}
+
+// a token other than whitespace and comments, `depth` counts the brackets open before it
+struct CodeToken<'a> {
+    token: &'a Token,
+    line: usize,
+    column: usize,
+    token_index: usize,
+    depth: usize,
+}
+
+impl CodeToken<'_> {
+    fn is(&self, token_type: TokenType, value: &str) -> bool {
+        self.token.token_type == token_type && self.token.value == value
+    }
+
+    fn is_name(&self) -> bool {
+        matches!(self.token.token_type, TokenType::Identifier | TokenType::FunctionName | TokenType::ClassName)
+    }
+
+    fn position(&self) -> BracketPosition {
+        BracketPosition { line: self.line, column: self.column }
+    }
+}
+
+struct Declaration {
+    symbol: Symbol,
+    parent: Option<usize>,
+    // position of the `}` closing the body, None until the body is found
+    body_end: Option<BracketPosition>,
+    // depth of the tokens directly inside the body
+    body_depth: usize,
+    // depth of the declaration itself, its body opens at this depth
+    depth: usize,
+    // dropped when a `;` comes before a body
+    needs_body: bool,
+}
+
+fn code_tokens(line_contents: &[LineContent]) -> Vec<CodeToken<'_>> {
+    let mut code_tokens = Vec::new();
+    let mut depth: usize = 0;
+
+    for (line, line_content) in line_contents.iter().enumerate() {
+        let mut column = 0;
+
+        for (token_index, token) in line_content.tokens.iter().enumerate() {
+            let is_code = !matches!(
+                token.token_type,
+                TokenType::Whitespace | TokenType::Comment | TokenType::Annotation | TokenType::Url
+            );
+            if is_code {
+                code_tokens.push(CodeToken { token, line, column, token_index, depth });
+            }
+            if token.token_type == TokenType::Punctuation {
+                for bracket in token.value.chars() {
+                    match bracket {
+                        '(' | '[' | '{' => depth += 1,
+                        ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                        _ => {},
+                    }
+                }
+            }
+            column += token.value.encode_utf16().count();
+        }
+    }
+    code_tokens
+}
+
+// text of the line from token `start` up to, without, token `end`
+fn line_text(line_content: &LineContent, start: usize, end: usize) -> String {
+    line_content.tokens[start..end].iter()
+        .map(|token| token.value.as_str())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// `impl<T> Display for Point<T>`, the name is what follows `impl` and its generics
+fn impl_name(line_contents: &[LineContent], tokens: &[CodeToken], index: usize) -> Option<String> {
+    let impl_token = &tokens[index];
+    let mut start = index + 1;
+
+    if tokens.get(start).is_some_and(|token| token.token.value == "<") {
+        let mut angle_depth = 0;
+        for (offset, token) in tokens[start..].iter().enumerate() {
+            angle_depth += token.token.value.matches('<').count();
+            angle_depth = angle_depth.saturating_sub(token.token.value.matches('>').count());
+            if angle_depth == 0 {
+                start += offset + 1;
+                break;
+            }
+        }
+    }
+    let name_start = tokens.get(start).filter(|token| token.line == impl_token.line)?;
+    let name_end = tokens[start..].iter()
+        .take_while(|token| token.line == impl_token.line)
+        .find(|token| {
+            token.depth == impl_token.depth && (token.token.value == "{" || token.token.value == ";" || token.is(TokenType::Keyword, "where"))
+        })
+        .map_or(line_contents[impl_token.line].tokens.len(), |token| token.token_index);
+
+    let name = line_text(&line_contents[impl_token.line], name_start.token_index, name_end);
+    (!name.is_empty()).then_some(name)
+}
+
+// Name token, kind and, for impl blocks, the name of a declaration starting at token `index`.
+// `parent` is the declaration whose body the token is in
+fn find_declaration(
+    language: &str, 
+    line_contents: &[LineContent], 
+    tokens: &[CodeToken], 
+    index: usize, 
+    parent: Option<&Declaration>
+) -> Option<(usize, SymbolKind, Option<String>)> {
+    let token = &tokens[index];
+    let next = tokens.get(index + 1);
+    let next_is_name = next.is_some_and(|next| next.is_name());
+    let parent_kind = parent.map(|parent| parent.symbol.kind);
+
+    if token.token.token_type == TokenType::Keyword {
+        match token.token.value.as_str() {
+            "fn" | "function" | "def" if next_is_name => return Some((index + 1, SymbolKind::Function, None)),
+            // a Go method's receiver comes before its name, `func (p *Point) Scale(`.
+            // Func literals like `func(x int) bool {` have no name followed by `(` there
+            "func" if next.is_some_and(|next| next.token.value == "(") => {
+                let name_index = tokens[index + 2..].iter()
+                    .position(|receiver_token| receiver_token.depth == token.depth)
+                    .map(|offset| index + 2 + offset)?;
+                let name = &tokens[name_index];
+                let is_call = tokens.get(name_index + 1).is_some_and(|open| open.line == name.line && open.token.value == "(");
+                return (name.is_name() && is_call).then_some((name_index, SymbolKind::Method, None));
+            },
+            "func" if next_is_name => return Some((index + 1, SymbolKind::Function, None)),
+            "class" if next_is_name => return Some((index + 1, SymbolKind::Class, None)),
+            "struct" if next_is_name => return Some((index + 1, SymbolKind::Struct, None)),
+            "union" if next_is_name => return Some((index + 1, SymbolKind::Struct, None)),
+            "enum" if next_is_name => return Some((index + 1, SymbolKind::Enum, None)),
+            "interface" if next_is_name => return Some((index + 1, SymbolKind::Interface, None)),
+            "trait" if next_is_name => return Some((index + 1, SymbolKind::Trait, None)),
+            "mod" | "namespace" | "module" if next_is_name => return Some((index + 1, SymbolKind::Module, None)),
+            // Go's `type Point struct {`
+            "type" if next_is_name => {
+                let kind = match tokens.get(index + 2).map(|token| token.token.value.as_str()) {
+                    Some("struct") => SymbolKind::Struct,
+                    Some("interface") => SymbolKind::Interface,
+                    _ => SymbolKind::TypeAlias,
+                };
+                return Some((index + 1, kind, None));
+            },
+            "impl" => return impl_name(line_contents, tokens, index).map(|name| (index, SymbolKind::Impl, Some(name))),
+            // `const handler = (event) => {` and `const f = function () {` are functions
+            "const" | "static" if next_is_name && token.depth == 0 => {
+                let is_function = tokens[index + 2..].iter()
+                    .take_while(|value_token| value_token.line == token.line)
+                    .any(|value_token| value_token.token.value == "=>" || value_token.is(TokenType::Keyword, "function"));
+                let kind = if is_function { SymbolKind::Function } else { SymbolKind::Constant };
+                return Some((index + 1, kind, None));
+            },
+            _ => return None,
+        }
+    }
+
+    let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+    let starts_line = previous.map_or(true, |previous| previous.line < token.line);
+
+    // Python's module level `MAX_SIZE = 10`
+    if language == "python" && token.column == 0 && token.is_name() {
+        let is_constant_name = token.token.value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        let is_assigned = next.is_some_and(|next| next.line == token.line && next.is(TokenType::Operator, "="));
+        if is_constant_name && token.token.value.chars().any(|c| c.is_ascii_uppercase()) && is_assigned {
+            return Some((index, SymbolKind::Constant, None));
+        }
+    }
+    if token.token.token_type != TokenType::FunctionName {
+        return None;
+    }
+    // methods of a class or interface body, `static async load(` or `area(): number;`.
+    // A call in a field initializer follows an operator
+    let in_type_body = matches!(parent_kind, Some(SymbolKind::Class | SymbolKind::Interface)) && language != "python";
+    if in_type_body && parent.is_some_and(|parent| parent.body_depth == token.depth) {
+        let after_operator = previous.is_some_and(|previous| previous.token.token_type == TokenType::Operator && !starts_line);
+        return (!after_operator).then_some((index, SymbolKind::Method, None));
+    }
+    // C and C++ function definitions at the top level or in a namespace, class or struct
+    let in_scope = match parent {
+        None => token.depth == 0,
+        Some(parent) => {
+            matches!(parent.symbol.kind, SymbolKind::Module | SymbolKind::Class | SymbolKind::Struct)
+                && parent.body_depth == token.depth
+        },
+    };
+    if KEYWORDLESS_FUNCTION_LANGUAGES.contains(&language) && in_scope {
+        let after_operator = previous.is_some_and(|previous| {
+            previous.token.token_type == TokenType::Operator && !matches!(previous.token.value.as_str(), "::" | "*" | "&" | "~")
+        });
+        return (!after_operator).then_some((index, SymbolKind::Function, None));
+    }
+    None
+}
+
+pub fn outline(language: &str, line_contents: &[LineContent]) -> Vec<Symbol> {
+    let tokens = code_tokens(line_contents);
+    let bracket_pairs = BracketPairs::analyze(line_contents);
+    let by_indentation = language == "python";
+    // with indentation a block ends at the last line of the region folding starts at its line
+    let indentation_block_ends: Vec<(usize, usize)> = match by_indentation {
+        true => fold_ranges(language, line_contents).into_iter()
+            .filter(|fold_range| fold_range.kind == FoldKind::Region)
+            .map(|fold_range| (fold_range.start_line, fold_range.end_line))
+            .collect(),
+        false => Vec::new(),
+    };
+
+    let mut declarations: Vec<Declaration> = Vec::new();
+    // declarations whose body the current token is in, innermost last
+    let mut open_declarations: Vec<usize> = Vec::new();
+    // declaration whose body hasn't started yet
+    let mut pending: Option<usize> = None;
+
+    for (index, token) in tokens.iter().enumerate() {
+        while let Some(&open) = open_declarations.last() {
+            let body_end = declarations[open].body_end.unwrap();
+            if (token.line, token.column) < (body_end.line, body_end.column) {
+                break;
+            }
+            open_declarations.pop();
+        }
+
+        if let Some(pending_index) = pending {
+            let declaration = &mut declarations[pending_index];
+            // Without semicolons a declaration ends with its line. It goes on with `{` or
+            // `where` on the next line, or when either line breaks at an operator or a `,`
+            let previous = &tokens[index - 1];
+            let continues_declaration = token.line == previous.line
+                || token.depth > declaration.depth
+                || token.token.value == "{"
+                || token.is(TokenType::Keyword, "where")
+                || token.token.token_type == TokenType::Operator
+                || previous.token.token_type == TokenType::Operator
+                || matches!(previous.token.value.as_str(), "," | ":" | "where");
+
+            if token.depth == declaration.depth && token.token.value == "{" {
+                declaration.body_end = bracket_pairs.get(token.position()).and_then(|bracket| bracket.partner);
+                declaration.body_depth = token.depth + 1;
+                if let Some(body_end) = declaration.body_end {
+                    declaration.symbol.end_line = body_end.line;
+                    open_declarations.push(pending_index);
+                }
+                pending = None;
+                continue;
+            }
+            let ends_declaration = token.depth < declaration.depth
+                || (token.depth == declaration.depth && token.token.value == ";")
+                || !continues_declaration;
+            if ends_declaration {
+                if declaration.needs_body {
+                    declarations.pop();
+                }
+                pending = None;
+            }
+            else {
+                continue;
+            }
+        }
+
+        let parent = open_declarations.last().copied();
+        let Some((name_index, kind, name)) = find_declaration(
+            language, 
+            line_contents, 
+            &tokens, 
+            index, 
+            parent.map(|parent| &declarations[parent])
+        ) else {
+            continue;
+        };
+
+        let name_token = &tokens[name_index];
+        let parent_kind = parent.map(|parent| declarations[parent].symbol.kind);
+        let kind = match (kind, parent_kind) {
+            (SymbolKind::Function, Some(SymbolKind::Class | SymbolKind::Impl | SymbolKind::Trait | SymbolKind::Interface)) => SymbolKind::Method,
+            _ => kind,
+        };
+        let symbol = Symbol {
+            name: name.unwrap_or_else(|| name_token.token.value.clone()),
+            kind,
+            line: token.line,
+            column: name_token.column,
+            end_line: token.line,
+            children: Vec::new(),
+        };
+        // forward declarations, prototypes and variables of a struct type end with `;`
+        let needs_body = KEYWORDLESS_FUNCTION_LANGUAGES.contains(&language);
+        declarations.push(Declaration { symbol, parent, body_end: None, body_depth: 0, depth: token.depth, needs_body });
+
+        if by_indentation {
+            let declaration_index = declarations.len() - 1;
+            let declaration = &mut declarations[declaration_index];
+            let block = indentation_block_ends.binary_search_by_key(&token.line, |&(start_line, _)| start_line);
+            if let Ok(block) = block {
+                let end_line = indentation_block_ends[block].1;
+                declaration.symbol.end_line = end_line;
+                // anything up to the end of the last line of the block is inside it
+                declaration.body_end = Some(BracketPosition { line: end_line, column: usize::MAX });
+                open_declarations.push(declaration_index);
+            }
+        }
+        else {
+            pending = Some(declarations.len() - 1);
+        }
+    }
+    if pending.is_some_and(|pending_index| declarations[pending_index].needs_body) {
+        declarations.pop();
+    }
+
+    nest_symbols(declarations)
+}
+
+// the declarations as a tree, every symbol under its parent in source order
+fn nest_symbols(declarations: Vec<Declaration>) -> Vec<Symbol> {
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); declarations.len()];
+    let mut roots: Vec<usize> = Vec::new();
+    for (index, declaration) in declarations.iter().enumerate() {
+        match declaration.parent {
+            Some(parent) => children[parent].push(index),
+            None => roots.push(index),
+        }
+    }
+    let mut symbols: Vec<Option<Symbol>> = declarations.into_iter().map(|declaration| Some(declaration.symbol)).collect();
+
+    fn build(index: usize, symbols: &mut [Option<Symbol>], children: &[Vec<usize>]) -> Symbol {
+        let mut symbol = symbols[index].take().unwrap();
+        symbol.children = children[index].iter().map(|&child| build(child, symbols, children)).collect();
+        symbol
+    }
+    roots.iter().map(|&root| build(root, &mut symbols, &children)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize_lines;
+    use crate::settings::Settings;
+    use crate::syntax_highlight::theme::Theme;
+
+    #[test]
+    fn test_outline() {
+        let theme = Theme::new(&Settings::init_default_values());
+        // (name, kind, line, column, end_line, children) of every symbol, depth first
+        fn flatten(symbols: &[Symbol], flat: &mut Vec<(String, SymbolKind, usize, usize, usize, usize)>) {
+            for symbol in symbols {
+                flat.push((symbol.name.clone(), symbol.kind, symbol.line, symbol.column, symbol.end_line, symbol.children.len()));
+                flatten(&symbol.children, flat);
+            }
+        }
+        let symbols = |language: &str, source: &str| {
+            let lines: Vec<String> = source.lines().map(|line| line.to_string()).collect();
+            let mut flat = Vec::new();
+            flatten(&outline(language, &tokenize_lines(language, &theme, &lines)), &mut flat);
+            flat
+        };
+        let symbol = |name: &str, kind: SymbolKind, line: usize, column: usize, end_line: usize, children: usize| {
+            (name.to_string(), kind, line, column, end_line, children)
+        };
+
+        let rust = "const MAX: usize = 10;\nstruct Point { x: f64 }\nimpl<T> Display for Point<T>\nwhere\n    T: Debug,\n{\n    fn fmt(&self) -> String {\n        fn inner() {}\n        format!(\"{}\", self.x)\n    }\n}\npub trait Shape {\n    fn area(&self) -> f64;\n}\nenum Color { Red }";
+        assert_eq!(symbols("rust", rust), [
+            symbol("MAX", SymbolKind::Constant, 0, 6, 0, 0), 
+            symbol("Point", SymbolKind::Struct, 1, 7, 1, 0), 
+            symbol("Display for Point<T>", SymbolKind::Impl, 2, 0, 10, 1), 
+            symbol("fmt", SymbolKind::Method, 6, 7, 9, 1), 
+            symbol("inner", SymbolKind::Function, 7, 11, 7, 0), 
+            symbol("Shape", SymbolKind::Trait, 11, 10, 13, 1), 
+            symbol("area", SymbolKind::Method, 12, 7, 12, 0), 
+            symbol("Color", SymbolKind::Enum, 14, 5, 14, 0)
+        ]);
+
+        let python = "MAX_SIZE = 10\nclass Reader(Base):\n    def read(self):\n        return len(self.data)\n\n    def close(self):\n        pass\n\ndef main():\n    Reader().read()";
+        assert_eq!(symbols("python", python), [
+            symbol("MAX_SIZE", SymbolKind::Constant, 0, 0, 0, 0), 
+            symbol("Reader", SymbolKind::Class, 1, 6, 6, 2), 
+            symbol("read", SymbolKind::Method, 2, 8, 3, 0), 
+            symbol("close", SymbolKind::Method, 5, 8, 6, 0), 
+            symbol("main", SymbolKind::Function, 8, 4, 9, 0)
+        ]);
+
+        // without semicolons a constant ends with its line
+        let typescript = "interface Shape {\n  area(): number\n  name(): string\n}\nconst LIMIT = 5\nexport const handler = (event) => {\n  return event\n}\nclass Circle {\n  static create() {\n    return new Circle()\n  }\n}";
+        assert_eq!(symbols("typescript", typescript), [
+            symbol("Shape", SymbolKind::Interface, 0, 10, 3, 2), 
+            symbol("area", SymbolKind::Method, 1, 2, 1, 0), 
+            symbol("name", SymbolKind::Method, 2, 2, 2, 0), 
+            symbol("LIMIT", SymbolKind::Constant, 4, 6, 4, 0), 
+            symbol("handler", SymbolKind::Function, 5, 13, 7, 0), 
+            symbol("Circle", SymbolKind::Class, 8, 6, 12, 1), 
+            symbol("create", SymbolKind::Method, 9, 9, 11, 0)
+        ]);
+
+        let go = "type Point struct {\n\tX int\n}\nfunc (p *Point) Scale(f int) {\n\tp.X *= f\n}\nfunc main() {\n}";
+        assert_eq!(symbols("go", go), [
+            symbol("Point", SymbolKind::Struct, 0, 5, 2, 0), 
+            symbol("Scale", SymbolKind::Method, 3, 16, 5, 0), 
+            symbol("main", SymbolKind::Function, 6, 5, 7, 0)
+        ]);
+
+        // func literals are not methods
+        let go_literals = "func main() {\n\tf := func(x int) bool {\n\t\treturn x > 0\n\t}\n\tgo func() error {\n\t\treturn nil\n\t}()\n}";
+        assert_eq!(symbols("go", go_literals), [symbol("main", SymbolKind::Function, 0, 5, 7, 0)]);
+
+        // prototypes and calls are left out
+        let c = "int add(int a, int b);\nint add(int a, int b)\n{\n    return printf(\"%d\", a + b);\n}";
+        assert_eq!(symbols("c", c), [symbol("add", SymbolKind::Function, 1, 4, 4, 0)]);
+    }
+}
@@ -0,0 +1,302 @@
+// scanning helpers shared by every language tokenizer
+// each handler consumes input starting at `pos` and pushes the resulting token(s)
+
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, BlockCommentSyntax, create_token};
+use crate::syntax_highlight::languages::get_language;
+
+pub fn handle_whitespace(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && input_chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Whitespace, &value));
+}
+
+// comment running until the end of the line, e.g. `// ...` or `# ...`
+pub fn handle_line_comment(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && input_chars[*pos] != '\n' {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Comment, &value));
+}
+
+// block comment starting at `pos`, sets `state` when it continues on the next line
+pub fn handle_block_comment(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    syntax: &BlockCommentSyntax, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_pos = *pos;
+    *pos += syntax.open.chars().count(); // Move past the opening marker
+
+    let depth = scan_block_comment(input_chars, pos, syntax.open, syntax.close, 1, syntax.nested);
+    if depth > 0 {
+        *state = LineState::InBlockComment { depth };
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Comment, &value));
+}
+
+pub fn handle_number(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && input_chars[*pos].is_ascii_digit() {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    tokens.push(create_token(theme, language, TokenType::Number, &value));
+}
+
+pub fn handle_operator(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    current_char: char, 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let mut value = current_char.to_string();
+    *pos += 1;
+
+    // Handle multi-character operators like ==, ===, =>, etc.
+    if let Some(next_char) = input_chars.get(*pos) {
+        if current_char == '=' && (*next_char == '=' || *next_char == '>') {
+            value.push(*next_char);
+            *pos += 1;
+
+            // Handle === case
+            if *next_char == '=' && *pos < input_chars.len() && input_chars[*pos] == '=' {
+                value.push('=');
+                *pos += 1;
+            }
+        } else if current_char == '!' && *next_char == '=' {
+            value.push(*next_char);
+            *pos += 1;
+        }
+    }
+
+    tokens.push(create_token(theme, language, TokenType::Operator, &value));
+}
+
+// identifier followed by `(` is a function name, otherwise keyword, class name
+// (starts with an uppercase letter) or plain identifier
+pub fn handle_identifier(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    keywords: &[impl AsRef<str>], 
+    input_chars: &[char], 
+    pos: &mut usize
+) {
+    let start_pos = *pos;
+    while *pos < input_chars.len() && (input_chars[*pos].is_alphanumeric() || input_chars[*pos] == '_') {
+        *pos += 1;
+    }
+    let value: String = input_chars[start_pos..*pos].iter().collect();
+    let next_char = input_chars.get(*pos).unwrap_or(&' ');
+
+    if *next_char == '(' {
+        tokens.push(create_token(theme, language, TokenType::FunctionName, &value));
+    } else {
+        let token_type;
+        if keywords.iter().any(|keyword| keyword.as_ref() == value) {
+            token_type = TokenType::Keyword;
+        } else if value.chars().next().unwrap().is_uppercase() {
+            token_type = TokenType::ClassName;
+        } else {
+            token_type = TokenType::Identifier;
+        }
+        tokens.push(create_token(theme, language, token_type, &value));
+    }
+}
+
+// first character after `pos` that isn't whitespace
+pub fn peek_non_whitespace(input_chars: &[char], pos: usize) -> Option<char> {
+    input_chars[pos.min(input_chars.len())..].iter().find(|ch| !ch.is_whitespace()).copied()
+}
+
+pub fn handle_punctuation(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    current_char: char, 
+    pos: &mut usize
+) {
+    let value = current_char.to_string();
+    tokens.push(create_token(theme, language, TokenType::Punctuation, &value));
+    *pos += 1;
+}
+
+pub fn handle_unknown(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    current_char: char, 
+    pos: &mut usize
+) {
+    let value = current_char.to_string();
+    tokens.push(create_token(theme, language, TokenType::Unknown, &value));
+    *pos += 1;
+}
+
+// Move `pos` past the closing `delimiter` of a string literal.
+// Returns false when the line ends before the delimiter, pos is then at the end of the line.
+pub fn scan_string_body(
+    input_chars: &[char], 
+    pos: &mut usize, 
+    delimiter: &str, 
+    raw: bool
+) -> bool {
+    let delimiter_chars: Vec<char> = delimiter.chars().collect();
+
+    while *pos < input_chars.len() {
+        // escaped characters never close the string, raw strings have no escapes
+        if !raw && input_chars[*pos] == '\\' {
+            *pos = (*pos + 2).min(input_chars.len());
+            continue;
+        }
+        if input_chars[*pos..].starts_with(&delimiter_chars) {
+            *pos += delimiter_chars.len();
+            return true;
+        }
+        *pos += 1;
+    }
+    false
+}
+
+// Move `pos` past a block comment body that is `depth` levels deep.
+// Returns the depth left open at the end of the line, 0 when the comment is closed.
+// Languages without nested comments pass nested = false so an inner opener is ignored.
+pub fn scan_block_comment(
+    input_chars: &[char], 
+    pos: &mut usize, 
+    open: &str, 
+    close: &str, 
+    mut depth: u32, 
+    nested: bool
+) -> u32 {
+    let open_chars: Vec<char> = open.chars().collect();
+    let close_chars: Vec<char> = close.chars().collect();
+
+    while *pos < input_chars.len() && depth > 0 {
+        if input_chars[*pos..].starts_with(&close_chars) {
+            *pos += close_chars.len();
+            depth -= 1;
+        } else if nested && input_chars[*pos..].starts_with(&open_chars) {
+            *pos += open_chars.len();
+            depth += 1;
+        } else {
+            *pos += 1;
+        }
+    }
+    depth
+}
+
+// Hand `input_chars[*pos..end]` to an embedded language, continuing from its `state`.
+// Returns the embedded language's state at `end`.
+pub fn tokenize_embedded(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    end: usize, 
+    state: &LineState
+) -> LineState {
+    let segment: String = input_chars[*pos..end].iter().collect();
+    let (embedded_tokens, embedded_state) = get_language(language).tokenize_line(theme, &segment, state);
+
+    tokens.extend(embedded_tokens);
+    *pos = end;
+    embedded_state
+}
+
+// Finish a construct left open by the previous line before regular tokenizing starts.
+// Updates `state` to Normal once the construct is closed on this line.
+pub fn handle_line_state(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    block_comment: Option<&BlockCommentSyntax>, 
+    input_chars: &[char], 
+    pos: &mut usize, 
+    state: &mut LineState
+) {
+    let start_pos = *pos;
+
+    let token_type = match state.clone() {
+        LineState::Normal => return,
+        LineState::InBlockComment { depth } => {
+            let Some(syntax) = block_comment else {
+                *state = LineState::Normal;
+                return;
+            };
+            let depth = scan_block_comment(input_chars, pos, syntax.open, syntax.close, depth, syntax.nested);
+            *state = if depth > 0 { LineState::InBlockComment { depth } } else { LineState::Normal };
+            TokenType::Comment
+        },
+        LineState::InString { delimiter, raw } => {
+            if scan_string_body(input_chars, pos, &delimiter, raw) {
+                *state = LineState::Normal;
+            }
+            TokenType::StringLiteral
+        },
+        LineState::InIndentedBlock { indent } => {
+            let line_indent = input_chars.iter().take_while(|ch| ch.is_whitespace()).count();
+            // blank lines never end the block
+            if line_indent < input_chars.len() && line_indent as u32 <= indent {
+                *state = LineState::Normal;
+                return;
+            }
+            *pos = input_chars.len();
+            TokenType::StringLiteral
+        },
+        LineState::InHeredoc { delimiter, strip_tabs } => {
+            let line: String = input_chars.iter().collect();
+            let terminator = if strip_tabs { line.trim_start_matches('\t') } else { line.as_str() };
+            *pos = input_chars.len();
+            if terminator == delimiter {
+                *state = LineState::Normal;
+                TokenType::Operator
+            } else {
+                TokenType::StringLiteral
+            }
+        },
+        // the language knows where the region ends and what's inside, it resumes it itself
+        LineState::InEmbedded { .. } 
+        | LineState::InTag { .. } 
+        | LineState::InInterpolatedString { .. } 
+        | LineState::InScript { .. } => return,
+    };
+
+    // empty lines inside a comment or string stay empty lines
+    if *pos > start_pos {
+        let value: String = input_chars[start_pos..*pos].iter().collect();
+        tokens.push(create_token(theme, language, token_type, &value));
+    }
+}
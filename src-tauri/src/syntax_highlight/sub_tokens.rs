@@ -0,0 +1,310 @@
+// Second pass over a line's tokens, after the language's tokenizer ran. Comments and
+// string literals are split into sub-tokens for the parts worth a color of their own:
+// TODO/FIXME/HACK/XXX annotations and URLs in comments, URLs, escape sequences and
+// format placeholders in strings. The pass only looks at the token text, the same
+// rules apply to every language.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::{Token, TokenType, LineState, create_token};
+
+const ANNOTATIONS: [&str; 4] = ["TODO", "FIXME", "HACK", "XXX"];
+const URL_SCHEMES: [&str; 6] = ["https://", "http://", "ftp://", "file://", "wss://", "ws://"];
+// conversions of printf style placeholders, C's plus Python's `%r` and Go's `%v`, `%q`, `%T`, `%t` and `%w`
+const PRINTF_CONVERSIONS: &str = "diouxXeEfFgGcsprvqTtw";
+
+// `start_state` is the state the line started in, a string continued from the previous
+// line is raw when the string it continues was
+pub fn split_sub_tokens(
+    theme: &Theme, 
+    language: &str, 
+    tokens: Vec<Token>, 
+    start_state: &LineState
+) -> Vec<Token> {
+    // most lines have nothing to split
+    if !tokens.iter().any(|token| matches!(token.token_type, TokenType::Comment | TokenType::StringLiteral)) {
+        return tokens;
+    }
+    let mut split_tokens = Vec::with_capacity(tokens.len());
+
+    for (index, token) in tokens.into_iter().enumerate() {
+        let sub_tokens = match token.token_type {
+            TokenType::Comment => comment_sub_tokens(&token.value),
+            TokenType::StringLiteral => {
+                let raw = match start_state {
+                    LineState::InString { raw, .. } if index == 0 => *raw,
+                    _ => is_raw_string(language, &token.value),
+                };
+                // `{2}` in a JavaScript regex literal is a quantifier
+                let regex = token.value.starts_with('/');
+                string_sub_tokens(&token.value, raw, !regex)
+            }
+            _ => Vec::new(),
+        };
+        if sub_tokens.is_empty() {
+            split_tokens.push(token);
+        }
+        else {
+            push_split_token(&mut split_tokens, theme, language, &token, &sub_tokens);
+        }
+    }
+    split_tokens
+}
+
+// the parts of `token` between the sub-tokens keep its type, color and style
+fn push_split_token(
+    tokens: &mut Vec<Token>, 
+    theme: &Theme, 
+    language: &str, 
+    token: &Token, 
+    sub_tokens: &[(Range<usize>, TokenType)]
+) {
+    let rest_of_token = |range: Range<usize>| Token {
+        token_type: token.token_type,
+        value: token.value[range].to_string(),
+        color: Arc::clone(&token.color),
+        style: token.style.clone(),
+    };
+    let mut end = 0;
+
+    for (range, token_type) in sub_tokens {
+        if range.start > end {
+            tokens.push(rest_of_token(end..range.start));
+        }
+        tokens.push(create_token(theme, language, *token_type, &token.value[range.clone()]));
+        end = range.end;
+    }
+    if end < token.value.len() {
+        tokens.push(rest_of_token(end..token.value.len()));
+    }
+}
+
+// Raw strings have no escape sequences: prefixed ones like Rust's r"..", Python's r'..',
+// C++'s R"(..)" and C#'s @"..", and the quotes some languages never escape in
+fn is_raw_string(language: &str, value: &str) -> bool {
+    let Some(quote_pos) = value.find(['"', '\'', '`', '#']) else {
+        return false;
+    };
+    let prefix = &value[..quote_pos];
+
+    if prefix.is_empty() {
+        let quote = value.as_bytes()[0];
+        return match language {
+            "go" => quote == b'`',
+            "shell" | "toml" | "yaml" | "dockerfile" | "makefile" => quote == b'\'',
+            _ => false,
+        };
+    }
+    prefix.len() <= 3
+        && prefix.chars().all(|c| "rRbBuUfFcCL8@$".contains(c))
+        && prefix.contains(['r', 'R', '@'])
+}
+
+fn comment_sub_tokens(value: &str) -> Vec<(Range<usize>, TokenType)> {
+    let bytes = value.as_bytes();
+    let mut sub_tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if let Some(end) = url_end(bytes, pos) {
+            sub_tokens.push((pos..end, TokenType::Url));
+            pos = end;
+        }
+        else if let Some(end) = annotation_end(bytes, pos) {
+            sub_tokens.push((pos..end, TokenType::Annotation));
+            pos = end;
+        }
+        else {
+            pos += 1;
+        }
+    }
+    sub_tokens
+}
+
+fn string_sub_tokens(value: &str, raw: bool, placeholders: bool) -> Vec<(Range<usize>, TokenType)> {
+    let bytes = value.as_bytes();
+    let mut sub_tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if let Some(end) = url_end(bytes, pos) {
+            sub_tokens.push((pos..end, TokenType::Url));
+            pos = end;
+        }
+        else if bytes[pos] == b'\\' && !raw {
+            // a `\` ending the line continues the string, it escapes nothing on this line
+            let Some(end) = escape_end(value, pos) else {
+                break;
+            };
+            sub_tokens.push((pos..end, TokenType::EscapeSequence));
+            pos = end;
+        }
+        else if let Some(end) = placeholder_end(bytes, pos).filter(|_| placeholders) {
+            sub_tokens.push((pos..end, TokenType::Placeholder));
+            pos = end;
+        }
+        // `{{`, `}}` and `%%` are a literal brace or percent sign, not the start of a placeholder
+        else if matches!(bytes[pos], b'{' | b'}' | b'%') && bytes.get(pos + 1) == Some(&bytes[pos]) {
+            pos += 2;
+        }
+        else {
+            pos += 1;
+        }
+    }
+    sub_tokens
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+fn annotation_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    if pos > 0 && is_word_byte(bytes[pos - 1]) {
+        return None;
+    }
+    let annotation = ANNOTATIONS.iter().find(|annotation| bytes[pos..].starts_with(annotation.as_bytes()))?;
+    let end = pos + annotation.len();
+
+    (!bytes.get(end).is_some_and(|&byte| is_word_byte(byte))).then_some(end)
+}
+
+// End of a URL starting at `pos`. It runs up to whitespace, a quote or a bracket
+// no URL contains, without the punctuation of the sentence around it
+fn url_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    if pos > 0 && bytes[pos - 1].is_ascii_alphanumeric() {
+        return None;
+    }
+    let scheme = URL_SCHEMES.iter().find(|scheme| bytes[pos..].starts_with(scheme.as_bytes()))?;
+
+    let mut end = pos + scheme.len();
+    while end < bytes.len() && bytes[end].is_ascii_graphic() && !b"<>\"'`\\{}|^".contains(&bytes[end]) {
+        end += 1;
+    }
+    loop {
+        let url = &bytes[pos..end];
+        match url.last() {
+            Some(b'.' | b',' | b';' | b':' | b'!' | b'?' | b'*') => end -= 1,
+            // `(see https://example.com)`, a link to a wiki page keeps its parentheses
+            Some(b')') if !url.contains(&b'(') => end -= 1,
+            Some(b']') if !url.contains(&b'[') => end -= 1,
+            _ => break,
+        }
+    }
+    (end > pos + scheme.len()).then_some(end)
+}
+
+fn count_while(bytes: &[u8], start: usize, max: usize, predicate: impl Fn(u8) -> bool) -> usize {
+    bytes[start.min(bytes.len())..].iter()
+        .take(max)
+        .take_while(|&&byte| predicate(byte))
+        .count()
+}
+
+// End of the escape sequence starting with the `\` at `pos`
+fn escape_end(value: &str, pos: usize) -> Option<usize> {
+    let bytes = value.as_bytes();
+    let escaped = value[pos + 1..].chars().next()?;
+    let after = pos + 2;
+
+    let end = match escaped {
+        'x' => after + count_while(bytes, after, 2, |byte| byte.is_ascii_hexdigit()),
+        // `\u{1F600}` and Python's `\N{DASH}`
+        'u' | 'N' if bytes.get(after) == Some(&b'{') => {
+            let name_length = count_while(bytes, after + 1, 32, |byte| byte != b'}' && byte != b'\\');
+            match bytes.get(after + 1 + name_length) {
+                Some(b'}') => after + name_length + 2,
+                _ => after,
+            }
+        }
+        'u' if count_while(bytes, after, 4, |byte| byte.is_ascii_hexdigit()) == 4 => after + 4,
+        'U' if count_while(bytes, after, 8, |byte| byte.is_ascii_hexdigit()) == 8 => after + 8,
+        '0'..='7' => pos + 1 + count_while(bytes, pos + 1, 3, |byte| (b'0'..=b'7').contains(&byte)),
+        _ => pos + 1 + escaped.len_utf8(),
+    };
+    Some(end)
+}
+
+// End of a `{}` / `{0}` / `{name:>8.2}` or `%s` / `%-5.2f` / `%(name)s` placeholder starting at `pos`
+fn placeholder_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    match bytes[pos] {
+        b'{' => {
+            let mut end = pos + 1;
+            if bytes.get(end) == Some(&b'{') {
+                return None;
+            }
+            end += count_while(bytes, end, 32, |byte| is_word_byte(byte) || byte == b'.');
+            if bytes.get(end) == Some(&b':') {
+                end += 1 + count_while(bytes, end + 1, 32, |byte| !matches!(byte, b'{' | b'}' | b'"' | b'\''));
+            }
+            (bytes.get(end) == Some(&b'}')).then_some(end + 1)
+        }
+        b'%' => {
+            let mut end = pos + 1;
+            if bytes.get(end) == Some(&b'(') {
+                end += 1 + count_while(bytes, end + 1, 32, is_word_byte);
+                if bytes.get(end) != Some(&b')') {
+                    return None;
+                }
+                end += 1;
+            }
+            end += count_while(bytes, end, 4, |byte| b"-+#0".contains(&byte));
+            end += count_while(bytes, end, 3, |byte| byte.is_ascii_digit() || byte == b'*');
+            if bytes.get(end) == Some(&b'.') {
+                end += 1 + count_while(bytes, end + 1, 3, |byte| byte.is_ascii_digit() || byte == b'*');
+            }
+            end += count_while(bytes, end, 2, |byte| b"hlLzj".contains(&byte));
+
+            let conversion = *bytes.get(end)?;
+            PRINTF_CONVERSIONS.as_bytes().contains(&conversion).then_some(end + 1)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::settings::Settings;
+    use crate::syntax_highlight::LineContent;
+
+    #[test]
+    fn test_tokenize_sub_tokens() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let values_of = |line_content: &LineContent, token_type: TokenType| -> Vec<String> {
+            line_content.tokens.iter()
+                .filter(|token| token.token_type == token_type)
+                .map(|token| token.value.clone())
+                .collect()
+        };
+
+        let comment = tokenize("rust", &theme, "// TODO: see https://en.wikipedia.org/wiki/Rust_(language). FIXME(x) XXXL", &LineState::Normal);
+        assert_eq!(values_of(&comment, TokenType::Annotation), ["TODO", "FIXME"]);
+        assert_eq!(values_of(&comment, TokenType::Url), ["https://en.wikipedia.org/wiki/Rust_(language)"]);
+        assert_eq!(values_of(&comment, TokenType::Comment), ["// ", ": see ", ". ", "(x) XXXL"]);
+
+        let strings = tokenize("rust", &theme, "println!(\"{} is {name:>8}\\n\\u{1F600} {{x}} http://localhost:8080/\", r\"C:\\new\");", &LineState::Normal);
+        assert_eq!(values_of(&strings, TokenType::Placeholder), ["{}", "{name:>8}"]);
+        assert_eq!(values_of(&strings, TokenType::EscapeSequence), ["\\n", "\\u{1F600}"]);
+        assert_eq!(values_of(&strings, TokenType::Url), ["http://localhost:8080/"]);
+        // raw strings have no escapes
+        assert!(values_of(&strings, TokenType::StringLiteral).contains(&"r\"C:\\new\"".to_string()));
+
+        let escape = strings.tokens.iter().find(|token| token.token_type == TokenType::EscapeSequence).unwrap();
+        assert_eq!(escape.color, *theme.color("rust", TokenType::EscapeSequence));
+        assert_eq!(strings.tokens[3].color, *theme.color("rust", TokenType::StringLiteral));
+
+        let printf = tokenize("python", &theme, "print(\"%-5.2f%% of %(name)s, 50% off\\x41\" % x)", &LineState::Normal);
+        assert_eq!(values_of(&printf, TokenType::Placeholder), ["%-5.2f", "%(name)s"]);
+        assert_eq!(values_of(&printf, TokenType::EscapeSequence), ["\\x41"]);
+
+        let shell = tokenize("shell", &theme, "printf '%s\\n' \"a\\tb\"", &LineState::Normal);
+        assert_eq!(values_of(&shell, TokenType::EscapeSequence), ["\\t"]);
+
+        // the rest of a raw string continued from the previous line
+        let raw = tokenize("rust", &theme, "C:\\new\"#;", &LineState::InString { delimiter: "\"#".to_string(), raw: true });
+        assert_eq!(raw.tokens[0].value, "C:\\new\"#");
+    }
+}
@@ -0,0 +1,395 @@
+// Tree-sitter based highlighting, enabled with the `tree-sitter` cargo feature.
+// Unlike the line based lexers in `languages` the whole buffer is parsed, so highlight
+// captures can tell types, fields, methods and generic parameters apart.
+// The output is the same per-line LineContent the lexers produce.
+// Without the feature SyntaxTree can't be constructed and every file uses the lexers.
+
+use std::ops::Range;
+
+use crate::syntax_highlight::theme::Theme;
+use crate::syntax_highlight::LineContent;
+
+#[cfg(feature = "tree-sitter")]
+use std::collections::HashMap;
+#[cfg(feature = "tree-sitter")]
+use std::sync::OnceLock;
+#[cfg(feature = "tree-sitter")]
+use streaming_iterator::StreamingIterator;
+#[cfg(feature = "tree-sitter")]
+use tree_sitter::{InputEdit, Parser, Point, Query, QueryCursor, Tree};
+#[cfg(feature = "tree-sitter")]
+use crate::syntax_highlight::{Token, TokenType, LineState, create_token};
+#[cfg(feature = "tree-sitter")]
+use crate::syntax_highlight::sub_tokens::split_sub_tokens;
+
+#[cfg(feature = "tree-sitter")]
+struct Grammar {
+    language: tree_sitter::Language,
+    query: Query,
+}
+
+#[cfg(feature = "tree-sitter")]
+fn load_grammar(language: &str) -> Option<Grammar> {
+    let (ts_language, highlights_query): (tree_sitter::Language, String) = match language {
+        "rust" => (tree_sitter_rust::LANGUAGE.into(), tree_sitter_rust::HIGHLIGHTS_QUERY.to_string()),
+        "python" => (tree_sitter_python::LANGUAGE.into(), tree_sitter_python::HIGHLIGHTS_QUERY.to_string()),
+        // JSX patterns come first so they win over the generic identifier ones
+        "javascript" => (
+            tree_sitter_javascript::LANGUAGE.into(),
+            format!("{}\n{}", tree_sitter_javascript::JSX_HIGHLIGHT_QUERY, tree_sitter_javascript::HIGHLIGHT_QUERY)
+        ),
+        // the typescript query only covers what typescript adds on top of javascript
+        "typescript" => (
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            format!("{}\n{}", tree_sitter_typescript::HIGHLIGHTS_QUERY, tree_sitter_javascript::HIGHLIGHT_QUERY)
+        ),
+        "tsx" => (
+            tree_sitter_typescript::LANGUAGE_TSX.into(),
+            format!(
+                "{}\n{}\n{}", 
+                tree_sitter_javascript::JSX_HIGHLIGHT_QUERY, 
+                tree_sitter_typescript::HIGHLIGHTS_QUERY, 
+                tree_sitter_javascript::HIGHLIGHT_QUERY
+            )
+        ),
+        _ => return None,
+    };
+    let query = Query::new(&ts_language, &highlights_query).ok()?;
+    Some(Grammar { language: ts_language, query })
+}
+
+// grammars and their compiled highlight queries, built on first use
+#[cfg(feature = "tree-sitter")]
+static GRAMMARS: OnceLock<HashMap<&'static str, Grammar>> = OnceLock::new();
+
+#[cfg(feature = "tree-sitter")]
+fn grammar(language: &str) -> Option<&'static Grammar> {
+    GRAMMARS
+        .get_or_init(|| {
+            ["rust", "python", "javascript", "typescript", "tsx"].into_iter()
+                .filter_map(|name| load_grammar(name).map(|grammar| (name, grammar)))
+                .collect()
+        })
+        .get(language)
+}
+
+// map a highlight capture name like `function.method` onto a TokenType,
+// None leaves the text to nested captures or the defaults
+#[cfg(feature = "tree-sitter")]
+fn capture_token_type(capture_name: &str, node_kind: &str) -> Option<TokenType> {
+    let mut parts = capture_name.split('.');
+
+    let token_type = match (parts.next().unwrap_or(""), parts.next()) {
+        ("embedded", _) => return None,
+        // some grammars capture number literals as builtin constants
+        ("constant", Some("builtin")) if node_kind.contains("integer") || node_kind.contains("float") => TokenType::Number,
+        ("keyword", _) | ("constant", Some("builtin")) => TokenType::Keyword,
+        ("function", Some("builtin")) => TokenType::Builtin,
+        ("function", _) | ("attribute", _) => TokenType::FunctionName,
+        ("type", Some("builtin")) => TokenType::Builtin,
+        ("type", _) | ("constructor", _) => TokenType::ClassName,
+        // JSX elements, like HTML tags in the lexers
+        ("tag", _) => TokenType::Keyword,
+        ("string", Some("escape")) | ("escape", _) => TokenType::EscapeSequence,
+        ("string", _) => TokenType::StringLiteral,
+        ("comment", _) => TokenType::Comment,
+        ("number", _) => TokenType::Number,
+        ("operator", _) => TokenType::Operator,
+        ("punctuation", _) => TokenType::Punctuation,
+        // the rust query captures the name of a lifetime as a label
+        ("label", _) => TokenType::Lifetime,
+        _ => TokenType::Identifier, // variable, property, constant, ...
+    };
+    Some(token_type)
+}
+
+// every line followed by \n, plus the byte offset each line starts at
+// and the source length as the final offset
+#[cfg(feature = "tree-sitter")]
+fn join_lines(lines: &[String]) -> (String, Vec<usize>) {
+    let mut source = String::new();
+    let mut line_offsets: Vec<usize> = Vec::with_capacity(lines.len() + 1);
+
+    for line in lines {
+        line_offsets.push(source.len());
+        source.push_str(line);
+        source.push('\n');
+    }
+    line_offsets.push(source.len());
+    (source, line_offsets)
+}
+
+#[cfg(feature = "tree-sitter")]
+#[derive(Debug)]
+pub struct SyntaxTree {
+    language: String,
+    tree: Tree,
+    source: String,
+    line_offsets: Vec<usize>,
+}
+
+#[cfg(not(feature = "tree-sitter"))]
+#[derive(Debug)]
+pub enum SyntaxTree {}
+
+#[cfg(feature = "tree-sitter")]
+impl SyntaxTree {
+    // None when there is no grammar for the language
+    pub fn parse(language: &str, lines: &[String]) -> Option<SyntaxTree> {
+        let grammar = grammar(language)?;
+        let (source, line_offsets) = join_lines(lines);
+
+        let mut parser = Parser::new();
+        parser.set_language(&grammar.language).ok()?;
+        let tree = parser.parse(&source, None)?;
+
+        Some(SyntaxTree {
+            language: language.to_string(),
+            tree,
+            source,
+            line_offsets,
+        })
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_offsets.len() - 1
+    }
+
+    // Replace lines `start_line..end_line` with `new_lines` and reparse incrementally.
+    // Returns the range of lines whose highlighting may have changed.
+    pub fn edit(&mut self, start_line: usize, end_line: usize, new_lines: &[String]) -> Range<usize> {
+        let start_byte = self.line_offsets[start_line];
+        let old_end_byte = self.line_offsets[end_line];
+        let (new_text, new_offsets) = join_lines(new_lines);
+        let new_end_byte = start_byte + new_text.len();
+
+        self.source.replace_range(start_byte..old_end_byte, &new_text);
+
+        let mut line_offsets: Vec<usize> = self.line_offsets[..start_line].to_vec();
+        line_offsets.extend(new_offsets[..new_lines.len()].iter().map(|offset| offset + start_byte));
+        line_offsets.extend(self.line_offsets[end_line..].iter().map(|offset| offset - old_end_byte + new_end_byte));
+        self.line_offsets = line_offsets;
+
+        self.tree.edit(&InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+            start_position: Point::new(start_line, 0),
+            old_end_position: Point::new(end_line, 0),
+            new_end_position: Point::new(start_line + new_lines.len(), 0),
+        });
+
+        let mut changed_lines = start_line..start_line + new_lines.len();
+
+        let grammar = grammar(&self.language).expect("syntax tree was parsed with this grammar");
+        let mut parser = Parser::new();
+        let new_tree = parser.set_language(&grammar.language).ok()
+            .and_then(|_| parser.parse(&self.source, Some(&self.tree)));
+
+        if let Some(new_tree) = new_tree {
+            for range in self.tree.changed_ranges(&new_tree) {
+                changed_lines.start = changed_lines.start.min(range.start_point.row);
+                changed_lines.end = changed_lines.end.max(range.end_point.row + 1);
+            }
+            self.tree = new_tree;
+        }
+        changed_lines.end = changed_lines.end.min(self.line_count());
+        changed_lines
+    }
+
+    // per-line tokens for `lines`
+    pub fn tokenize_lines(&self, theme: &Theme, lines: Range<usize>) -> Vec<LineContent> {
+        let grammar = grammar(&self.language).expect("syntax tree was parsed with this grammar");
+        let byte_range = self.line_offsets[lines.start]..self.line_offsets[lines.end];
+
+        let mut highlights: Vec<(Range<usize>, usize, TokenType)> = Vec::new();
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(byte_range.clone());
+        let mut captures = cursor.captures(&grammar.query, self.tree.root_node(), self.source.as_bytes());
+
+        while let Some((query_match, capture_index)) = captures.next() {
+            let capture = query_match.captures[*capture_index];
+            let capture_name = grammar.query.capture_names()[capture.index as usize];
+
+            // the `'` of a lifetime is outside the captured name, paint the whole lifetime
+            let node = match capture.node.parent() {
+                Some(parent) if parent.kind() == "lifetime" => parent,
+                _ => capture.node,
+            };
+            if let Some(token_type) = capture_token_type(capture_name, node.kind()) {
+                highlights.push((node.byte_range(), query_match.pattern_index, token_type));
+            }
+        }
+
+        // wider nodes first so nested captures paint over them, for the same node
+        // the first pattern in the query wins like in tree-sitter's own highlighter
+        highlights.sort_by(|a, b| {
+            b.0.len().cmp(&a.0.len()).then(b.1.cmp(&a.1))
+        });
+
+        // index into highlights for every byte of the range
+        let mut painted: Vec<Option<usize>> = vec![None; byte_range.len()];
+        for (index, (range, _, _)) in highlights.iter().enumerate() {
+            let start = range.start.max(byte_range.start);
+            let end = range.end.min(byte_range.end);
+            if start < end {
+                painted[start - byte_range.start..end - byte_range.start].fill(Some(index));
+            }
+        }
+
+        lines
+            .map(|line_index| {
+                let line_start = self.line_offsets[line_index];
+                let line_end = self.line_offsets[line_index + 1] - 1; // without the \n
+                let line_painted = &painted[line_start - byte_range.start..line_end - byte_range.start];
+
+                let tokens = self.line_tokens(
+                    theme,
+                    &self.source[line_start..line_end],
+                    line_painted,
+                    &highlights
+                );
+                LineContent {
+                    tokens: split_sub_tokens(theme, &self.language, tokens, &LineState::Normal),
+                    state: LineState::Normal,
+                }
+            })
+            .collect()
+    }
+
+    // split a line into tokens wherever the capture changes,
+    // text no capture covers is an identifier or single punctuation character
+    fn line_tokens(
+        &self, 
+        theme: &Theme, 
+        line: &str, 
+        painted: &[Option<usize>], 
+        highlights: &[(Range<usize>, usize, TokenType)]
+    ) -> Vec<Token> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut current: Option<(TokenType, Option<usize>, usize)> = None; // type, capture, start offset
+
+        let push_token = |tokens: &mut Vec<Token>, token_type: TokenType, start: usize, end: usize| {
+            tokens.push(create_token(theme, &self.language, token_type, &line[start..end]));
+        };
+
+        for (offset, ch) in line.char_indices() {
+            let capture = painted[offset];
+            let token_type = match capture {
+                Some(index) => highlights[index].2,
+                None if ch.is_whitespace() => TokenType::Whitespace,
+                None if ch.is_alphanumeric() || ch == '_' => TokenType::Identifier,
+                None => TokenType::Punctuation,
+            };
+
+            if let Some((current_type, current_capture, start)) = current {
+                let single_char = current_type == TokenType::Punctuation && current_capture.is_none();
+                if current_type == token_type && current_capture == capture && !single_char {
+                    continue;
+                }
+                push_token(&mut tokens, current_type, start, offset);
+            }
+            current = Some((token_type, capture, offset));
+        }
+        if let Some((current_type, _, start)) = current {
+            push_token(&mut tokens, current_type, start, line.len());
+        }
+        tokens
+    }
+}
+
+#[cfg(not(feature = "tree-sitter"))]
+impl SyntaxTree {
+    pub fn parse(_language: &str, _lines: &[String]) -> Option<SyntaxTree> {
+        None
+    }
+
+    pub fn line_count(&self) -> usize {
+        match *self {}
+    }
+
+    pub fn edit(&mut self, _start_line: usize, _end_line: usize, _new_lines: &[String]) -> Range<usize> {
+        match *self {}
+    }
+
+    pub fn tokenize_lines(&self, _theme: &Theme, _lines: Range<usize>) -> Vec<LineContent> {
+        match *self {}
+    }
+}
+
+#[cfg(all(test, feature = "tree-sitter"))]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+
+    fn lines(source: &str) -> Vec<String> {
+        source.lines().map(|line| line.to_string()).collect()
+    }
+
+    fn token_type_of(line_content: &LineContent, value: &str) -> TokenType {
+        line_content.tokens.iter().find(|token| token.value == value).unwrap().token_type
+    }
+
+    #[test]
+    fn test_tokenize_lines_rust() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let source = lines("struct Point<T> { x: T }\nfn main() {\n    let p = Point { x: 1 };\n}");
+        let syntax_tree = SyntaxTree::parse("rust", &source).unwrap();
+
+        let line_contents = syntax_tree.tokenize_lines(&theme, 0..source.len());
+        assert_eq!(line_contents.len(), 4);
+        assert_eq!(token_type_of(&line_contents[0], "struct"), TokenType::Keyword);
+        assert_eq!(token_type_of(&line_contents[0], "T"), TokenType::ClassName);
+        assert_eq!(token_type_of(&line_contents[1], "main"), TokenType::FunctionName);
+        assert_eq!(token_type_of(&line_contents[2], "1"), TokenType::Number);
+
+        // token values put back together give the line again
+        for (line_content, line) in line_contents.iter().zip(&source) {
+            let joined: String = line_content.tokens.iter().map(|token| token.value.as_str()).collect();
+            assert_eq!(&joined, line);
+        }
+    }
+
+    #[test]
+    fn test_tokenize_lines_rust_lifetimes() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let source = lines("fn first<'a>(s: &'a str) -> &'a str { s } // TODO \"\\n\"");
+        let syntax_tree = SyntaxTree::parse("rust", &source).unwrap();
+
+        let line_contents = syntax_tree.tokenize_lines(&theme, 0..source.len());
+        assert_eq!(token_type_of(&line_contents[0], "'a"), TokenType::Lifetime);
+        // comments from the tree are split like the lexers' ones
+        assert_eq!(token_type_of(&line_contents[0], "TODO"), TokenType::Annotation);
+    }
+
+    #[test]
+    fn test_tokenize_lines_tsx() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let source = lines("const view = (n: number) => <Counter count={n}><span>{n}</span></Counter>;");
+        let syntax_tree = SyntaxTree::parse("tsx", &source).unwrap();
+
+        let line_contents = syntax_tree.tokenize_lines(&theme, 0..source.len());
+        assert_eq!(token_type_of(&line_contents[0], "number"), TokenType::Builtin);
+        assert_eq!(token_type_of(&line_contents[0], "span"), TokenType::Keyword);
+    }
+
+    #[test]
+    fn test_edit_reparses_incrementally() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let source = lines("x = 1\ny = 2\n# \"\"\"");
+        let mut syntax_tree = SyntaxTree::parse("python", &source).unwrap();
+
+        // opening a triple-quoted string turns the following lines into a string
+        let changed = syntax_tree.edit(0, 1, &["x = \"\"\"".to_string()]);
+        assert_eq!(changed, 0..3);
+
+        let line_contents = syntax_tree.tokenize_lines(&theme, changed);
+        assert_eq!(line_contents[1].tokens[0].token_type, TokenType::StringLiteral);
+        assert_eq!(line_contents[2].tokens[0].token_type, TokenType::StringLiteral);
+    }
+
+    #[test]
+    fn test_parse_without_grammar() {
+        assert!(SyntaxTree::parse("cobol", &lines("IDENTIFICATION DIVISION.")).is_none());
+    }
+}
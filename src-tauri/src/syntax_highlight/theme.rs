@@ -0,0 +1,133 @@
+// Colors and font styles of the syntax_highlight settings resolved once, so tokenizers
+// look them up by (language, TokenType) instead of searching the settings per token.
+// Equal colors share one allocation, every token of a color holds the same Arc<str>.
+// Rebuilt whenever the settings change.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::settings::{Settings, SyntaxHighlight, TokenStyle, BracketSetting};
+use crate::syntax_highlight::{TokenType, TokenPalette};
+use crate::syntax_highlight::languages::DEFAULT_LANGUAGE;
+
+#[derive(Debug, Clone)]
+struct LanguageTheme {
+    colors: [Arc<str>; TokenType::ALL.len()],
+    // CSS declarations of the token styles, None for token types without one
+    styles: [Option<Arc<str>>; TokenType::ALL.len()],
+    token_styles: BTreeMap<TokenType, TokenStyle>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    languages: HashMap<String, LanguageTheme>,
+    // colors of languages without settings of their own, python's like before
+    fallback: LanguageTheme,
+    // colors of brackets by depth, empty unless rainbow brackets are on
+    bracket_colors: Vec<Arc<str>>,
+}
+
+impl Theme {
+    pub fn new(settings_config: &Settings) -> Theme {
+        Theme::from_syntax_highlight(&settings_config.syntax_highlight, &settings_config.brackets)
+    }
+
+    pub fn from_syntax_highlight(syntax_highlight: &SyntaxHighlight, bracket_setting: &BracketSetting) -> Theme {
+        let mut interned: HashMap<String, Arc<str>> = HashMap::new();
+        let mut intern = |value: &str| -> Arc<str> {
+            Arc::clone(interned.entry(value.to_string()).or_insert_with(|| Arc::from(value)))
+        };
+
+        let mut languages = HashMap::new();
+        for language_syntax in &syntax_highlight.languages {
+            let colors = TokenType::ALL.map(|token_type| intern(language_syntax.color(token_type)));
+            let styles = TokenType::ALL.map(|token_type| {
+                let css = language_syntax.token_styles.get(&token_type)?.css();
+                (!css.is_empty()).then(|| intern(&css))
+            });
+            let language_theme = LanguageTheme { colors, styles, token_styles: language_syntax.token_styles.clone() };
+            // the first entry of a language wins, as with the lookup this replaces
+            languages.entry(language_syntax.language.clone()).or_insert(language_theme);
+        }
+        // plain text takes the editor's text color. settings.json files written before it
+        // was left out of the defaults have colors for it that were never used
+        let plain_text = LanguageTheme {
+            colors: TokenType::ALL.map(|_| intern("inherit")),
+            styles: TokenType::ALL.map(|_| None),
+            token_styles: BTreeMap::new(),
+        };
+        languages.insert(DEFAULT_LANGUAGE.to_string(), plain_text.clone());
+        let fallback = match languages.get("python") {
+            Some(language_theme) => language_theme.clone(),
+            None => plain_text,
+        };
+
+        let bracket_colors = match bracket_setting.rainbow {
+            true => bracket_setting.colors.iter().map(|color| intern(color)).collect(),
+            false => Vec::new(),
+        };
+
+        Theme { languages, fallback, bracket_colors }
+    }
+
+    fn language_theme(&self, language: &str) -> &LanguageTheme {
+        self.languages.get(language).unwrap_or(&self.fallback)
+    }
+
+    pub fn color(&self, language: &str, token_type: TokenType) -> &Arc<str> {
+        &self.language_theme(language).colors[token_type as usize]
+    }
+
+    pub fn style(&self, language: &str, token_type: TokenType) -> Option<&Arc<str>> {
+        self.language_theme(language).styles[token_type as usize].as_ref()
+    }
+
+    pub fn palette(&self, language: &str) -> TokenPalette {
+        TokenType::ALL.iter()
+            .map(|&token_type| (token_type, self.color(language, token_type).to_string()))
+            .collect()
+    }
+
+    pub fn bracket_colors(&self) -> &[Arc<str>] {
+        &self.bracket_colors
+    }
+
+    pub fn token_styles(&self, language: &str) -> BTreeMap<TokenType, TokenStyle> {
+        self.language_theme(language).token_styles.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cmd_utils::tokenize;
+    use crate::syntax_highlight::{TokenSpan, LineState};
+
+    #[test]
+    fn test_token_palette() {
+        let mut settings_config = Settings::init_default_values();
+        let palette = Theme::new(&settings_config).palette("rust");
+        assert_eq!(palette.len(), TokenType::ALL.len());
+        assert_eq!(palette[&TokenType::Identifier], "#26ffd7");
+
+        // a new theme only needs a new palette, the spans stay the same
+        let rust = settings_config.syntax_highlight.languages.iter_mut().find(|lang| lang.language == "rust").unwrap();
+        rust.keyword_color = "#123456".to_string();
+        assert_eq!(Theme::new(&settings_config).palette("rust")[&TokenType::Keyword], "#123456");
+
+        let serialized = serde_json::to_string(&TokenSpan(TokenType::Keyword, 0, 2)).unwrap();
+        assert_eq!(serialized, "[\"Keyword\",0,2]");
+    }
+
+    #[test]
+    fn test_theme_interns_colors() {
+        let theme = Theme::new(&Settings::init_default_values());
+        let tokens = tokenize("rust", &theme, "a b", &LineState::Normal).tokens;
+
+        // tokens of one color share the theme's string
+        assert!(Arc::ptr_eq(&tokens[0].color, &tokens[2].color));
+        assert!(Arc::ptr_eq(&tokens[0].color, theme.color("python", TokenType::Identifier)));
+        // languages without colors in the settings get python's
+        assert_eq!(theme.color("cobol", TokenType::Keyword), theme.color("python", TokenType::Keyword));
+    }
+}
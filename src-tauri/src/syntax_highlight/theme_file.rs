@@ -0,0 +1,620 @@
+// Color schemes in $HOME/BaCE/themes, one *.json file per theme, picked by the `theme`
+// key of settings.json. A theme colors a token type in every language at once and
+// can still give single languages their own colors, for example
+//
+// {
+//   "name": "Monokai",
+//   "colors": { "Keyword": "#f92672", "StringLiteral": "#e6db74", "Comment": "#75715e" },
+//   "languages": { "rust": { "Lifetime": "#fd971f" } }
+// }
+//
+// Token types a theme leaves out keep their syntax_highlight colors.
+// VS Code color themes (.json) and TextMate themes (.tmTheme) are converted to this
+// format by import_theme_file.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::ThemeError;
+use crate::settings::{Settings, SyntaxHighlight, TokenStyle, is_valid_color};
+use crate::syntax_highlight::TokenType;
+use crate::syntax_highlight::theme::Theme;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeFile {
+    pub name: String,
+    #[serde(default)]
+    pub colors: BTreeMap<TokenType, String>,
+    #[serde(default)]
+    pub languages: BTreeMap<String, BTreeMap<TokenType, String>>,
+    // font styles of token types in every language, `{ "Comment": { "italic": true } }`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub styles: BTreeMap<TokenType, TokenStyle>,
+}
+
+// TextMate scopes whose color a token type takes, most specific first.
+// The first one any rule of a theme selects wins
+const TOKEN_TYPE_SCOPES: [(TokenType, &[&str]); 17] = [
+    (TokenType::Keyword, &["keyword.control", "storage.type", "keyword"]),
+    (TokenType::Operator, &["keyword.operator"]),
+    (TokenType::Identifier, &["variable.other", "variable"]),
+    (TokenType::Number, &["constant.numeric"]),
+    (TokenType::Punctuation, &["punctuation.separator", "punctuation"]),
+    (TokenType::StringLiteral, &["string.quoted", "string"]),
+    (TokenType::Comment, &["comment.line", "comment"]),
+    (TokenType::ClassName, &["entity.name.type.class", "entity.name.class", "support.class", "entity.name.type"]),
+    (TokenType::FunctionName, &["entity.name.function", "support.function"]),
+    (TokenType::Preprocessor, &["meta.preprocessor", "keyword.control.directive"]),
+    (TokenType::Lifetime, &["storage.modifier.lifetime", "entity.name.type.lifetime"]),
+    (TokenType::Builtin, &["support.function.builtin", "support.type.primitive", "support.function"]),
+    (TokenType::Unknown, &["invalid"]),
+    (TokenType::Annotation, &["keyword.other.todo", "comment.keyword"]),
+    (TokenType::Url, &["markup.underline.link", "markup.link"]),
+    (TokenType::EscapeSequence, &["constant.character.escape"]),
+    (TokenType::Placeholder, &["constant.other.placeholder", "constant.character.format.placeholder"]),
+];
+
+impl ThemeFile {
+    pub fn apply(&self, syntax_highlight: &mut SyntaxHighlight) {
+        for language_syntax in syntax_highlight.languages.iter_mut() {
+            let language_colors = self.languages.get(&language_syntax.language);
+
+            for (token_type, color) in self.colors.iter().chain(language_colors.into_iter().flatten()) {
+                language_syntax.set_color(*token_type, color);
+            }
+            for (token_type, token_style) in &self.styles {
+                language_syntax.token_styles.insert(*token_type, token_style.clone());
+            }
+        }
+    }
+}
+
+pub fn get_themes_dir_path() -> PathBuf {
+    let mut dir_path = PathBuf::from(env::var("HOME").unwrap());
+    dir_path.push("BaCE/themes");
+    dir_path
+}
+
+pub fn read_theme_file(file_path: &Path) -> Result<ThemeFile, ThemeError> {
+    let file_name = file_path.to_string_lossy().into_owned();
+
+    let data = fs::read_to_string(file_path)
+        .map_err(|_| ThemeError::ReadThemeFileFail(file_name.clone()))?;
+
+    let theme_file: ThemeFile = serde_json::from_str(&data)
+        .map_err(|e| ThemeError::InvalidTheme(file_name.clone(), format!("{e}")))?;
+
+    // colors and backgrounds end up in a style attribute like the ones in settings.json
+    let invalid_color = theme_file.colors.values()
+        .chain(theme_file.languages.values().flat_map(|language_colors| language_colors.values()))
+        .map(|color| color.as_str())
+        .chain(theme_file.styles.values().filter_map(|token_style| token_style.background.as_deref()))
+        .find(|color| !is_valid_color(color));
+    if let Some(color) = invalid_color {
+        return Err(ThemeError::InvalidTheme(file_name, format!("'{color}' is not a valid color")));
+    }
+    Ok(theme_file)
+}
+
+// Names of the themes in `dir_path`, the file names without .json
+pub fn list_themes(dir_path: &Path) -> Result<Vec<String>, ThemeError> {
+    // the themes folder is optional
+    if !dir_path.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(dir_path).map_err(|_| ThemeError::ReadThemesDirectoryFail)?;
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+// Theme for the tokenizers, syntax_highlight with the colors of the selected theme file on top
+pub fn load_theme(settings_config: &Settings, dir_path: &Path) -> Result<Theme, ThemeError> {
+    let Some(ref name) = settings_config.theme else {
+        return Ok(Theme::new(settings_config));
+    };
+    let file_path = dir_path.join(format!("{name}.json"));
+    if !file_path.is_file() {
+        return Err(ThemeError::ThemeNotFound(name.clone()));
+    }
+    let theme_file = read_theme_file(&file_path)?;
+
+    let mut syntax_highlight = settings_config.syntax_highlight.clone();
+    theme_file.apply(&mut syntax_highlight);
+    Ok(Theme::from_syntax_highlight(&syntax_highlight, &settings_config.brackets))
+}
+
+// Convert a VS Code or TextMate theme and save it in `dir_path`.
+// Returns the name the converted theme is selected by.
+pub fn import_theme_file(source_path: &Path, dir_path: &Path) -> Result<String, ThemeError> {
+    let file_name = source_path.to_string_lossy().into_owned();
+
+    let data = fs::read_to_string(source_path)
+        .map_err(|_| ThemeError::ReadThemeFileFail(file_name.clone()))?;
+
+    let is_textmate = source_path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tmTheme"));
+    let theme_file = if is_textmate {
+        parse_plist(&data).map(|plist| convert_textmate_theme(&plist))
+    } else {
+        parse_jsonc(&data).map(|json| convert_vscode_theme(&json))
+    };
+    let mut theme_file = theme_file.map_err(|e| ThemeError::InvalidTheme(file_name.clone(), e))?;
+
+    if theme_file.colors.is_empty() {
+        return Err(ThemeError::InvalidTheme(file_name, "no token colors".to_string()));
+    }
+    let source_name = source_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    if theme_file.name.trim().is_empty() {
+        theme_file.name = source_name.to_string();
+    }
+    let mut name = theme_file_name(&theme_file.name);
+    if name.is_empty() {
+        name = theme_file_name(source_name);
+    }
+
+    let file_path = dir_path.join(format!("{name}.json"));
+    let write_fail = || ThemeError::WriteThemeFileFail(file_path.to_string_lossy().into_owned());
+
+    fs::create_dir_all(dir_path).map_err(|_| write_fail())?;
+    let json_data = serde_json::to_string_pretty(&theme_file).map_err(|_| write_fail())?;
+    fs::write(&file_path, json_data).map_err(|_| write_fail())?;
+    Ok(name)
+}
+
+// "Monokai Pro (Filter)" -> "monokai-pro-filter"
+fn theme_file_name(name: &str) -> String {
+    let name: String = name.chars()
+        .map(|ch| if ch.is_alphanumeric() { ch.to_ascii_lowercase() } else { '-' })
+        .collect();
+    name.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+fn convert_vscode_theme(json: &Value) -> ThemeFile {
+    let name = json["name"].as_str().unwrap_or_default();
+    let foreground = json["colors"]["editor.foreground"].as_str();
+    let rules = json["tokenColors"].as_array().map(Vec::as_slice).unwrap_or_default();
+    convert_textmate_rules(name, rules, foreground)
+}
+
+// a .tmTheme's rules are in `settings`, the one without a scope holds the editor colors
+fn convert_textmate_theme(plist: &Value) -> ThemeFile {
+    let name = plist["name"].as_str().unwrap_or_default();
+    let rules = plist["settings"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let foreground = rules.iter()
+        .find(|rule| rule.get("scope").is_none())
+        .and_then(|rule| rule["settings"]["foreground"].as_str());
+    convert_textmate_rules(name, rules, foreground)
+}
+
+// `scope` of a rule is "a, b" or ["a", "b"], of a descendant selector like
+// "source.python string" only the last scope counts and exclusions after " - " are ignored
+fn rule_selectors(rule: &Value) -> Vec<&str> {
+    let scopes: Vec<&str> = match &rule["scope"] {
+        Value::String(scope) => scope.split(',').collect(),
+        Value::Array(scopes) => scopes.iter().filter_map(Value::as_str).flat_map(|scope| scope.split(',')).collect(),
+        _ => Vec::new(),
+    };
+    scopes.iter()
+        .filter_map(|scope| scope.split(" - ").next()?.split_whitespace().last())
+        .collect()
+}
+
+// `keyword` selects `keyword.operator`, `key` doesn't
+fn selector_matches(selector: &str, scope: &str) -> bool {
+    scope == selector || (scope.starts_with(selector) && scope[selector.len()..].starts_with('.'))
+}
+
+// `setting` of the rule with the most specific selector of `scope`, a later rule wins a tie
+fn rule_setting<'a>(rules: &'a [Value], scope: &str, setting: &str) -> Option<&'a str> {
+    let mut best: Option<(usize, &str)> = None;
+
+    for rule in rules {
+        let Some(value) = rule["settings"][setting].as_str() else {
+            continue;
+        };
+        for selector in rule_selectors(rule) {
+            if selector_matches(selector, scope) && best.map_or(true, |(length, _)| selector.len() >= length) {
+                best = Some((selector.len(), value));
+            }
+        }
+    }
+    best.map(|(_, value)| value)
+}
+
+// "italic bold" of a rule's fontStyle, the theme's own backgrounds don't carry over
+fn font_style(font_style: &str) -> TokenStyle {
+    let styles: Vec<&str> = font_style.split_whitespace().collect();
+    TokenStyle {
+        bold: styles.contains(&"bold"),
+        italic: styles.contains(&"italic"),
+        underline: styles.contains(&"underline"),
+        background: None,
+    }
+}
+
+fn convert_textmate_rules(name: &str, rules: &[Value], foreground: Option<&str>) -> ThemeFile {
+    let mut colors = BTreeMap::new();
+    let mut styles = BTreeMap::new();
+
+    if let Some(foreground) = foreground.filter(|color| is_valid_color(color)) {
+        colors.insert(TokenType::Identifier, foreground.to_string());
+        colors.insert(TokenType::Whitespace, foreground.to_string());
+        colors.insert(TokenType::Operator, foreground.to_string());
+        colors.insert(TokenType::Punctuation, foreground.to_string());
+        colors.insert(TokenType::Unknown, foreground.to_string());
+    }
+
+    for (token_type, scopes) in TOKEN_TYPE_SCOPES {
+        let color = scopes.iter().find_map(|scope| rule_setting(rules, scope, "foreground"));
+        if let Some(color) = color.filter(|color| is_valid_color(color)) {
+            colors.insert(token_type, color.to_string());
+        }
+        let token_style = scopes.iter().find_map(|scope| rule_setting(rules, scope, "fontStyle")).map(font_style);
+        if let Some(token_style) = token_style.filter(|token_style| *token_style != TokenStyle::default()) {
+            styles.insert(token_type, token_style);
+        }
+    }
+    ThemeFile { name: name.to_string(), colors, languages: BTreeMap::new(), styles }
+}
+
+// length of the JSON string starting at `pos`, quotes included
+fn json_string_length(chars: &[char], pos: usize) -> usize {
+    let mut end = pos + 1;
+    while end < chars.len() && chars[end] != '"' {
+        end += if chars[end] == '\\' { 2 } else { 1 };
+    }
+    (end + 1).min(chars.len()) - pos
+}
+
+// VS Code themes are JSON with comments and trailing commas
+fn parse_jsonc(data: &str) -> Result<Value, String> {
+    let chars: Vec<char> = data.chars().collect();
+    let mut without_comments: Vec<char> = Vec::with_capacity(chars.len());
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        if chars[pos] == '"' {
+            let length = json_string_length(&chars, pos);
+            without_comments.extend(&chars[pos..pos + length]);
+            pos += length;
+        }
+        else if chars[pos..].starts_with(&['/', '/']) {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+        }
+        else if chars[pos..].starts_with(&['/', '*']) {
+            pos += 2;
+            while pos < chars.len() && !chars[pos..].starts_with(&['*', '/']) {
+                pos += 1;
+            }
+            pos = (pos + 2).min(chars.len());
+        }
+        else {
+            without_comments.push(chars[pos]);
+            pos += 1;
+        }
+    }
+
+    let chars = without_comments;
+    let mut json = String::with_capacity(chars.len());
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        if chars[pos] == '"' {
+            let length = json_string_length(&chars, pos);
+            json.extend(&chars[pos..pos + length]);
+            pos += length;
+            continue;
+        }
+        let next_char = chars[pos + 1..].iter().find(|ch| !ch.is_whitespace());
+        if chars[pos] != ',' || !matches!(next_char, Some('}') | Some(']')) {
+            json.push(chars[pos]);
+        }
+        pos += 1;
+    }
+    serde_json::from_str(&json).map_err(|e| format!("{e}"))
+}
+
+enum XmlPiece<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Empty(&'a str),
+    Text(&'a str),
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// .tmTheme files are XML property lists, read into the JSON shape of a VS Code theme
+fn parse_plist(data: &str) -> Result<Value, String> {
+    let tag = Regex::new(r"(?s)<!--.*?-->|<\?.*?\?>|<!DOCTYPE[^>]*>|<(/?)([A-Za-z]+)[^>]*?(/?)>").unwrap();
+    let mut pieces: Vec<XmlPiece> = Vec::new();
+    let mut last_end = 0;
+
+    for captures in tag.captures_iter(data) {
+        let whole = captures.get(0).unwrap();
+        if whole.start() > last_end {
+            pieces.push(XmlPiece::Text(&data[last_end..whole.start()]));
+        }
+        last_end = whole.end();
+
+        if let Some(name) = captures.get(2) {
+            if !captures[1].is_empty() {
+                pieces.push(XmlPiece::Close(name.as_str()));
+            }
+            else if !captures[3].is_empty() {
+                pieces.push(XmlPiece::Empty(name.as_str()));
+            }
+            else {
+                pieces.push(XmlPiece::Open(name.as_str()));
+            }
+        }
+    }
+
+    let mut pos = 0;
+    skip_text(&pieces, &mut pos);
+    if matches!(pieces.get(pos), Some(XmlPiece::Open("plist"))) {
+        pos += 1;
+    }
+    parse_plist_value(&pieces, &mut pos)
+}
+
+fn skip_text(pieces: &[XmlPiece], pos: &mut usize) {
+    while let Some(XmlPiece::Text(_)) = pieces.get(*pos) {
+        *pos += 1;
+    }
+}
+
+// text up to the closing tag of `name`
+fn plist_text(pieces: &[XmlPiece], pos: &mut usize, name: &str) -> Result<String, String> {
+    let mut text = String::new();
+    loop {
+        match pieces.get(*pos) {
+            Some(XmlPiece::Text(value)) => text.push_str(value),
+            Some(XmlPiece::Close(close)) if *close == name => {
+                *pos += 1;
+                return Ok(unescape_xml(&text));
+            },
+            _ => return Err(format!("unclosed <{name}>")),
+        }
+        *pos += 1;
+    }
+}
+
+fn parse_plist_value(pieces: &[XmlPiece], pos: &mut usize) -> Result<Value, String> {
+    skip_text(pieces, pos);
+    let Some(piece) = pieces.get(*pos) else {
+        return Err("unexpected end of file".to_string());
+    };
+    *pos += 1;
+
+    match piece {
+        XmlPiece::Open("dict") => {
+            let mut dict = Map::new();
+            loop {
+                skip_text(pieces, pos);
+                match pieces.get(*pos) {
+                    Some(XmlPiece::Close("dict")) => {
+                        *pos += 1;
+                        return Ok(Value::Object(dict));
+                    },
+                    Some(XmlPiece::Open("key")) => {
+                        *pos += 1;
+                        let key = plist_text(pieces, pos, "key")?;
+                        dict.insert(key, parse_plist_value(pieces, pos)?);
+                    },
+                    _ => return Err("expected <key> in <dict>".to_string()),
+                }
+            }
+        },
+        XmlPiece::Open("array") => {
+            let mut array = Vec::new();
+            loop {
+                skip_text(pieces, pos);
+                if let Some(XmlPiece::Close("array")) = pieces.get(*pos) {
+                    *pos += 1;
+                    return Ok(Value::Array(array));
+                }
+                array.push(parse_plist_value(pieces, pos)?);
+            }
+        },
+        XmlPiece::Empty("true") => Ok(Value::Bool(true)),
+        XmlPiece::Empty("false") => Ok(Value::Bool(false)),
+        XmlPiece::Empty("dict") => Ok(Value::Object(Map::new())),
+        XmlPiece::Empty("array") => Ok(Value::Array(Vec::new())),
+        XmlPiece::Empty(_) => Ok(Value::String(String::new())),
+        // strings, numbers and dates are all kept as text
+        XmlPiece::Open(name) => plist_text(pieces, pos, name).map(Value::String),
+        XmlPiece::Close(name) => Err(format!("unexpected </{name}>")),
+        XmlPiece::Text(_) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const VSCODE_THEME: &str = r##"{
+        // comments and trailing commas are allowed in VS Code themes
+        "name": "Night Owl (No Italics)",
+        "type": "dark",
+        "colors": { "editor.foreground": "#d6deeb", "editor.background": "#011627" },
+        "tokenColors": [
+            { "settings": { "foreground": "#d6deeb" } },
+            { "scope": "comment", "settings": { "foreground": "#637777", "fontStyle": "italic" } },
+            { "scope": ["string", "string.quoted.double"], "settings": { "foreground": "#ecc48d" } },
+            { "scope": "keyword", "settings": { "foreground": "#c792ea", "fontStyle": "" } },
+            { "scope": "keyword.operator, keyword.operator.arithmetic", "settings": { "foreground": "#7fdbca" } },
+            { "scope": "source.rust storage.modifier", "settings": { "foreground": "#ff5874" } },
+            { "scope": "entity.name.function", "settings": { "foreground": "#82aaff" } },
+            /* later rules win ties */
+            { "scope": "comment", "settings": { "foreground": "#637778" } },
+        ],
+    }"##;
+
+    const TEXTMATE_THEME: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Monokai &amp; Co</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>background</key>
+                <string>#272822</string>
+                <key>foreground</key>
+                <string>#F8F8F2</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>name</key>
+            <string>Comment</string>
+            <key>scope</key>
+            <string>comment</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#75715E</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>storage.type, keyword</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#F92672</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>constant.numeric</string>
+            <key>settings</key>
+            <dict>
+                <key>fontStyle</key>
+                <string></string>
+                <key>foreground</key>
+                <string>#AE81FF</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>support.class, support.type</string>
+            <key>settings</key>
+            <dict/>
+        </dict>
+    </array>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn test_load_theme() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("solar.json"), r##"{
+            "name": "Solar",
+            "colors": { "Keyword": "#859900", "Comment": "#586e75" },
+            "languages": { "rust": { "Keyword": "#cb4b16" } },
+            "styles": { "Comment": { "italic": true }, "Keyword": { "bold": true, "underline": true } }
+        }"##).unwrap();
+
+        let mut settings_config = Settings::init_default_values();
+        settings_config.theme = Some("solar".to_string());
+        let theme = load_theme(&settings_config, dir.path()).unwrap();
+
+        assert_eq!(&**theme.color("python", TokenType::Keyword), "#859900");
+        assert_eq!(&**theme.color("rust", TokenType::Keyword), "#cb4b16");
+        assert_eq!(&**theme.color("rust", TokenType::Comment), "#586e75");
+        // colors the theme leaves out come from syntax_highlight
+        assert_eq!(&**theme.color("rust", TokenType::Identifier), "#26ffd7");
+        assert_eq!(theme.style("go", TokenType::Comment).map(|style| &**style), Some("font-style: italic"));
+        assert_eq!(theme.style("go", TokenType::Keyword).map(|style| &**style), Some("font-weight: bold; text-decoration: underline"));
+        assert_eq!(theme.style("go", TokenType::Number), None);
+        assert_eq!(list_themes(dir.path()).unwrap(), vec!["solar".to_string()]);
+
+        settings_config.theme = Some("missing".to_string());
+        assert_eq!(load_theme(&settings_config, dir.path()).unwrap_err(), ThemeError::ThemeNotFound("missing".to_string()));
+
+        // a color that isn't one could end the style attribute it is written into
+        fs::write(dir.path().join("broken.json"), r##"{
+            "name": "Broken",
+            "languages": { "rust": { "Keyword": "red\"><img src=x onerror=alert(1)>" } }
+        }"##).unwrap();
+        settings_config.theme = Some("broken".to_string());
+        assert!(matches!(load_theme(&settings_config, dir.path()), Err(ThemeError::InvalidTheme(_, _))));
+        fs::write(dir.path().join("broken.json"), r##"{ "name": "Broken", "colors": { "Comment": "red; x: y" } }"##).unwrap();
+        assert!(matches!(load_theme(&settings_config, dir.path()), Err(ThemeError::InvalidTheme(_, _))));
+        assert!(list_themes(&dir.path().join("nothing here")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_vscode_theme() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("night-owl-color-theme.json");
+        fs::write(&source_path, VSCODE_THEME).unwrap();
+
+        let themes_dir = dir.path().join("themes");
+        let name = import_theme_file(&source_path, &themes_dir).unwrap();
+        assert_eq!(name, "night-owl-no-italics");
+
+        let theme_file = read_theme_file(&themes_dir.join("night-owl-no-italics.json")).unwrap();
+        assert_eq!(theme_file.name, "Night Owl (No Italics)");
+        assert_eq!(theme_file.colors[&TokenType::Comment], "#637778");
+        assert_eq!(theme_file.colors[&TokenType::StringLiteral], "#ecc48d");
+        assert_eq!(theme_file.colors[&TokenType::Keyword], "#c792ea");
+        // keyword.operator is more specific than keyword
+        assert_eq!(theme_file.colors[&TokenType::Operator], "#7fdbca");
+        assert_eq!(theme_file.colors[&TokenType::Lifetime], "#ff5874");
+        assert_eq!(theme_file.colors[&TokenType::FunctionName], "#82aaff");
+        assert_eq!(theme_file.colors[&TokenType::Identifier], "#d6deeb");
+        assert!(!theme_file.colors.contains_key(&TokenType::Number));
+        assert_eq!(theme_file.styles[&TokenType::Comment], TokenStyle { italic: true, ..TokenStyle::default() });
+        assert_eq!(theme_file.styles.len(), 1);
+    }
+
+    #[test]
+    fn test_import_textmate_theme() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("Monokai.tmTheme");
+        fs::write(&source_path, TEXTMATE_THEME).unwrap();
+
+        let name = import_theme_file(&source_path, dir.path()).unwrap();
+        assert_eq!(name, "monokai-co");
+
+        let theme_file = read_theme_file(&dir.path().join("monokai-co.json")).unwrap();
+        assert_eq!(theme_file.name, "Monokai & Co");
+        assert_eq!(theme_file.colors[&TokenType::Comment], "#75715E");
+        assert_eq!(theme_file.colors[&TokenType::Keyword], "#F92672");
+        assert_eq!(theme_file.colors[&TokenType::Number], "#AE81FF");
+        assert_eq!(theme_file.colors[&TokenType::Whitespace], "#F8F8F2");
+        // a rule without a foreground doesn't color anything
+        assert!(!theme_file.colors.contains_key(&TokenType::ClassName));
+
+        let broken_path = dir.path().join("broken.tmTheme");
+        fs::write(&broken_path, "<plist><dict><key>name</key>").unwrap();
+        assert!(matches!(import_theme_file(&broken_path, dir.path()), Err(ThemeError::InvalidTheme(_, _))));
+    }
+}
@@ -23,6 +23,21 @@ export interface LanguageSyntaxHighlight {
   commentColor: string;
   classNameColor: string;
   functionNameColor: string;
+  preprocessorColor: string;
+  lifetimeColor: string;
+  builtinColor: string;
+  annotationColor: string; // TODO, FIXME, HACK and XXX in comments
+  urlColor: string;
+  escapeSequenceColor: string;
+  placeholderColor: string; // format placeholders in strings, {} and %s
+  tokenStyles?: Record<string, TokenStyle>; // token type to font style
+}
+
+export interface TokenStyle {
+  bold: boolean;
+  italic: boolean;
+  underline: boolean;
+  background: string | null;
 }
 
 export interface SyntaxHighlight {
@@ -48,6 +63,11 @@ interface TabBarSetting {
   color: string;
 }
 
+interface BracketSetting {
+  rainbow: boolean;
+  colors: Array<string>; // one per nesting level, repeating
+}
+
 interface EditorSetting {
   fontSize: string;
   fontWeight: string;
@@ -59,17 +79,77 @@ export interface Settings {
   tabBar: TabBarSetting;
   editor: EditorSetting;
   syntaxHighlight: SyntaxHighlight;
+  fileAssociations: Record<string, string>; // extension or glob to language name
+  theme: string | null; // theme file name in ~/BaCE/themes
+  preset: string | null; // built-in preset the colors came from: dark, light, solarized or high-contrast
+  brackets: BracketSetting;
 }
 
 export interface Token {
   value: string;
   color: string;
+  style?: string; // CSS declarations of the token style
 }
 
 export interface LineContent {
   tokens: Array<Token>;
 }
 
+// [token type, start, length], offsets are UTF-16 code units into the line
+export type TokenSpan = [string, number, number];
+
+// [start of the bracket's TokenSpan, depth], the depth indexes brackets.colors, repeating
+export type BracketDepth = [number, number];
+
+// columns are UTF-16 code units into the line, like TokenSpan
+export interface BracketPosition {
+  line: number;
+  column: number;
+}
+
+export interface Bracket {
+  position: BracketPosition;
+  bracket: string;
+  depth: number;
+  partner: BracketPosition | null;
+}
+
+// start_line stays visible when folded, the lines after it up to end_line are hidden
+export interface FoldRange {
+  startLine: number;
+  endLine: number;
+  kind: "region" | "imports" | "comment";
+}
+
+export type SymbolKind = "function" | "method" | "class" | "struct" | "enum" | "interface" | "trait" | "impl" | "module" | "typeAlias" | "constant";
+
+// line and column of the name, end_line is the last line of the body
+export interface OutlineSymbol {
+  name: string;
+  kind: SymbolKind;
+  line: number;
+  column: number;
+  endLine: number;
+  children: Array<OutlineSymbol>;
+}
+
+export interface ReadFileTokensResult {
+  line_contents_string: Array<string>;
+  line_tokens: Array<Array<TokenSpan>>;
+  line_bracket_depths: Array<Array<BracketDepth>>;
+  palette: Record<string, string>; // token type to color
+  token_styles: Record<string, TokenStyle>;
+  language: string;
+}
+
+// the new DOM, tokens and bracket depths of the lines from start_line on
+export interface RehighlightLinesResult {
+  start_line: number;
+  line_contents_dom: Array<string>;
+  line_tokens: Array<Array<TokenSpan>>;
+  line_bracket_depths: Array<Array<BracketDepth>>;
+}
+
 export interface State {
   currentOpeningFiles: Array<SidebarItem>;
   currentEditingFile: SidebarItem;